@@ -0,0 +1,51 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use crate::generated::programs::FUSIONAMM_ID;
+use solana_pubkey::Pubkey;
+use std::{error::Error, sync::Mutex};
+
+/// The currently selected FusionAMM program id used for PDA derivations and account fetches.
+pub static PROGRAM_ID: Mutex<Pubkey> = Mutex::new(FUSIONAMM_ID);
+
+/// Sets the currently selected FusionAMM program id, e.g. for a program deployed on a localnet or a fork.
+pub fn set_program_id(program_id: Pubkey) -> Result<(), Box<dyn Error>> {
+    *PROGRAM_ID.try_lock()? = program_id;
+    Ok(())
+}
+
+/// Returns the currently selected FusionAMM program id.
+pub fn get_program_id() -> Pubkey {
+    *PROGRAM_ID.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_fusion_pool_address;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_set_program_id() {
+        let token_mint_a = Pubkey::new_unique();
+        let token_mint_b = Pubkey::new_unique();
+        let program_id = Pubkey::new_unique();
+        let default_address = get_fusion_pool_address(&token_mint_a, &token_mint_b, 64).unwrap();
+
+        set_program_id(program_id).unwrap();
+        assert_eq!(get_program_id(), program_id);
+        let address = get_fusion_pool_address(&token_mint_a, &token_mint_b, 64).unwrap();
+        set_program_id(FUSIONAMM_ID).unwrap();
+
+        let tick_spacing_bytes = 64u16.to_le_bytes();
+        let seeds = &[b"fusion_pool", token_mint_a.as_ref(), token_mint_b.as_ref(), tick_spacing_bytes.as_ref()];
+        assert_eq!(address, Pubkey::find_program_address(seeds, &program_id));
+        assert_ne!(address, default_address);
+        assert_eq!(get_program_id(), FUSIONAMM_ID);
+    }
+}
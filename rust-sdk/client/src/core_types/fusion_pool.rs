@@ -9,8 +9,12 @@
 //
 
 use fusionamm_core::FusionPoolFacade;
+#[cfg(feature = "fetch")]
+use fusionamm_core::{get_tick_array_start_tick_index, CoreError, TickArrayFacade, TickArrays, INVALID_TICK_ARRAY_SEQUENCE, TICK_ARRAY_SIZE};
 
 use crate::FusionPool;
+#[cfg(feature = "fetch")]
+use crate::{DecodedAccount, TickArray};
 
 impl From<FusionPool> for FusionPoolFacade {
     fn from(val: FusionPool) -> Self {
@@ -34,3 +38,78 @@ impl From<FusionPool> for FusionPoolFacade {
         }
     }
 }
+
+#[cfg(feature = "fetch")]
+impl DecodedAccount<FusionPool> {
+    /// Converts the fusion pool and its tick arrays into the inputs expected by the swap quote functions.
+    ///
+    /// The tick arrays must be the current tick array and the two tick arrays on each side of it,
+    /// in any order. They are returned sorted by their start tick index.
+    ///
+    /// # Arguments
+    /// - `tick_arrays`: The five tick arrays surrounding the current tick index of the pool.
+    ///
+    /// # Returns
+    /// - `(FusionPoolFacade, TickArrays)`: The pool facade and the ordered tick arrays.
+    pub fn into_swap_inputs(self, tick_arrays: Vec<TickArray>) -> Result<(FusionPoolFacade, TickArrays), CoreError> {
+        let offset = self.data.tick_spacing as i32 * TICK_ARRAY_SIZE as i32;
+        let current_start_index = get_tick_array_start_tick_index(self.data.tick_current_index, self.data.tick_spacing);
+        let expected_start_indexes = [-2, -1, 0, 1, 2].map(|i| current_start_index + i * offset);
+
+        let mut tick_arrays: Vec<TickArrayFacade> = tick_arrays.into_iter().map(|x| x.into()).collect();
+        tick_arrays.sort_by_key(|x| x.start_tick_index);
+
+        let tick_arrays: [TickArrayFacade; 5] = tick_arrays.try_into().map_err(|_| INVALID_TICK_ARRAY_SEQUENCE)?;
+        if tick_arrays.map(|x| x.start_tick_index) != expected_start_indexes {
+            return Err(INVALID_TICK_ARRAY_SEQUENCE);
+        }
+
+        Ok((self.data.into(), tick_arrays.into()))
+    }
+}
+
+#[cfg(all(test, feature = "fetch"))]
+mod tests {
+    use super::*;
+    use fusionamm_core::{sqrt_price_to_tick_index, swap_quote_by_input_token};
+    use solana_account::Account;
+    use solana_pubkey::Pubkey;
+
+    fn test_fusion_pool(sqrt_price: u128) -> DecodedAccount<FusionPool> {
+        let mut fusion_pool = FusionPool::from_bytes(&[0; FusionPool::LEN]).unwrap();
+        fusion_pool.tick_spacing = 2;
+        fusion_pool.fee_rate = 3000;
+        fusion_pool.liquidity = 100000000;
+        fusion_pool.sqrt_price = sqrt_price;
+        fusion_pool.tick_current_index = sqrt_price_to_tick_index(sqrt_price);
+        DecodedAccount {
+            address: Pubkey::new_unique(),
+            account: Account::default(),
+            data: fusion_pool,
+        }
+    }
+
+    fn test_tick_array(start_tick_index: i32) -> TickArray {
+        let mut tick_array = TickArray::from_bytes(&[0; TickArray::LEN]).unwrap();
+        tick_array.start_tick_index = start_tick_index;
+        tick_array
+    }
+
+    #[test]
+    fn test_into_swap_inputs() {
+        let tick_arrays = [176, -352, 0, 352, -176].map(test_tick_array).to_vec();
+        let (fusion_pool, tick_arrays) = test_fusion_pool(1 << 64).into_swap_inputs(tick_arrays).unwrap();
+        let quote = swap_quote_by_input_token(1000, true, 1000, fusion_pool, tick_arrays, None, None).unwrap();
+        assert_eq!(quote.token_in, 1000);
+        assert_eq!(quote.token_est_out, 996);
+        assert_eq!(quote.token_min_out, 896);
+    }
+
+    #[test]
+    fn test_into_swap_inputs_invalid_tick_arrays() {
+        let missing = [-352, -176, 0, 176].map(test_tick_array).to_vec();
+        assert_eq!(test_fusion_pool(1 << 64).into_swap_inputs(missing).err(), Some(INVALID_TICK_ARRAY_SEQUENCE));
+        let shifted = [-176, 0, 176, 352, 528].map(test_tick_array).to_vec();
+        assert_eq!(test_fusion_pool(1 << 64).into_swap_inputs(shifted).err(), Some(INVALID_TICK_ARRAY_SEQUENCE));
+    }
+}
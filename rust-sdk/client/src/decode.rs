@@ -0,0 +1,218 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use crate::generated::instructions::*;
+use borsh::BorshDeserialize;
+use solana_pubkey::Pubkey;
+
+const DISCRIMINATOR_LEN: usize = 8;
+
+/// Generates the `DecodedFusionInstruction` enum and `decode_instruction` from the generated instructions.
+/// The accounts must be listed in the order of the instruction accounts.
+macro_rules! decoded_instructions {
+    ($($name:ident($data:ident $(, $args:ident)?) { $($account:ident),* $(,)? }),* $(,)?) => {
+        /// A FusionAMM instruction decoded from its data and accounts.
+        #[derive(Debug)]
+        #[allow(clippy::large_enum_variant)]
+        pub enum DecodedFusionInstruction {
+            $(
+                $name { accounts: $name $(, args: $args)? },
+            )*
+        }
+
+        /// Decodes a FusionAMM instruction from its data and the addresses of its accounts, e.g. from a confirmed transaction.
+        /// The instruction is matched by the discriminator at the beginning of the data. Remaining accounts are ignored.
+        ///
+        /// Returns `None` if the discriminator is unknown, the args cannot be deserialized or there are not enough accounts.
+        pub fn decode_instruction(data: &[u8], accounts: &[Pubkey]) -> Option<DecodedFusionInstruction> {
+            if data.len() < DISCRIMINATOR_LEN {
+                return None;
+            }
+            let (discriminator, args_data) = data.split_at(DISCRIMINATOR_LEN);
+            $(
+                if $data::try_from_slice(discriminator).ok() == Some($data::new()) {
+                    let mut account_keys = accounts.iter();
+                    return Some(DecodedFusionInstruction::$name {
+                        accounts: $name { $($account: *account_keys.next()?),* },
+                        $(args: $args::deserialize(&mut &args_data[..]).ok()?,)?
+                    });
+                }
+            )*
+            None
+        }
+    };
+}
+
+decoded_instructions! {
+    CloseBundledPosition(CloseBundledPositionInstructionData, CloseBundledPositionInstructionArgs) {
+        bundled_position, position_bundle, position_bundle_token_account, position_bundle_authority, receiver,
+    },
+    CloseLimitOrder(CloseLimitOrderInstructionData) {
+        limit_order_authority, receiver, limit_order, limit_order_mint, limit_order_token_account, token2022_program,
+    },
+    ClosePosition(ClosePositionInstructionData) {
+        position_authority, receiver, position, position_mint, position_token_account, token2022_program,
+    },
+    CollectFees(CollectFeesInstructionData, CollectFeesInstructionArgs) {
+        fusion_pool, position_authority, position, position_token_account, token_mint_a, token_mint_b, token_owner_account_a, token_owner_account_b, token_vault_a, token_vault_b, token_program_a, token_program_b, memo_program,
+    },
+    CollectProtocolFees(CollectProtocolFeesInstructionData, CollectProtocolFeesInstructionArgs) {
+        fusion_pools_config, fusion_pool, collect_protocol_fees_authority, token_mint_a, token_mint_b, token_vault_a, token_vault_b, token_destination_a, token_destination_b, token_program_a, token_program_b, memo_program,
+    },
+    DecreaseLimitOrder(DecreaseLimitOrderInstructionData, DecreaseLimitOrderInstructionArgs) {
+        limit_order_authority, fusion_pool, limit_order, limit_order_token_account, token_mint_a, token_mint_b, token_owner_account_a, token_owner_account_b, token_vault_a, token_vault_b, tick_array, token_program_a, token_program_b, memo_program,
+    },
+    DecreaseLiquidity(DecreaseLiquidityInstructionData, DecreaseLiquidityInstructionArgs) {
+        fusion_pool, token_program_a, token_program_b, memo_program, position_authority, position, position_token_account, token_mint_a, token_mint_b, token_owner_account_a, token_owner_account_b, token_vault_a, token_vault_b, tick_array_lower, tick_array_upper,
+    },
+    DeletePositionBundle(DeletePositionBundleInstructionData) {
+        position_bundle, position_bundle_mint, position_bundle_token_account, position_bundle_owner, receiver, token_program,
+    },
+    DeleteTokenBadge(DeleteTokenBadgeInstructionData) {
+        fusion_pools_config, token_badge_authority, token_mint, token_badge, receiver,
+    },
+    IncreaseLimitOrder(IncreaseLimitOrderInstructionData, IncreaseLimitOrderInstructionArgs) {
+        limit_order_authority, fusion_pool, limit_order, limit_order_token_account, token_mint, token_owner_account, token_vault, tick_array, token_program, memo_program,
+    },
+    IncreaseLiquidity(IncreaseLiquidityInstructionData, IncreaseLiquidityInstructionArgs) {
+        fusion_pool, token_program_a, token_program_b, memo_program, position_authority, position, position_token_account, token_mint_a, token_mint_b, token_owner_account_a, token_owner_account_b, token_vault_a, token_vault_b, tick_array_lower, tick_array_upper,
+    },
+    InitializeConfig(InitializeConfigInstructionData, InitializeConfigInstructionArgs) {
+        fusion_pools_config, funder, system_program,
+    },
+    InitializePool(InitializePoolInstructionData, InitializePoolInstructionArgs) {
+        fusion_pools_config, token_mint_a, token_mint_b, token_badge_a, token_badge_b, funder, fusion_pool, token_vault_a, token_vault_b, token_program_a, token_program_b, system_program, rent,
+    },
+    InitializePositionBundle(InitializePositionBundleInstructionData) {
+        position_bundle, position_bundle_mint, position_bundle_token_account, position_bundle_owner, funder, token_program, system_program, rent, associated_token_program,
+    },
+    InitializePositionBundleWithMetadata(InitializePositionBundleWithMetadataInstructionData) {
+        position_bundle, position_bundle_mint, position_bundle_metadata, position_bundle_token_account, position_bundle_owner, funder, metadata_update_auth, token_program, system_program, rent, associated_token_program, metadata_program,
+    },
+    InitializeTickArray(InitializeTickArrayInstructionData, InitializeTickArrayInstructionArgs) {
+        fusion_pool, funder, tick_array, system_program,
+    },
+    InitializeTokenBadge(InitializeTokenBadgeInstructionData) {
+        fusion_pools_config, token_badge_authority, token_mint, token_badge, funder, system_program,
+    },
+    OpenBundledPosition(OpenBundledPositionInstructionData, OpenBundledPositionInstructionArgs) {
+        bundled_position, position_bundle, position_bundle_token_account, position_bundle_authority, fusion_pool, funder, system_program, rent,
+    },
+    OpenLimitOrder(OpenLimitOrderInstructionData, OpenLimitOrderInstructionArgs) {
+        funder, owner, limit_order, limit_order_mint, limit_order_token_account, fusion_pool, token2022_program, system_program, associated_token_program, metadata_update_auth,
+    },
+    OpenPosition(OpenPositionInstructionData, OpenPositionInstructionArgs) {
+        funder, owner, position, position_mint, position_token_account, fusion_pool, token2022_program, system_program, associated_token_program, metadata_update_auth,
+    },
+    ResetPoolPrice(ResetPoolPriceInstructionData, ResetPoolPriceInstructionArgs) {
+        fee_authority, fusion_pools_config, token_vault_a, token_vault_b, fusion_pool,
+    },
+    SetClpRewardRate(SetClpRewardRateInstructionData, SetClpRewardRateInstructionArgs) {
+        fusion_pools_config, fusion_pool, fee_authority,
+    },
+    SetCollectProtocolFeesAuthority(SetCollectProtocolFeesAuthorityInstructionData) {
+        fusion_pools_config, collect_protocol_fees_authority, new_collect_protocol_fees_authority,
+    },
+    SetDefaultClpRewardRate(SetDefaultClpRewardRateInstructionData, SetDefaultClpRewardRateInstructionArgs) {
+        fusion_pools_config, fee_authority,
+    },
+    SetDefaultOrderProtocolFeeRate(SetDefaultOrderProtocolFeeRateInstructionData, SetDefaultOrderProtocolFeeRateInstructionArgs) {
+        fusion_pools_config, fee_authority,
+    },
+    SetDefaultProtocolFeeRate(SetDefaultProtocolFeeRateInstructionData, SetDefaultProtocolFeeRateInstructionArgs) {
+        fusion_pools_config, fee_authority,
+    },
+    SetFeeAuthority(SetFeeAuthorityInstructionData) {
+        fusion_pools_config, fee_authority, new_fee_authority,
+    },
+    SetFeeRate(SetFeeRateInstructionData, SetFeeRateInstructionArgs) {
+        fusion_pools_config, fusion_pool, fee_authority,
+    },
+    SetOrderProtocolFeeRate(SetOrderProtocolFeeRateInstructionData, SetOrderProtocolFeeRateInstructionArgs) {
+        fusion_pools_config, fusion_pool, fee_authority,
+    },
+    SetPositionRange(SetPositionRangeInstructionData, SetPositionRangeInstructionArgs) {
+        position_authority, position, position_token_account, fusion_pool,
+    },
+    SetProtocolFeeRate(SetProtocolFeeRateInstructionData, SetProtocolFeeRateInstructionArgs) {
+        fusion_pools_config, fusion_pool, fee_authority,
+    },
+    SetTokenBadgeAuthority(SetTokenBadgeAuthorityInstructionData) {
+        fusion_pools_config, fee_authority, new_token_badge_authority,
+    },
+    Swap(SwapInstructionData, SwapInstructionArgs) {
+        token_program_a, token_program_b, memo_program, token_authority, fusion_pool, token_mint_a, token_mint_b, token_owner_account_a, token_owner_account_b, token_vault_a, token_vault_b, tick_array0, tick_array1, tick_array2,
+    },
+    TwoHopSwap(TwoHopSwapInstructionData, TwoHopSwapInstructionArgs) {
+        fusion_pool_one, fusion_pool_two, token_mint_input, token_mint_intermediate, token_mint_output, token_program_input, token_program_intermediate, token_program_output, token_owner_account_input, token_vault_one_input, token_vault_one_intermediate, token_vault_two_intermediate, token_vault_two_output, token_owner_account_output, token_authority, tick_array_one0, tick_array_one1, tick_array_one2, tick_array_two0, tick_array_two1, tick_array_two2, memo_program,
+    },
+    UpdateFees(UpdateFeesInstructionData) {
+        fusion_pool, position, tick_array_lower, tick_array_upper,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{get_limit_order_address, OpenLimitOrder, OpenLimitOrderInstructionArgs, SetFeeAuthority};
+
+    #[test]
+    fn test_decode_open_limit_order() {
+        let limit_order_mint = Pubkey::new_unique();
+        let instruction = OpenLimitOrder {
+            funder: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            limit_order: get_limit_order_address(&limit_order_mint).unwrap().0,
+            limit_order_mint,
+            limit_order_token_account: Pubkey::new_unique(),
+            fusion_pool: Pubkey::new_unique(),
+            token2022_program: Pubkey::new_unique(),
+            system_program: Pubkey::new_unique(),
+            associated_token_program: Pubkey::new_unique(),
+            metadata_update_auth: Pubkey::new_unique(),
+        }
+        .instruction(OpenLimitOrderInstructionArgs {
+            tick_index: -128,
+            a_to_b: true,
+            with_token_metadata_extension: false,
+        });
+        let accounts: Vec<Pubkey> = instruction.accounts.iter().map(|meta| meta.pubkey).collect();
+
+        match decode_instruction(&instruction.data, &accounts) {
+            Some(DecodedFusionInstruction::OpenLimitOrder {
+                accounts: decoded_accounts,
+                args,
+            }) => {
+                assert_eq!(args.tick_index, -128);
+                assert!(args.a_to_b);
+                assert!(!args.with_token_metadata_extension);
+                assert_eq!(decoded_accounts.limit_order_mint, limit_order_mint);
+                assert_eq!(decoded_accounts.metadata_update_auth, accounts[9]);
+            }
+            decoded => panic!("Unexpected decoded instruction: {:?}", decoded),
+        }
+
+        // Not enough accounts or truncated args.
+        assert!(decode_instruction(&instruction.data, &accounts[..9]).is_none());
+        assert!(decode_instruction(&instruction.data[..10], &accounts).is_none());
+    }
+
+    #[test]
+    fn test_decode_instruction_without_args() {
+        let accounts: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let instruction = SetFeeAuthority {
+            fusion_pools_config: accounts[0],
+            fee_authority: accounts[1],
+            new_fee_authority: accounts[2],
+        }
+        .instruction();
+        assert!(matches!(decode_instruction(&instruction.data, &accounts), Some(DecodedFusionInstruction::SetFeeAuthority { .. })));
+
+        assert!(decode_instruction(&[0; 8], &accounts).is_none());
+        assert!(decode_instruction(&[], &accounts).is_none());
+    }
+}
@@ -56,5 +56,5 @@ pub async fn fetch_all_fusion_pool_with_filter(
 ) -> Result<Vec<DecodedAccount<FusionPool>>, Box<dyn Error>> {
     let mut filters: Vec<RpcFilterType> = filters.into_iter().map(|filter| filter.into()).collect();
     filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, FUSION_POOL_DISCRIMINATOR)));
-    fetch_decoded_program_accounts(rpc, filters).await
+    fetch_decoded_program_accounts(rpc, FUSION_POOL_DISCRIMINATOR, filters).await
 }
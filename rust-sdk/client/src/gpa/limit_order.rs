@@ -43,5 +43,5 @@ pub async fn fetch_all_limit_order_with_filter(
 ) -> Result<Vec<DecodedAccount<LimitOrder>>, Box<dyn Error>> {
     let mut filters: Vec<RpcFilterType> = filters.into_iter().map(|filter| filter.into()).collect();
     filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, LIMIT_ORDER_DISCRIMINATOR)));
-    fetch_decoded_program_accounts(rpc, filters).await
+    fetch_decoded_program_accounts(rpc, LIMIT_ORDER_DISCRIMINATOR, filters).await
 }
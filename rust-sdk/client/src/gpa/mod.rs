@@ -29,3 +29,4 @@ pub use position_bundle::*;
 pub use tick_array::*;
 pub use token_badge::*;
 pub(crate) use utils::*;
+pub use utils::{decode_any_account, AccountKind, FusionAccount, FusionAccountType};
@@ -48,5 +48,5 @@ impl From<PositionFilter> for RpcFilterType {
 pub async fn fetch_all_position_with_filter(rpc: &RpcClient, filters: Vec<PositionFilter>) -> Result<Vec<DecodedAccount<Position>>, Box<dyn Error>> {
     let mut filters: Vec<RpcFilterType> = filters.into_iter().map(|filter| filter.into()).collect();
     filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, POSITION_DISCRIMINATOR)));
-    fetch_decoded_program_accounts(rpc, filters).await
+    fetch_decoded_program_accounts(rpc, POSITION_DISCRIMINATOR, filters).await
 }
@@ -40,5 +40,5 @@ pub async fn fetch_all_position_bundle_with_filter(
 ) -> Result<Vec<DecodedAccount<PositionBundle>>, Box<dyn Error>> {
     let mut filters: Vec<RpcFilterType> = filters.into_iter().map(|filter| filter.into()).collect();
     filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, POSITION_BUNDLE_DISCRIMINATOR)));
-    fetch_decoded_program_accounts(rpc, filters).await
+    fetch_decoded_program_accounts(rpc, POSITION_BUNDLE_DISCRIMINATOR, filters).await
 }
@@ -40,5 +40,5 @@ pub async fn fetch_all_token_badge_with_filter(
 ) -> Result<Vec<DecodedAccount<TokenBadge>>, Box<dyn Error>> {
     let mut filters: Vec<RpcFilterType> = filters.into_iter().map(|filter| filter.into()).collect();
     filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, TOKEN_BADGE_DISCRIMINATOR)));
-    fetch_decoded_program_accounts(rpc, filters).await
+    fetch_decoded_program_accounts(rpc, TOKEN_BADGE_DISCRIMINATOR, filters).await
 }
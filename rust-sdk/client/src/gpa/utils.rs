@@ -10,7 +10,11 @@
 
 use std::error::Error;
 
-use crate::{DecodedAccount, FUSIONAMM_ID};
+use crate::{
+    get_program_id, DecodedAccount, FusionPool, FusionPoolsConfig, LimitOrder, Position, PositionBundle, TickArray, TokenBadge,
+    FUSION_POOLS_CONFIG_DISCRIMINATOR, FUSION_POOL_DISCRIMINATOR, LIMIT_ORDER_DISCRIMINATOR, POSITION_BUNDLE_DISCRIMINATOR, POSITION_DISCRIMINATOR,
+    TICK_ARRAY_DISCRIMINATOR, TOKEN_BADGE_DISCRIMINATOR,
+};
 use borsh::BorshDeserialize;
 use solana_account_decoder::UiAccountEncoding;
 use solana_client::{
@@ -49,17 +53,137 @@ pub(crate) fn rpc_program_accounts_config(filters: Vec<RpcFilterType>) -> RpcPro
     }
 }
 
+/// Deserializes account data after verifying that it starts with the expected discriminator.
+pub(crate) fn decode_checked<T: BorshDeserialize>(data: &[u8], discriminator: &[u8]) -> Result<T, Box<dyn Error>> {
+    let account_discriminator = data.get(..discriminator.len()).unwrap_or(data);
+    if account_discriminator != discriminator {
+        return Err(format!("Discriminator mismatch: expected {:?}, got {:?}", discriminator, account_discriminator).into());
+    }
+    let mut data = data;
+    Ok(T::deserialize(&mut data)?)
+}
+
+/// Any account owned by the FusionAMM program.
+#[derive(Debug, Clone)]
+pub enum FusionAccount {
+    FusionPool(FusionPool),
+    Position(Position),
+    LimitOrder(LimitOrder),
+    TickArray(Box<TickArray>),
+    TokenBadge(TokenBadge),
+    PositionBundle(PositionBundle),
+    FusionPoolsConfig(FusionPoolsConfig),
+}
+
+/// The type of an account owned by the FusionAMM program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccountKind {
+    FusionPool,
+    Position,
+    LimitOrder,
+    TickArray,
+    TokenBadge,
+    PositionBundle,
+    FusionPoolsConfig,
+}
+
+impl AccountKind {
+    /// Returns the account kind identified by the leading discriminator of the account data.
+    pub fn from_discriminator(discriminator: &[u8]) -> Option<Self> {
+        match discriminator {
+            FUSION_POOL_DISCRIMINATOR => Some(AccountKind::FusionPool),
+            POSITION_DISCRIMINATOR => Some(AccountKind::Position),
+            LIMIT_ORDER_DISCRIMINATOR => Some(AccountKind::LimitOrder),
+            TICK_ARRAY_DISCRIMINATOR => Some(AccountKind::TickArray),
+            TOKEN_BADGE_DISCRIMINATOR => Some(AccountKind::TokenBadge),
+            POSITION_BUNDLE_DISCRIMINATOR => Some(AccountKind::PositionBundle),
+            FUSION_POOLS_CONFIG_DISCRIMINATOR => Some(AccountKind::FusionPoolsConfig),
+            _ => None,
+        }
+    }
+}
+
+/// An account type of the FusionAMM program with a known `AccountKind`.
+pub trait FusionAccountType {
+    const ACCOUNT_KIND: AccountKind;
+}
+
+impl FusionAccountType for FusionPool {
+    const ACCOUNT_KIND: AccountKind = AccountKind::FusionPool;
+}
+
+impl FusionAccountType for Position {
+    const ACCOUNT_KIND: AccountKind = AccountKind::Position;
+}
+
+impl FusionAccountType for LimitOrder {
+    const ACCOUNT_KIND: AccountKind = AccountKind::LimitOrder;
+}
+
+impl FusionAccountType for TickArray {
+    const ACCOUNT_KIND: AccountKind = AccountKind::TickArray;
+}
+
+impl FusionAccountType for TokenBadge {
+    const ACCOUNT_KIND: AccountKind = AccountKind::TokenBadge;
+}
+
+impl FusionAccountType for PositionBundle {
+    const ACCOUNT_KIND: AccountKind = AccountKind::PositionBundle;
+}
+
+impl FusionAccountType for FusionPoolsConfig {
+    const ACCOUNT_KIND: AccountKind = AccountKind::FusionPoolsConfig;
+}
+
+impl<T: FusionAccountType> DecodedAccount<T> {
+    /// Returns the kind of the decoded account, so that accounts of different types can be handled together.
+    pub fn account_type(&self) -> AccountKind {
+        T::ACCOUNT_KIND
+    }
+}
+
+impl FusionAccount {
+    /// Returns the kind of the account.
+    pub fn account_type(&self) -> AccountKind {
+        match self {
+            FusionAccount::FusionPool(_) => AccountKind::FusionPool,
+            FusionAccount::Position(_) => AccountKind::Position,
+            FusionAccount::LimitOrder(_) => AccountKind::LimitOrder,
+            FusionAccount::TickArray(_) => AccountKind::TickArray,
+            FusionAccount::TokenBadge(_) => AccountKind::TokenBadge,
+            FusionAccount::PositionBundle(_) => AccountKind::PositionBundle,
+            FusionAccount::FusionPoolsConfig(_) => AccountKind::FusionPoolsConfig,
+        }
+    }
+}
+
+/// Deserializes the data of any FusionAMM account, selecting the account type by its leading discriminator.
+pub fn decode_any_account(data: &[u8]) -> Result<FusionAccount, Box<dyn Error>> {
+    let discriminator = data.get(..8).ok_or("Account data is too short to contain a discriminator")?;
+    match discriminator {
+        FUSION_POOL_DISCRIMINATOR => Ok(FusionAccount::FusionPool(decode_checked(data, discriminator)?)),
+        POSITION_DISCRIMINATOR => Ok(FusionAccount::Position(decode_checked(data, discriminator)?)),
+        LIMIT_ORDER_DISCRIMINATOR => Ok(FusionAccount::LimitOrder(decode_checked(data, discriminator)?)),
+        TICK_ARRAY_DISCRIMINATOR => Ok(FusionAccount::TickArray(Box::new(decode_checked(data, discriminator)?))),
+        TOKEN_BADGE_DISCRIMINATOR => Ok(FusionAccount::TokenBadge(decode_checked(data, discriminator)?)),
+        POSITION_BUNDLE_DISCRIMINATOR => Ok(FusionAccount::PositionBundle(decode_checked(data, discriminator)?)),
+        FUSION_POOLS_CONFIG_DISCRIMINATOR => Ok(FusionAccount::FusionPoolsConfig(decode_checked(data, discriminator)?)),
+        _ => Err(format!("Unknown account discriminator: {:?}", discriminator).into()),
+    }
+}
+
 pub(crate) async fn fetch_decoded_program_accounts<T: BorshDeserialize>(
     rpc: &RpcClient,
+    discriminator: &[u8],
     filters: Vec<RpcFilterType>,
 ) -> Result<Vec<DecodedAccount<T>>, Box<dyn Error>> {
     let accounts = rpc
-        .get_program_accounts_with_config(&FUSIONAMM_ID, rpc_program_accounts_config(filters))
+        .get_program_accounts_with_config(&get_program_id(), rpc_program_accounts_config(filters))
         .await?;
     let mut decoded_accounts: Vec<DecodedAccount<T>> = Vec::new();
     for (address, account) in accounts {
-        let mut data = account.data.as_slice();
-        let decoded = T::deserialize(&mut data)?;
+        let decoded = decode_checked(&account.data, discriminator)?;
         decoded_accounts.push(DecodedAccount {
             address,
             account: account.clone(),
@@ -68,3 +192,103 @@ pub(crate) async fn fetch_decoded_program_accounts<T: BorshDeserialize>(
     }
     Ok(decoded_accounts)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_account::Account;
+    use solana_pubkey::Pubkey;
+
+    fn account_data(len: usize, discriminator: &[u8]) -> Vec<u8> {
+        let mut data = vec![0; len];
+        data[..8].copy_from_slice(discriminator);
+        data
+    }
+
+    #[test]
+    fn test_decode_checked() {
+        let mut data = vec![0; TickArray::LEN];
+        data[..8].copy_from_slice(TICK_ARRAY_DISCRIMINATOR);
+        let tick_array: TickArray = decode_checked(&data, TICK_ARRAY_DISCRIMINATOR).unwrap();
+        assert_eq!(tick_array.discriminator, TICK_ARRAY_DISCRIMINATOR);
+    }
+
+    #[test]
+    fn test_decode_checked_discriminator_mismatch() {
+        let mut data = vec![0; TickArray::LEN];
+        data[..8].copy_from_slice(TICK_ARRAY_DISCRIMINATOR);
+        let result = decode_checked::<TickArray>(&data, FUSION_POOL_DISCRIMINATOR);
+        assert!(result.unwrap_err().to_string().starts_with("Discriminator mismatch"));
+
+        let result = decode_checked::<TickArray>(&data[..4], TICK_ARRAY_DISCRIMINATOR);
+        assert!(result.unwrap_err().to_string().starts_with("Discriminator mismatch"));
+    }
+
+    #[test]
+    fn test_decode_any_account() {
+        let data = account_data(FusionPool::LEN, FUSION_POOL_DISCRIMINATOR);
+        assert!(matches!(decode_any_account(&data).unwrap(), FusionAccount::FusionPool(pool) if pool.discriminator == FUSION_POOL_DISCRIMINATOR));
+
+        let data = account_data(Position::LEN, POSITION_DISCRIMINATOR);
+        assert!(matches!(decode_any_account(&data).unwrap(), FusionAccount::Position(position) if position.discriminator == POSITION_DISCRIMINATOR));
+
+        let data = account_data(TickArray::LEN, TICK_ARRAY_DISCRIMINATOR);
+        assert!(matches!(decode_any_account(&data).unwrap(), FusionAccount::TickArray(_)));
+    }
+
+    fn decoded_account<T: BorshDeserialize>(len: usize, discriminator: &[u8]) -> DecodedAccount<T> {
+        DecodedAccount {
+            address: Pubkey::new_unique(),
+            account: Account::default(),
+            data: decode_checked(&account_data(len, discriminator), discriminator).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_account_type() {
+        let pools: Vec<DecodedAccount<FusionPool>> = (0..2).map(|_| decoded_account(FusionPool::LEN, FUSION_POOL_DISCRIMINATOR)).collect();
+        let positions: Vec<DecodedAccount<Position>> = (0..3).map(|_| decoded_account(Position::LEN, POSITION_DISCRIMINATOR)).collect();
+
+        let accounts: Vec<(AccountKind, Pubkey)> = pools
+            .iter()
+            .map(|pool| (pool.account_type(), pool.address))
+            .chain(positions.iter().map(|position| (position.account_type(), position.address)))
+            .collect();
+
+        let pool_addresses: Vec<Pubkey> = accounts
+            .iter()
+            .filter(|(kind, _)| *kind == AccountKind::FusionPool)
+            .map(|(_, address)| *address)
+            .collect();
+        assert_eq!(pool_addresses, pools.iter().map(|pool| pool.address).collect::<Vec<_>>());
+
+        let position_addresses: Vec<Pubkey> = accounts
+            .iter()
+            .filter(|(kind, _)| *kind == AccountKind::Position)
+            .map(|(_, address)| *address)
+            .collect();
+        assert_eq!(position_addresses, positions.iter().map(|position| position.address).collect::<Vec<_>>());
+
+        assert!(!accounts.iter().any(|(kind, _)| *kind == AccountKind::TickArray));
+    }
+
+    #[test]
+    fn test_account_kind_from_discriminator() {
+        assert_eq!(AccountKind::from_discriminator(FUSION_POOL_DISCRIMINATOR), Some(AccountKind::FusionPool));
+        assert_eq!(AccountKind::from_discriminator(POSITION_DISCRIMINATOR), Some(AccountKind::Position));
+        assert_eq!(AccountKind::from_discriminator(&[0; 8]), None);
+
+        let data = account_data(TickArray::LEN, TICK_ARRAY_DISCRIMINATOR);
+        assert_eq!(decode_any_account(&data).unwrap().account_type(), AccountKind::TickArray);
+    }
+
+    #[test]
+    fn test_decode_any_account_unknown_discriminator() {
+        let data = account_data(FusionPool::LEN, &[0; 8]);
+        assert!(decode_any_account(&data)
+            .unwrap_err()
+            .to_string()
+            .starts_with("Unknown account discriminator"));
+        assert!(decode_any_account(&data[..4]).is_err());
+    }
+}
@@ -11,7 +11,9 @@
 #[rustfmt::skip]
 mod generated;
 
+mod config;
 mod consts;
+mod decode;
 mod pda;
 
 #[cfg(feature = "fetch")]
@@ -33,7 +35,9 @@ pub use generated::shared::*;
 #[cfg(feature = "fetch")]
 pub(crate) use generated::*;
 
+pub use config::*;
 pub use consts::*;
+pub use decode::*;
 pub use pda::*;
 
 #[cfg(feature = "fetch")]
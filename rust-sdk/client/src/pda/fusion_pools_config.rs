@@ -8,11 +8,11 @@
 // See the LICENSE file in the project root for license information.
 //
 
-use crate::generated::programs::FUSIONAMM_ID;
+use crate::get_program_id;
 use solana_program::program_error::ProgramError;
 use solana_pubkey::Pubkey;
 
 pub fn get_fusion_pools_config_address() -> Result<(Pubkey, u8), ProgramError> {
     let seeds = &[b"config".as_ref()];
-    Pubkey::try_find_program_address(seeds, &FUSIONAMM_ID).ok_or(ProgramError::InvalidSeeds)
+    Pubkey::try_find_program_address(seeds, &get_program_id()).ok_or(ProgramError::InvalidSeeds)
 }
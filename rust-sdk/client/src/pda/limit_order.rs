@@ -8,11 +8,34 @@
 // See the LICENSE file in the project root for license information.
 //
 
-use crate::generated::programs::FUSIONAMM_ID;
+use crate::get_program_id;
 use solana_program::program_error::ProgramError;
 use solana_pubkey::Pubkey;
 
 pub fn get_limit_order_address(limit_order_mint: &Pubkey) -> Result<(Pubkey, u8), ProgramError> {
     let seeds = &[b"limit_order", limit_order_mint.as_ref()];
-    Pubkey::try_find_program_address(seeds, &FUSIONAMM_ID).ok_or(ProgramError::InvalidSeeds)
+    Pubkey::try_find_program_address(seeds, &get_program_id()).ok_or(ProgramError::InvalidSeeds)
+}
+
+/// Derives the limit order addresses for several limit order mints.
+/// Returns the first derivation error encountered.
+pub fn get_limit_order_addresses(limit_order_mints: &[Pubkey]) -> Result<Vec<(Pubkey, u8)>, ProgramError> {
+    limit_order_mints.iter().map(get_limit_order_address).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_get_limit_order_addresses() {
+        let limit_order_mints: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let addresses = get_limit_order_addresses(&limit_order_mints).unwrap();
+        assert_eq!(addresses.len(), limit_order_mints.len());
+        for (address, limit_order_mint) in addresses.iter().zip(&limit_order_mints) {
+            assert_eq!(*address, get_limit_order_address(limit_order_mint).unwrap());
+        }
+    }
 }
@@ -8,11 +8,34 @@
 // See the LICENSE file in the project root for license information.
 //
 
-use crate::generated::programs::FUSIONAMM_ID;
+use crate::get_program_id;
 use solana_program::program_error::ProgramError;
 use solana_pubkey::Pubkey;
 
 pub fn get_position_address(position_mint: &Pubkey) -> Result<(Pubkey, u8), ProgramError> {
     let seeds = &[b"position", position_mint.as_ref()];
-    Pubkey::try_find_program_address(seeds, &FUSIONAMM_ID).ok_or(ProgramError::InvalidSeeds)
+    Pubkey::try_find_program_address(seeds, &get_program_id()).ok_or(ProgramError::InvalidSeeds)
+}
+
+/// Derives the position addresses for several position mints.
+/// Returns the first derivation error encountered.
+pub fn get_position_addresses(position_mints: &[Pubkey]) -> Result<Vec<(Pubkey, u8)>, ProgramError> {
+    position_mints.iter().map(get_position_address).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_get_position_addresses() {
+        let position_mints: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let addresses = get_position_addresses(&position_mints).unwrap();
+        assert_eq!(addresses.len(), position_mints.len());
+        for (address, position_mint) in addresses.iter().zip(&position_mints) {
+            assert_eq!(*address, get_position_address(position_mint).unwrap());
+        }
+    }
 }
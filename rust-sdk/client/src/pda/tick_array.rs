@@ -8,12 +8,39 @@
 // See the LICENSE file in the project root for license information.
 //
 
-use crate::generated::programs::FUSIONAMM_ID;
+use crate::get_program_id;
 use solana_program::program_error::ProgramError;
 use solana_pubkey::Pubkey;
 
 pub fn get_tick_array_address(fusion_pool: &Pubkey, start_tick_index: i32) -> Result<(Pubkey, u8), ProgramError> {
     let start_tick_index_str = start_tick_index.to_string();
     let seeds = &[b"tick_array", fusion_pool.as_ref(), start_tick_index_str.as_bytes()];
-    Pubkey::try_find_program_address(seeds, &FUSIONAMM_ID).ok_or(ProgramError::InvalidSeeds)
+    Pubkey::try_find_program_address(seeds, &get_program_id()).ok_or(ProgramError::InvalidSeeds)
+}
+
+/// Derives the tick array addresses of a fusion pool for several start tick indexes.
+/// Returns the first derivation error encountered.
+pub fn get_tick_array_addresses(fusion_pool: &Pubkey, start_tick_indexes: &[i32]) -> Result<Vec<(Pubkey, u8)>, ProgramError> {
+    start_tick_indexes
+        .iter()
+        .map(|start_tick_index| get_tick_array_address(fusion_pool, *start_tick_index))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_get_tick_array_addresses() {
+        let fusion_pool = Pubkey::new_unique();
+        let start_tick_indexes = [-5632, -2816, 0, 2816, 5632];
+        let addresses = get_tick_array_addresses(&fusion_pool, &start_tick_indexes).unwrap();
+        assert_eq!(addresses.len(), start_tick_indexes.len());
+        for (address, start_tick_index) in addresses.iter().zip(start_tick_indexes) {
+            assert_eq!(*address, get_tick_array_address(&fusion_pool, start_tick_index).unwrap());
+        }
+    }
 }
@@ -65,3 +65,24 @@ pub const INVALID_TICK_ARRAY_SEQUENCE: CoreError = "Invalid tick array sequence"
 
 #[cfg_attr(feature = "wasm", wasm_expose)]
 pub const LIMIT_ORDER_AND_POOL_ARE_OUT_OF_SYNC: CoreError = "Limit order and pool/ticks data are out of sync";
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const INSUFFICIENT_LIQUIDITY: CoreError = "Insufficient liquidity";
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const SWAP_OUTPUT_BELOW_MINIMUM: CoreError = "Swap output is below the minimum";
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const SWAP_INPUT_ABOVE_MAXIMUM: CoreError = "Swap input is above the maximum";
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const INVALID_FEE_RATE: CoreError = "Invalid fee rate";
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const TICK_INDEX_INCONSISTENT_WITH_SQRT_PRICE: CoreError = "Tick index is inconsistent with sqrt price";
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const INVALID_TICK_SPACING: CoreError = "Invalid tick spacing";
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const INVALID_ORDER_BOOK_PARAMETERS: CoreError = "Invalid order book parameters";
@@ -16,6 +16,9 @@ use fusionamm_macros::wasm_expose;
 #[cfg_attr(feature = "wasm", wasm_expose)]
 pub const FEE_RATE_MUL_VALUE: u32 = 1_000_000;
 
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const MAX_FEE_RATE: u16 = 60_000;
+
 #[cfg_attr(feature = "wasm", wasm_expose)]
 pub const MAX_PROTOCOL_FEE_RATE: u16 = 2_500;
 
@@ -28,6 +31,10 @@ pub const MAX_CLP_REWARD_RATE: u16 = 10_000;
 #[cfg_attr(feature = "wasm", wasm_expose)]
 pub const PROTOCOL_FEE_RATE_MUL_VALUE: u16 = 10_000;
 
+/// The maximum number of tick arrays that can be passed to a swap.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const MAX_SWAP_TICK_ARRAYS: usize = 6;
+
 // TODO: WASM export (which doesn't work with u128 yet)
 pub const MIN_SQRT_PRICE: u128 = 4295048016;
 pub const MAX_SQRT_PRICE: u128 = 79226673515401279992447579055;
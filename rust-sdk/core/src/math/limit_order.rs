@@ -5,8 +5,14 @@
 // See the LICENSE file in the project root for license information.
 //
 
+#[cfg(feature = "wasm")]
+use fusionamm_macros::wasm_expose;
+
 use crate::{div_by_sqrt_price_squared, mul_by_sqrt_price_squared, CoreError};
 
+#[cfg(feature = "floats")]
+use crate::{get_initializable_tick_index, price_to_sqrt_price, sqrt_price_to_tick_index, tick_index_to_sqrt_price};
+
 /// Computes the limit order output amount by input amount.
 /// ### Parameters
 /// - `input_amount` - Input amount.
@@ -21,3 +27,62 @@ pub fn get_limit_order_output_amount(input_amount: u64, a_to_b_order: bool, sqrt
     };
     Ok(output_amount)
 }
+
+/// Computes the initializable tick index of a limit order for the desired price.
+/// The tick is rounded so that the limit order price is not worse than the requested one.
+/// IMPORTANT: floating point operations can reduce the precision of the result.
+///
+/// ### Parameters
+/// - `price` - The desired price expressed as the output token amount per one input token.
+/// - `a_to_b` - The limit order direction.
+/// - `decimals_a` - The number of decimals of the token A.
+/// - `decimals_b` - The number of decimals of the token B.
+/// - `tick_spacing` - The tick spacing of the pool.
+#[cfg(feature = "floats")]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn limit_order_tick_index_for_price(price: f64, a_to_b: bool, decimals_a: u8, decimals_b: u8, tick_spacing: u16) -> i32 {
+    // The pool price is always expressed in token B per token A.
+    let pool_price = if a_to_b { price } else { 1.0 / price };
+    let sqrt_price: u128 = price_to_sqrt_price(pool_price, decimals_a, decimals_b).into();
+    let tick_index = sqrt_price_to_tick_index(sqrt_price.into());
+
+    if a_to_b {
+        // A higher price is better for the a_to_b order.
+        let tick_sqrt_price: u128 = tick_index_to_sqrt_price(tick_index).into();
+        let tick_index = if tick_sqrt_price < sqrt_price { tick_index + 1 } else { tick_index };
+        get_initializable_tick_index(tick_index, tick_spacing, Some(true))
+    } else {
+        // A lower price is better for the b_to_a order.
+        get_initializable_tick_index(tick_index, tick_spacing, Some(false))
+    }
+}
+
+#[cfg(all(test, feature = "floats", not(feature = "wasm")))]
+mod tests {
+    use super::*;
+    use crate::{price_to_tick_index, tick_index_to_price};
+
+    #[test]
+    fn test_limit_order_tick_index_for_price_a_to_b() {
+        let tick_index = limit_order_tick_index_for_price(1.05, true, 6, 6, 64);
+        assert_eq!(tick_index, get_initializable_tick_index(price_to_tick_index(1.05, 6, 6) + 1, 64, Some(true)));
+        assert_eq!(tick_index, 512);
+        assert!(tick_index_to_price(tick_index, 6, 6) >= 1.05);
+
+        let tick_index = limit_order_tick_index_for_price(0.0105, true, 9, 6, 1);
+        assert_eq!(tick_index, price_to_tick_index(0.0105, 9, 6) + 1);
+        assert!(tick_index_to_price(tick_index, 9, 6) >= 0.0105);
+    }
+
+    #[test]
+    fn test_limit_order_tick_index_for_price_b_to_a() {
+        let tick_index = limit_order_tick_index_for_price(0.95, false, 6, 6, 64);
+        assert_eq!(tick_index, get_initializable_tick_index(price_to_tick_index(1.0 / 0.95, 6, 6), 64, Some(false)));
+        assert_eq!(tick_index, 512);
+        assert!(1.0 / tick_index_to_price(tick_index, 6, 6) >= 0.95);
+
+        let tick_index = limit_order_tick_index_for_price(95.0, false, 9, 6, 1);
+        assert_eq!(tick_index, price_to_tick_index(1.0 / 95.0, 9, 6));
+        assert!(1.0 / tick_index_to_price(tick_index, 9, 6) >= 95.0);
+    }
+}
@@ -0,0 +1,202 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+#[cfg(feature = "wasm")]
+use fusionamm_macros::wasm_expose;
+
+use crate::{
+    order_tick_indexes, position_ratio_x64, position_status, sqrt_price_to_tick_index, try_get_amount_delta_a, try_get_amount_delta_b, DepositSwap,
+    PositionFacade, PositionFacades, PositionStatus, TokenAmounts, MAX_SQRT_PRICE, MIN_SQRT_PRICE, U128,
+};
+use ethnum::U256;
+
+/// Calculate the liquidity of the positions that are active at the current price.
+/// A position is active if `tick_lower_index <= current_tick_index < tick_upper_index`,
+/// which matches how the pool liquidity is updated when ticks are crossed.
+///
+/// # Parameters
+/// - `positions` - The positions to aggregate
+/// - `current_sqrt_price` - A u128 integer representing the sqrt price of the pool
+///
+/// # Returns
+/// - A u128 integer representing the sum of the in-range liquidity
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn effective_liquidity_at_price(positions: PositionFacades, current_sqrt_price: U128) -> U128 {
+    let positions: Vec<PositionFacade> = positions.into();
+    let current_tick_index = sqrt_price_to_tick_index(current_sqrt_price);
+    positions
+        .iter()
+        .filter(|position| {
+            let tick_range = order_tick_indexes(position.tick_lower_index, position.tick_upper_index);
+            tick_range.tick_lower_index <= current_tick_index && current_tick_index < tick_range.tick_upper_index
+        })
+        .fold(0u128, |liquidity, position| liquidity.saturating_add(position.liquidity))
+        .into()
+}
+
+/// Calculate the token amounts of a full-range position, which spans from `MIN_SQRT_PRICE` to `MAX_SQRT_PRICE`.
+/// The amounts are rounded up, so they can be used as the deposit for the given liquidity.
+/// Amounts that don't fit into a u64 integer saturate at `u64::MAX`.
+///
+/// # Parameters
+/// - `liquidity` - A u128 integer representing the liquidity of the position
+/// - `current_sqrt_price` - A u128 integer representing the sqrt price of the pool
+///
+/// # Returns
+/// - A TokenAmounts struct containing the token A and token B amounts
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn full_range_liquidity_amounts(liquidity: U128, current_sqrt_price: U128) -> TokenAmounts {
+    let liquidity: u128 = liquidity.into();
+    let current_sqrt_price: u128 = current_sqrt_price.into();
+    let current_sqrt_price = current_sqrt_price.clamp(MIN_SQRT_PRICE, MAX_SQRT_PRICE);
+    let amount_a = try_get_amount_delta_a(current_sqrt_price.into(), MAX_SQRT_PRICE.into(), liquidity.into(), true).unwrap_or(u64::MAX);
+    let amount_b = try_get_amount_delta_b(MIN_SQRT_PRICE.into(), current_sqrt_price.into(), liquidity.into(), true).unwrap_or(u64::MAX);
+    TokenAmounts { amount_a, amount_b }
+}
+
+/// Calculate the swap that converts the given token amounts into the ratio required by a position,
+/// so that both amounts can be deposited fully, e.g. to zap into a position or to rebalance it.
+/// The swap is valued at the current price; swap fees and price impact are not taken into account.
+///
+/// # Parameters
+/// - `amount_a` - The amount of token A held
+/// - `amount_b` - The amount of token B held
+/// - `current_sqrt_price` - A u128 integer representing the sqrt price of the pool
+/// - `tick_lower` - A i32 integer representing the lower tick index of the position
+/// - `tick_upper` - A i32 integer representing the upper tick index of the position
+///
+/// # Returns
+/// - A DepositSwap struct containing the swap direction (true if token A is swapped to token B) and the input amount of the swap
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn optimal_deposit_swap_amount(amount_a: u64, amount_b: u64, current_sqrt_price: U128, tick_lower: i32, tick_upper: i32) -> DepositSwap {
+    let current_sqrt_price: u128 = current_sqrt_price.into();
+    let (a_to_b, amount_in) = match position_status(current_sqrt_price.into(), tick_lower, tick_upper) {
+        PositionStatus::Invalid => (false, 0),
+        PositionStatus::PriceBelowRange => (false, amount_b),
+        PositionStatus::PriceAboveRange => (true, amount_a),
+        PositionStatus::PriceInRange => {
+            let ratio_a: U256 = position_ratio_x64(current_sqrt_price.into(), tick_lower, tick_upper).ratio_a.into();
+            let sqrt_price = U256::from(current_sqrt_price);
+
+            // The values of the tokens in token B as Q64.64 numbers.
+            let value_a: U256 = (U256::from(amount_a) * sqrt_price * sqrt_price) >> 64;
+            let value_b: U256 = U256::from(amount_b) << 64;
+            let total_value = value_a + value_b;
+            // Splits the multiplication by the ratio to avoid an overflow.
+            let target_value_a: U256 = (total_value >> 64) * ratio_a + (((total_value & U256::from(u64::MAX)) * ratio_a) >> 64);
+
+            if value_a > target_value_a {
+                let swap_amount_a: U256 = ((value_a - target_value_a) << 64) / (sqrt_price * sqrt_price);
+                (true, swap_amount_a.min(U256::from(amount_a)).as_u64())
+            } else {
+                let swap_amount_b: U256 = (target_value_a - value_a) >> 64;
+                (false, swap_amount_b.min(U256::from(amount_b)).as_u64())
+            }
+        }
+    };
+    DepositSwap { a_to_b, amount_in }
+}
+
+#[cfg(all(test, not(feature = "wasm")))]
+mod tests {
+    use super::*;
+    use crate::tick_index_to_sqrt_price;
+
+    fn test_position(liquidity: u128, tick_lower_index: i32, tick_upper_index: i32) -> PositionFacade {
+        PositionFacade {
+            liquidity,
+            tick_lower_index,
+            tick_upper_index,
+            ..PositionFacade::default()
+        }
+    }
+
+    #[test]
+    fn test_effective_liquidity_at_price() {
+        let positions = vec![
+            test_position(1000, -128, 128),
+            test_position(2000, -64, 64),
+            test_position(4000, 64, 256),
+            test_position(8000, -256, -64),
+        ];
+
+        let sqrt_price: u128 = tick_index_to_sqrt_price(0).into();
+        assert_eq!(effective_liquidity_at_price(positions.clone(), sqrt_price), 3000);
+
+        let sqrt_price: u128 = tick_index_to_sqrt_price(100).into();
+        assert_eq!(effective_liquidity_at_price(positions.clone(), sqrt_price), 5000);
+
+        // The lower tick is inclusive and the upper tick is exclusive.
+        let sqrt_price: u128 = tick_index_to_sqrt_price(64).into();
+        assert_eq!(effective_liquidity_at_price(positions.clone(), sqrt_price), 5000);
+        let sqrt_price: u128 = tick_index_to_sqrt_price(-64).into();
+        assert_eq!(effective_liquidity_at_price(positions.clone(), sqrt_price), 3000);
+
+        let sqrt_price: u128 = tick_index_to_sqrt_price(1000).into();
+        assert_eq!(effective_liquidity_at_price(positions.clone(), sqrt_price), 0);
+        assert_eq!(effective_liquidity_at_price(vec![], sqrt_price), 0);
+    }
+
+    #[test]
+    fn test_full_range_liquidity_amounts() {
+        // At a price of 1.0 both amounts are close to the liquidity.
+        let amounts = full_range_liquidity_amounts(1_000_000_000, 1 << 64);
+        assert_eq!(amounts.amount_a, 1_000_000_000);
+        assert_eq!(amounts.amount_b, 1_000_000_000);
+
+        assert_eq!(full_range_liquidity_amounts(1_000_000_000, tick_index_to_sqrt_price(0)), amounts);
+
+        // Only token B is needed at the max price, and only token A at the min price.
+        assert_eq!(full_range_liquidity_amounts(1_000_000_000, MAX_SQRT_PRICE).amount_a, 0);
+        assert_eq!(full_range_liquidity_amounts(1_000_000_000, MIN_SQRT_PRICE).amount_b, 0);
+
+        assert_eq!(full_range_liquidity_amounts(0, 1 << 64), TokenAmounts::default());
+        let amounts = full_range_liquidity_amounts(u128::MAX, 1 << 64);
+        assert_eq!((amounts.amount_a, amounts.amount_b), (u64::MAX, u64::MAX));
+    }
+
+    #[test]
+    fn test_optimal_deposit_swap_amount_below_range() {
+        let sqrt_price: u128 = tick_index_to_sqrt_price(0).into();
+        let swap = optimal_deposit_swap_amount(1000, 2000, sqrt_price, 128, 256);
+        assert_eq!((swap.a_to_b, swap.amount_in), (false, 2000));
+        let swap = optimal_deposit_swap_amount(1000, 0, sqrt_price, 128, 256);
+        assert_eq!((swap.a_to_b, swap.amount_in), (false, 0));
+    }
+
+    #[test]
+    fn test_optimal_deposit_swap_amount_above_range() {
+        let sqrt_price: u128 = tick_index_to_sqrt_price(0).into();
+        let swap = optimal_deposit_swap_amount(1000, 2000, sqrt_price, -256, -128);
+        assert_eq!((swap.a_to_b, swap.amount_in), (true, 1000));
+        let swap = optimal_deposit_swap_amount(0, 2000, sqrt_price, -256, -128);
+        assert_eq!((swap.a_to_b, swap.amount_in), (true, 0));
+    }
+
+    #[test]
+    fn test_optimal_deposit_swap_amount_in_range() {
+        // The range is symmetric around a price of 1.0, so half of the value is swapped.
+        let swap = optimal_deposit_swap_amount(1_000_000, 0, 1 << 64, -1000, 1000);
+        assert_eq!((swap.a_to_b, swap.amount_in), (true, 500_000));
+        // The swap amounts are rounded down.
+        let swap = optimal_deposit_swap_amount(0, 1_000_000, 1 << 64, -1000, 1000);
+        assert_eq!((swap.a_to_b, swap.amount_in), (false, 499_999));
+        assert_eq!(optimal_deposit_swap_amount(500_000, 500_000, 1 << 64, -1000, 1000).amount_in, 0);
+
+        // The remaining amounts match the position ratio at a price of 4.0.
+        let sqrt_price: u128 = 2 << 64;
+        let swap = optimal_deposit_swap_amount(1_000_000, 0, sqrt_price, 0, 27728);
+        assert!(swap.a_to_b);
+        let swap_amount = swap.amount_in;
+        let amount_a = 1_000_000 - swap_amount;
+        let amount_b = swap_amount * 4;
+        let ratio = position_ratio_x64(sqrt_price.into(), 0, 27728);
+        let expected_amount_b = ((amount_a as u128 * 4) * ratio.ratio_b / ratio.ratio_a) as u64;
+        // Within the rounding of two units of token A.
+        assert!(amount_b.abs_diff(expected_amount_b) <= 8);
+    }
+}
@@ -10,6 +10,7 @@
 
 mod bundle;
 mod limit_order;
+mod liquidity;
 mod position;
 mod tick;
 mod tick_array;
@@ -22,6 +23,7 @@ mod price;
 
 pub use bundle::*;
 pub use limit_order::*;
+pub use liquidity::*;
 pub use position::*;
 pub use tick::*;
 pub use tick_array::*;
@@ -107,6 +107,22 @@ pub fn price_to_tick_index(price: f64, decimals_a: u8, decimals_b: u8) -> i32 {
     sqrt_price_to_tick_index(sqrt_price)
 }
 
+/// Compute the precision lost when converting a price into a sqrt priceX64 and back
+/// Use it to detect extreme prices for which the conversion is no longer accurate.
+///
+/// # Parameters
+/// * `price` - The price to convert
+/// * `decimals_a` - The number of decimals of the base token
+/// * `decimals_b` - The number of decimals of the quote token
+///
+/// # Returns
+/// * `f64` - The absolute difference between the price and its round-tripped value
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn price_round_trip_error(price: f64, decimals_a: u8, decimals_b: u8) -> f64 {
+    let sqrt_price = price_to_sqrt_price(price, decimals_a, decimals_b);
+    (price - sqrt_price_to_price(sqrt_price, decimals_a, decimals_b)).abs()
+}
+
 #[cfg(all(test, not(feature = "wasm")))]
 mod tests {
     use approx::assert_relative_eq;
@@ -173,4 +189,28 @@ mod tests {
         let diff_rate = (diff as f64) / (sqrt_price as f64) * 100.0;
         assert_relative_eq!(diff_rate, 0.0, epsilon = 1e-10);
     }
+
+    #[test]
+    fn test_price_round_trip_error() {
+        assert_eq!(price_round_trip_error(1.0, 6, 6), 0.0);
+        assert_eq!(price_round_trip_error(100.0, 6, 6), 0.0);
+    }
+
+    #[test]
+    fn test_price_round_trip_error_small_prices() {
+        // The sqrt priceX64 is truncated, so the relative error grows as the price shrinks.
+        let relative_error = |price: f64| price_round_trip_error(price, 6, 6) / price;
+        assert!(relative_error(1e-9) > 0.0);
+        assert!(relative_error(1e-12) > relative_error(1e-9));
+        assert!(relative_error(1e-15) > relative_error(1e-12));
+        assert!(relative_error(1e-15) < 1e-11);
+    }
+
+    #[test]
+    fn test_price_round_trip_error_large_prices() {
+        // Only the floating point precision is lost, so the absolute error grows with the price.
+        assert!(price_round_trip_error(1e9, 6, 6) > 0.0);
+        assert!(price_round_trip_error(1e15, 6, 6) > price_round_trip_error(1e9, 6, 6));
+        assert!(price_round_trip_error(1e15, 6, 6) / 1e15 < 1e-15);
+    }
 }
@@ -14,7 +14,8 @@ use ethnum::U256;
 use fusionamm_macros::wasm_expose;
 
 use crate::{
-    CoreError, TickRange, FULL_RANGE_ONLY_TICK_SPACING_THRESHOLD, MAX_TICK_INDEX, MIN_TICK_INDEX, TICK_ARRAY_SIZE, TICK_INDEX_NOT_IN_ARRAY, U128,
+    CoreError, TickRange, FULL_RANGE_ONLY_TICK_SPACING_THRESHOLD, MAX_SQRT_PRICE, MAX_TICK_INDEX, MIN_SQRT_PRICE, MIN_TICK_INDEX,
+    SQRT_PRICE_OUT_OF_BOUNDS, TICK_ARRAY_SIZE, TICK_INDEX_NOT_IN_ARRAY, U128,
 };
 
 const LOG_B_2_X32: i128 = 59543866431248i128;
@@ -120,6 +121,23 @@ pub fn sqrt_price_to_tick_index(sqrt_price: U128) -> i32 {
     }
 }
 
+/// Derive the tick index from a sqrt price, checking that the sqrt price is within bounds.
+///
+/// # Parameters
+/// - `sqrt_price` - A u128 integer representing the sqrt price
+///
+/// # Returns
+/// - `Ok`: A i32 integer representing the tick integer
+/// - `Err`: `SQRT_PRICE_OUT_OF_BOUNDS` if the sqrt price is outside of {min, max} sqrt price
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn try_sqrt_price_to_tick_index(sqrt_price: U128) -> Result<i32, CoreError> {
+    let sqrt_price_x64: u128 = sqrt_price.into();
+    if !(MIN_SQRT_PRICE..=MAX_SQRT_PRICE).contains(&sqrt_price_x64) {
+        return Err(SQRT_PRICE_OUT_OF_BOUNDS);
+    }
+    Ok(sqrt_price_to_tick_index(sqrt_price_x64.into()))
+}
+
 /// Get the initializable tick index.
 /// If the tick index is already initializable, it is returned as is.
 ///
@@ -149,6 +167,23 @@ pub fn get_initializable_tick_index(tick_index: i32, tick_spacing: u16, round_up
     }
 }
 
+/// Get the initializable tick indexes below and above a tick index.
+/// If the tick index is already initializable, both bounds are equal to it.
+///
+/// # Parameters
+/// - `tick_index` - A i32 integer representing the tick integer
+/// - `tick_spacing` - A i32 integer representing the tick spacing
+///
+/// # Returns
+/// - A TickRange with the floor-aligned lower and the ceil-aligned upper initializable tick index
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn initializable_tick_bounds(tick_index: i32, tick_spacing: u16) -> TickRange {
+    TickRange {
+        tick_lower_index: get_initializable_tick_index(tick_index, tick_spacing, Some(false)),
+        tick_upper_index: get_initializable_tick_index(tick_index, tick_spacing, Some(true)),
+    }
+}
+
 /// Get the previous initializable tick index.
 ///
 /// # Parameters
@@ -457,7 +492,6 @@ fn get_sqrt_price_negative_tick(tick: i32) -> u128 {
 #[cfg(all(test, not(feature = "wasm")))]
 mod tests {
     use super::*;
-    use crate::{MAX_SQRT_PRICE, MIN_SQRT_PRICE};
 
     #[test]
     fn test_get_tick_array_start_tick_index() {
@@ -490,6 +524,16 @@ mod tests {
         assert_eq!(sqrt_price_to_tick_index(MIN_SQRT_PRICE), MIN_TICK_INDEX);
     }
 
+    #[test]
+    fn test_try_sqrt_price_to_tick_index() {
+        assert_eq!(try_sqrt_price_to_tick_index(MAX_SQRT_PRICE), Ok(MAX_TICK_INDEX));
+        assert_eq!(try_sqrt_price_to_tick_index(18446744073709551616), Ok(0));
+        assert_eq!(try_sqrt_price_to_tick_index(MIN_SQRT_PRICE), Ok(MIN_TICK_INDEX));
+        assert_eq!(try_sqrt_price_to_tick_index(MAX_SQRT_PRICE + 1), Err(SQRT_PRICE_OUT_OF_BOUNDS));
+        assert_eq!(try_sqrt_price_to_tick_index(MIN_SQRT_PRICE - 1), Err(SQRT_PRICE_OUT_OF_BOUNDS));
+        assert_eq!(try_sqrt_price_to_tick_index(0), Err(SQRT_PRICE_OUT_OF_BOUNDS));
+    }
+
     #[test]
     fn test_get_initializable_tick_index() {
         assert_eq!(get_initializable_tick_index(-100, 10, Some(true)), -100);
@@ -525,6 +569,25 @@ mod tests {
         assert_eq!(get_initializable_tick_index(109, 10, None), 110);
     }
 
+    #[test]
+    fn test_initializable_tick_bounds() {
+        let bounds = initializable_tick_bounds(100, 10);
+        assert_eq!((bounds.tick_lower_index, bounds.tick_upper_index), (100, 100));
+        let bounds = initializable_tick_bounds(-100, 10);
+        assert_eq!((bounds.tick_lower_index, bounds.tick_upper_index), (-100, -100));
+        let bounds = initializable_tick_bounds(0, 64);
+        assert_eq!((bounds.tick_lower_index, bounds.tick_upper_index), (0, 0));
+
+        let bounds = initializable_tick_bounds(101, 10);
+        assert_eq!((bounds.tick_lower_index, bounds.tick_upper_index), (100, 110));
+        let bounds = initializable_tick_bounds(109, 10);
+        assert_eq!((bounds.tick_lower_index, bounds.tick_upper_index), (100, 110));
+        let bounds = initializable_tick_bounds(-101, 10);
+        assert_eq!((bounds.tick_lower_index, bounds.tick_upper_index), (-110, -100));
+        let bounds = initializable_tick_bounds(-1, 64);
+        assert_eq!((bounds.tick_lower_index, bounds.tick_upper_index), (-64, 0));
+    }
+
     #[test]
     fn test_get_prev_initializable_tick_index() {
         assert_eq!(get_prev_initializable_tick_index(10, 10), 0);
@@ -8,11 +8,34 @@
 // See the LICENSE file in the project root for license information.
 //
 use crate::{
-    CoreError, TickArrayFacade, TickFacade, INVALID_TICK_ARRAY_SEQUENCE, INVALID_TICK_INDEX, MAX_TICK_INDEX, MIN_TICK_INDEX,
+    CoreError, TickArrayFacade, TickFacade, INVALID_TICK_ARRAY_SEQUENCE, INVALID_TICK_INDEX, INVALID_TICK_SPACING, MAX_TICK_INDEX, MIN_TICK_INDEX,
     TICK_ARRAY_NOT_EVENLY_SPACED, TICK_ARRAY_SIZE, TICK_INDEX_OUT_OF_BOUNDS, TICK_SEQUENCE_EMPTY,
 };
 
-use super::{get_initializable_tick_index, get_next_initializable_tick_index, get_prev_initializable_tick_index};
+use super::{get_initializable_tick_index, get_next_initializable_tick_index, get_prev_initializable_tick_index, get_tick_array_start_tick_index};
+
+#[cfg(feature = "wasm")]
+use fusionamm_macros::wasm_expose;
+
+/// Get the start tick indexes of all tick arrays covering a range of ticks.
+///
+/// # Parameters
+/// - `lower_tick_index` - A i32 integer representing the first tick index of the range
+/// - `upper_tick_index` - A i32 integer representing the last tick index of the range (inclusive)
+/// - `tick_spacing` - A u16 integer representing the tick spacing
+///
+/// # Returns
+/// - A vector of start tick indexes in ascending order, or an empty vector if the tick spacing is 0
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn tick_array_start_indices_for_range(lower_tick_index: i32, upper_tick_index: i32, tick_spacing: u16) -> Vec<i32> {
+    if tick_spacing == 0 {
+        return Vec::new();
+    }
+    let start_lower = get_tick_array_start_tick_index(lower_tick_index.min(upper_tick_index), tick_spacing);
+    let start_upper = get_tick_array_start_tick_index(lower_tick_index.max(upper_tick_index), tick_spacing);
+    let offset = TICK_ARRAY_SIZE as i32 * tick_spacing as i32;
+    (start_lower..=start_upper).step_by(offset as usize).collect()
+}
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TickArraySequence<const SIZE: usize> {
@@ -29,6 +52,10 @@ impl<const SIZE: usize> TickArraySequence<SIZE> {
             return Err(TICK_SEQUENCE_EMPTY);
         }
 
+        if tick_spacing == 0 {
+            return Err(INVALID_TICK_SPACING);
+        }
+
         let required_tick_array_spacing = TICK_ARRAY_SIZE as i32 * tick_spacing as i32;
         for i in 0..tick_arrays.len() - 1 {
             let current_start_tick_index = start_tick_index(&tick_arrays[i]);
@@ -219,6 +246,16 @@ mod tests {
         assert_eq!(sequence.tick(427648).map(|x| x.liquidity_net), Ok(13));
     }
 
+    #[test]
+    fn test_zero_tick_spacing() {
+        let tick_array = TickArrayFacade {
+            start_tick_index: 0,
+            ticks: test_ticks_initialized(),
+        };
+        let sequence = TickArraySequence::new([Some(tick_array), None], 0);
+        assert_eq!(sequence, Err(INVALID_TICK_SPACING));
+    }
+
     #[test]
     fn test_get_tick_errors() {
         let sequence = test_sequence(16, test_ticks_alternating_initialized());
@@ -409,4 +446,25 @@ mod tests {
         let pair = sequence.prev_initialized_tick(MIN_TICK_INDEX);
         assert_eq!(pair, Ok((Some(&test_tick(true, 60)), MIN_TICK_INDEX)));
     }
+
+    #[test]
+    fn test_tick_array_start_indices_for_single_array() {
+        assert_eq!(tick_array_start_indices_for_range(0, 100, 2), vec![0]);
+        assert_eq!(tick_array_start_indices_for_range(-10, -1, 2), vec![-176]);
+        assert_eq!(tick_array_start_indices_for_range(5, 5, 64), vec![0]);
+    }
+
+    #[test]
+    fn test_tick_array_start_indices_for_zero_tick_spacing() {
+        assert_eq!(tick_array_start_indices_for_range(0, 100, 0), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn test_tick_array_start_indices_for_multiple_arrays() {
+        assert_eq!(tick_array_start_indices_for_range(-100, 100, 2), vec![-176, 0]);
+        assert_eq!(tick_array_start_indices_for_range(-400, 400, 2), vec![-528, -352, -176, 0, 176, 352]);
+        assert_eq!(tick_array_start_indices_for_range(400, -400, 2), vec![-528, -352, -176, 0, 176, 352]);
+        assert_eq!(tick_array_start_indices_for_range(176, 352, 2), vec![176, 352]);
+        assert_eq!(tick_array_start_indices_for_range(-5632, 5631, 64), vec![-5632, 0]);
+    }
 }
@@ -10,8 +10,8 @@
 
 use crate::{
     get_initializable_tick_index, get_next_initializable_tick_index, get_prev_initializable_tick_index, CoreError, TickArrayFacade, TickFacade,
-    INVALID_TICK_ARRAY_SEQUENCE, INVALID_TICK_INDEX, MAX_TICK_INDEX, MIN_TICK_INDEX, TICK_ARRAY_NOT_EVENLY_SPACED, TICK_ARRAY_SIZE,
-    TICK_INDEX_OUT_OF_BOUNDS, TICK_SEQUENCE_EMPTY,
+    INVALID_TICK_ARRAY_SEQUENCE, INVALID_TICK_INDEX, INVALID_TICK_SPACING, MAX_TICK_INDEX, MIN_TICK_INDEX, TICK_ARRAY_NOT_EVENLY_SPACED,
+    TICK_ARRAY_SIZE, TICK_INDEX_OUT_OF_BOUNDS, TICK_SEQUENCE_EMPTY,
 };
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -29,6 +29,10 @@ impl TickArraySequenceVec {
             return Err(TICK_SEQUENCE_EMPTY);
         }
 
+        if tick_spacing == 0 {
+            return Err(INVALID_TICK_SPACING);
+        }
+
         let required_tick_array_spacing = TICK_ARRAY_SIZE as i32 * tick_spacing as i32;
         for i in 0..tick_arrays.len() - 1 {
             let current_start_tick_index = tick_arrays[i].start_tick_index;
@@ -54,6 +58,10 @@ impl TickArraySequenceVec {
     }
 
     pub fn tick(&self, tick_index: i32) -> Result<TickFacade, CoreError> {
+        self.tick_ref(tick_index).copied()
+    }
+
+    fn tick_ref(&self, tick_index: i32) -> Result<&TickFacade, CoreError> {
         if (tick_index < self.start_index()) || (tick_index > self.end_index()) {
             return Err(TICK_INDEX_OUT_OF_BOUNDS);
         }
@@ -63,9 +71,8 @@ impl TickArraySequenceVec {
         let first_index = self.tick_arrays[0].start_tick_index;
         let tick_array_index = ((tick_index - first_index) / (TICK_ARRAY_SIZE as i32 * self.tick_spacing as i32)) as usize;
         let tick_array_start_index = self.tick_arrays[tick_array_index].start_tick_index;
-        let tick_array_ticks = self.tick_arrays[tick_array_index].ticks;
         let index_in_array = (tick_index - tick_array_start_index) / self.tick_spacing as i32;
-        Ok(tick_array_ticks[index_in_array as usize])
+        Ok(&self.tick_arrays[tick_array_index].ticks[index_in_array as usize])
     }
 
     pub fn next_initialized_tick(&self, tick_index: i32) -> Result<(Option<TickFacade>, i32), CoreError> {
@@ -105,6 +112,20 @@ impl TickArraySequenceVec {
             prev_index = get_prev_initializable_tick_index(prev_index, self.tick_spacing);
         }
     }
+
+    /// Returns the next initialized tick in the swap direction: at or below `tick_index` if `a_to_b`
+    /// is true and above it otherwise.
+    /// Returns `None` instead of an error once there are no initialized ticks left in the sequence.
+    pub fn next_initialized_tick_opt(&self, tick_index: i32, a_to_b: bool) -> Option<(i32, &TickFacade)> {
+        let (tick, next_tick_index) = if a_to_b {
+            self.prev_initialized_tick(tick_index)
+        } else {
+            self.next_initialized_tick(tick_index)
+        }
+        .ok()?;
+        tick?;
+        self.tick_ref(next_tick_index).ok().map(|tick| (next_tick_index, tick))
+    }
 }
 
 #[cfg(all(test, not(feature = "wasm")))]
@@ -196,6 +217,16 @@ mod tests {
         assert_eq!(sequence.tick(427648).map(|x| x.liquidity_net), Ok(13));
     }
 
+    #[test]
+    fn test_zero_tick_spacing() {
+        let tick_array = TickArrayFacade {
+            start_tick_index: 0,
+            ticks: test_ticks_initialized(),
+        };
+        let sequence = TickArraySequenceVec::new(vec![tick_array], 0);
+        assert_eq!(sequence, Err(INVALID_TICK_SPACING));
+    }
+
     #[test]
     fn test_get_tick_errors() {
         let sequence = test_sequence(16, test_ticks_alternating_initialized());
@@ -301,6 +332,32 @@ mod tests {
         assert_eq!(pair, Ok((Some(test_tick(true, 28)), MAX_TICK_INDEX)));
     }
 
+    #[test]
+    fn test_next_initialized_tick_opt() {
+        let sequence = test_sequence(16, test_ticks_alternating_initialized());
+        assert_eq!(sequence.next_initialized_tick_opt(0, false), Some((16, &test_tick(true, 1))));
+        assert_eq!(sequence.next_initialized_tick_opt(32, true), Some((16, &test_tick(true, 1))));
+
+        // Walk to the edges of the sequence.
+        let mut tick_index = 0;
+        while let Some((next_tick_index, _)) = sequence.next_initialized_tick_opt(tick_index, false) {
+            tick_index = next_tick_index;
+        }
+        assert_eq!(tick_index, 2800);
+        assert_eq!(sequence.next_initialized_tick_opt(2815, false), None);
+
+        let mut tick_index = 0;
+        while let Some((next_tick_index, _)) = sequence.next_initialized_tick_opt(tick_index, true) {
+            tick_index = next_tick_index - 1;
+        }
+        assert_eq!(tick_index, -1392 - 1);
+        assert_eq!(sequence.next_initialized_tick_opt(-1409, true), None);
+
+        let sequence = test_sequence(16, test_ticks_uninitialized());
+        assert_eq!(sequence.next_initialized_tick_opt(0, false), None);
+        assert_eq!(sequence.next_initialized_tick_opt(0, true), None);
+    }
+
     #[test]
     fn test_get_prev_initializable_tick_index() {
         let sequence = test_sequence(16, test_ticks_alternating_initialized());
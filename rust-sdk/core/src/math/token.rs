@@ -335,6 +335,42 @@ pub fn div_by_sqrt_price_squared(amount: u64, sqrt_price: u128, round_up: bool)
     u64::try_from(value_u128).or(Err(ARITHMETIC_OVERFLOW))
 }
 
+/// Calculate the fee growth accrued since a checkpoint
+///
+/// The fee growth of the program wraps around u128, so the delta is computed with wrapping
+/// subtraction, matching the program.
+///
+/// # Parameters
+/// - `current`: The current fee growth
+/// - `checkpoint`: The fee growth at the checkpoint
+///
+/// # Returns
+/// - `u128`: The fee growth since the checkpoint
+pub fn fee_growth_delta(current: u128, checkpoint: u128) -> u128 {
+    current.wrapping_sub(checkpoint)
+}
+
+/// Format a raw token amount as a decimal string
+///
+/// The amount is split into its integer and fractional parts on the string representation,
+/// so the result is exact for any amount. The fractional part always has `decimals` digits.
+///
+/// # Parameters
+/// - `amount`: The raw token amount
+/// - `decimals`: The number of decimals of the token
+///
+/// # Returns
+/// - `String`: The formatted amount, e.g. `1.500000` for an amount of 1500000 with 6 decimals
+pub fn format_token_amount(amount: u64, decimals: u8) -> String {
+    let decimals = decimals as usize;
+    if decimals == 0 {
+        return amount.to_string();
+    }
+    let digits = format!("{:0>width$}", amount, width = decimals + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - decimals);
+    format!("{}.{}", integer, fraction)
+}
+
 // Private functions
 
 fn order_prices(a: u128, b: u128) -> (u128, u128) {
@@ -349,6 +385,37 @@ fn order_prices(a: u128, b: u128) -> (u128, u128) {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_fee_growth_delta() {
+        assert_eq!(fee_growth_delta(1000, 400), 600);
+        assert_eq!(fee_growth_delta(1000, 1000), 0);
+        assert_eq!(fee_growth_delta(u128::MAX, 0), u128::MAX);
+    }
+
+    #[test]
+    fn test_fee_growth_delta_wraparound() {
+        // The fee growth wrapped past u128::MAX after the checkpoint was taken.
+        assert_eq!(fee_growth_delta(99, u128::MAX - 100), 200);
+        assert_eq!(fee_growth_delta(0, u128::MAX), 1);
+        assert_eq!(fee_growth_delta(0, 1), u128::MAX);
+    }
+
+    #[test]
+    fn test_format_token_amount() {
+        assert_eq!(format_token_amount(1_500_000, 6), "1.500000");
+        assert_eq!(format_token_amount(1_000_000_000, 9), "1.000000000");
+        assert_eq!(format_token_amount(120_000, 0), "120000");
+        assert_eq!(format_token_amount(u64::MAX, 9), "18446744073.709551615");
+    }
+
+    #[test]
+    fn test_format_token_amount_less_than_one_unit() {
+        assert_eq!(format_token_amount(1, 6), "0.000001");
+        assert_eq!(format_token_amount(0, 6), "0.000000");
+        assert_eq!(format_token_amount(999, 3), "0.999");
+        assert_eq!(format_token_amount(42, 25), "0.0000000000000000000000042");
+    }
+
     #[test]
     fn test_get_amount_delta_a() {
         assert_eq!(try_get_amount_delta_a(4 << 64, 2 << 64, 4, true), Ok(1));
@@ -29,6 +29,37 @@ impl U256Muldiv {
         }
     }
 
+    /// Returns the value as 32 bytes in little-endian byte order.
+    pub fn to_le_bytes(&self) -> [u8; NUM_WORDS * 8] {
+        let mut bytes = [0u8; NUM_WORDS * 8];
+        for (chunk, word) in bytes.chunks_exact_mut(8).zip(self.items.iter()) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Returns the value as 32 bytes in big-endian byte order.
+    pub fn to_be_bytes(&self) -> [u8; NUM_WORDS * 8] {
+        let mut bytes = self.to_le_bytes();
+        bytes.reverse();
+        bytes
+    }
+
+    /// Creates a value from 32 bytes in little-endian byte order.
+    pub fn from_le_bytes(bytes: [u8; NUM_WORDS * 8]) -> Self {
+        let mut items: [u64; NUM_WORDS] = [0; NUM_WORDS];
+        for (word, chunk) in items.iter_mut().zip(bytes.chunks_exact(8)) {
+            *word = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        U256Muldiv { items }
+    }
+
+    /// Creates a value from 32 bytes in big-endian byte order.
+    pub fn from_be_bytes(mut bytes: [u8; NUM_WORDS * 8]) -> Self {
+        bytes.reverse();
+        Self::from_le_bytes(bytes)
+    }
+
     fn copy(&self) -> Self {
         let mut items: [u64; NUM_WORDS] = [0; NUM_WORDS];
         items.copy_from_slice(&self.items);
@@ -561,3 +592,35 @@ fn div_loop(
 
     (quotient, dividend)
 }
+
+#[cfg(all(test, not(feature = "wasm")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let values = [
+            U256Muldiv::new(0, 0),
+            U256Muldiv::new(0, u128::MAX),
+            U256Muldiv::new(0x0102030405060708090a0b0c0d0e0f10, 0x1112131415161718191a1b1c1d1e1f20),
+        ];
+        for value in values {
+            assert!(U256Muldiv::from_le_bytes(value.to_le_bytes()).eq(value));
+            assert!(U256Muldiv::from_be_bytes(value.to_be_bytes()).eq(value));
+        }
+    }
+
+    #[test]
+    fn test_bytes_order() {
+        let value = U256Muldiv::new(0x0102030405060708090a0b0c0d0e0f10, 0x1112131415161718191a1b1c1d1e1f20);
+        let be_bytes: [u8; 32] = core::array::from_fn(|i| i as u8 + 1);
+        assert_eq!(value.to_be_bytes(), be_bytes);
+        assert_eq!(value.to_le_bytes(), core::array::from_fn(|i| 32 - i as u8));
+
+        let max = U256Muldiv::new(0, u128::MAX);
+        assert_eq!(max.to_le_bytes()[..16], [0xff; 16]);
+        assert_eq!(max.to_le_bytes()[16..], [0; 16]);
+        assert_eq!(max.to_be_bytes()[..16], [0; 16]);
+        assert_eq!(U256Muldiv::new(0, 0).to_be_bytes(), [0; 32]);
+    }
+}
@@ -13,8 +13,8 @@ use ethnum::U256;
 use fusionamm_macros::wasm_expose;
 
 use crate::{
-    try_apply_transfer_fee, CollectFeesQuote, CoreError, FusionPoolFacade, PositionFacade, TickFacade, TransferFee, AMOUNT_EXCEEDS_MAX_U64,
-    ARITHMETIC_OVERFLOW, MAX_CLP_REWARD_RATE, MAX_ORDER_PROTOCOL_FEE_RATE,
+    fee_growth_delta, try_apply_transfer_fee, CollectFeesQuote, CoreError, FusionPoolFacade, PositionFacade, TickFacade, TransferFee,
+    AMOUNT_EXCEEDS_MAX_U64, ARITHMETIC_OVERFLOW, MAX_CLP_REWARD_RATE, MAX_ORDER_PROTOCOL_FEE_RATE,
 };
 
 /// Calculate fees owed for a position
@@ -45,28 +45,22 @@ pub fn collect_fees_quote(
     let mut fee_growth_above_b: u128 = tick_upper.fee_growth_outside_b;
 
     if fusion_pool.tick_current_index < position.tick_lower_index {
-        fee_growth_below_a = fusion_pool.fee_growth_global_a.wrapping_sub(fee_growth_below_a);
-        fee_growth_below_b = fusion_pool.fee_growth_global_b.wrapping_sub(fee_growth_below_b);
+        fee_growth_below_a = fee_growth_delta(fusion_pool.fee_growth_global_a, fee_growth_below_a);
+        fee_growth_below_b = fee_growth_delta(fusion_pool.fee_growth_global_b, fee_growth_below_b);
     }
 
     if fusion_pool.tick_current_index >= position.tick_upper_index {
-        fee_growth_above_a = fusion_pool.fee_growth_global_a.wrapping_sub(fee_growth_above_a);
-        fee_growth_above_b = fusion_pool.fee_growth_global_b.wrapping_sub(fee_growth_above_b);
+        fee_growth_above_a = fee_growth_delta(fusion_pool.fee_growth_global_a, fee_growth_above_a);
+        fee_growth_above_b = fee_growth_delta(fusion_pool.fee_growth_global_b, fee_growth_above_b);
     }
 
-    let fee_growth_inside_a = fusion_pool
-        .fee_growth_global_a
-        .wrapping_sub(fee_growth_below_a)
-        .wrapping_sub(fee_growth_above_a);
+    let fee_growth_inside_a = fee_growth_delta(fee_growth_delta(fusion_pool.fee_growth_global_a, fee_growth_below_a), fee_growth_above_a);
 
-    let fee_growth_inside_b = fusion_pool
-        .fee_growth_global_b
-        .wrapping_sub(fee_growth_below_b)
-        .wrapping_sub(fee_growth_above_b);
+    let fee_growth_inside_b = fee_growth_delta(fee_growth_delta(fusion_pool.fee_growth_global_b, fee_growth_below_b), fee_growth_above_b);
 
-    let fee_growth_delta_a = fee_growth_inside_a.wrapping_sub(position.fee_growth_checkpoint_a);
+    let fee_growth_delta_a = fee_growth_delta(fee_growth_inside_a, position.fee_growth_checkpoint_a);
 
-    let fee_growth_delta_b = fee_growth_inside_b.wrapping_sub(position.fee_growth_checkpoint_b);
+    let fee_growth_delta_b = fee_growth_delta(fee_growth_inside_b, position.fee_growth_checkpoint_b);
 
     let fee_owed_delta_a: U256 = <U256>::from(fee_growth_delta_a)
         .checked_mul(position.liquidity.into())
@@ -112,6 +106,7 @@ mod tests {
         }
     }
 
+    #[allow(clippy::needless_update)]
     fn test_position() -> PositionFacade {
         PositionFacade {
             liquidity: 10000000000000000000,
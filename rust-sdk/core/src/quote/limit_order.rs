@@ -12,6 +12,9 @@ use crate::{
     LIMIT_ORDER_AND_POOL_ARE_OUT_OF_SYNC, MAX_CLP_REWARD_RATE, PROTOCOL_FEE_RATE_MUL_VALUE,
 };
 
+#[cfg(feature = "floats")]
+use crate::tick_index_to_price;
+
 #[cfg(feature = "wasm")]
 use fusionamm_macros::wasm_expose;
 
@@ -75,6 +78,36 @@ pub fn limit_order_quote_by_output_token(
     Ok(amount_in)
 }
 
+/// Computes the part of `amount` of a limit order input which is not filled yet, from the state of the order tick.
+fn remaining_input(limit_order: &LimitOrderFacade, tick: &TickFacade, amount: u64) -> Result<u64, CoreError> {
+    // Not filled
+    if limit_order.age == tick.age {
+        Ok(amount)
+    }
+    // Partially filled
+    else if limit_order.age + 1 == tick.age {
+        if tick.part_filled_orders_input == 0 {
+            return Err(LIMIT_ORDER_AND_POOL_ARE_OUT_OF_SYNC);
+        }
+        try_mul_div(amount, tick.part_filled_orders_remaining_input as u128, tick.part_filled_orders_input as u128, false)
+    }
+    // Fulfilled
+    else if limit_order.age + 2 <= tick.age {
+        Ok(0)
+    } else {
+        Err(LIMIT_ORDER_AND_POOL_ARE_OUT_OF_SYNC)
+    }
+}
+
+/// Computes the input amount of a limit order which is not filled yet.
+/// ### Parameters
+/// - `limit_order` - The limit order state.
+/// - `tick` - The state of the limit order tick.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn limit_order_remaining_input(limit_order: LimitOrderFacade, tick: TickFacade) -> Result<u64, CoreError> {
+    remaining_input(&limit_order, &tick, limit_order.amount)
+}
+
 #[cfg_attr(feature = "wasm", wasm_expose)]
 pub fn decrease_limit_order_quote(
     fusion_pool: FusionPoolFacade,
@@ -88,27 +121,12 @@ pub fn decrease_limit_order_quote(
         return Err(AMOUNT_EXCEEDS_LIMIT_ORDER_INPUT_AMOUNT);
     }
 
-    // Not filled
-    let (amount_in, amount_out) = if limit_order.age == tick.age {
-        (amount, 0)
-    }
-    // Partially filled
-    else if limit_order.age + 1 == tick.age {
-        if tick.part_filled_orders_input == 0 {
-            return Err(LIMIT_ORDER_AND_POOL_ARE_OUT_OF_SYNC);
-        }
+    let amount_in = remaining_input(&limit_order, &tick, amount)?;
+    let amount_out = if amount_in < amount {
         let sqrt_price: u128 = tick_index_to_sqrt_price(limit_order.tick_index).into();
-        let remaining_input = try_mul_div(amount, tick.part_filled_orders_remaining_input as u128, tick.part_filled_orders_input as u128, false)?;
-        let amount_out = get_limit_order_output_amount(amount - remaining_input, limit_order.a_to_b, sqrt_price, false)?;
-        (remaining_input, amount_out)
-    }
-    // Fulfilled
-    else if limit_order.age + 2 <= tick.age {
-        let sqrt_price: u128 = tick_index_to_sqrt_price(limit_order.tick_index).into();
-        let amount_out = get_limit_order_output_amount(amount, limit_order.a_to_b, sqrt_price, false)?;
-        (0, amount_out)
+        get_limit_order_output_amount(amount - amount_in, limit_order.a_to_b, sqrt_price, false)?
     } else {
-        return Err(LIMIT_ORDER_AND_POOL_ARE_OUT_OF_SYNC);
+        0
     };
 
     let mut amount_out_a;
@@ -150,14 +168,96 @@ pub fn decrease_limit_order_quote(
         amount_out_b,
         reward_a,
         reward_b,
+        principal_returned: amount_in,
+        proceeds: amount_out,
     })
 }
 
+/// Computes the swap input, including the swap fee, required to fully consume a resting limit order.
+/// The order is filled at the price of its tick, so the result doesn't depend on the pool price.
+/// Only the input which is not filled yet is consumed, so the result is 0 for a fulfilled order.
+/// ### Parameters
+/// - `limit_order` - The limit order state.
+/// - `tick` - The state of the limit order tick, which tells how much of the order is filled.
+/// - `fee_rate` - The swap fee rate of the pool.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn input_to_fill_limit_order(limit_order: LimitOrderFacade, tick: TickFacade, fee_rate: u16) -> Result<u64, CoreError> {
+    let sqrt_price: u128 = tick_index_to_sqrt_price(limit_order.tick_index).into();
+    let remaining_input = limit_order_remaining_input(limit_order, tick)?;
+
+    // The swap input consumed by the order, which is the order output.
+    let amount_in = get_limit_order_output_amount(remaining_input, limit_order.a_to_b, sqrt_price, true)?;
+    // Swap fee in input token.
+    let fee_amount = try_mul_div(amount_in, fee_rate as u128, FEE_RATE_MUL_VALUE as u128 - fee_rate as u128, true)?;
+
+    amount_in.checked_add(fee_amount).ok_or(AMOUNT_EXCEEDS_MAX_U64)
+}
+
+/// Computes the average price realized so far by a limit order, including the order rewards.
+/// IMPORTANT: floating point operations can reduce the precision of the result.
+///
+/// The price is expressed as the pool price (token B per token A). If nothing is filled yet,
+/// the price of the order tick is returned. The output is computed with `decrease_limit_order_quote`,
+/// so the function fails in the same cases, e.g. if the limit order and the pool are out of sync.
+/// ### Parameters
+/// - `fusion_pool` - The fusion_pool state. The order rewards are a share of its OLP fees.
+/// - `limit_order` - The limit order state.
+/// - `tick` - The state of the limit order tick.
+/// - `decimals_a` - The number of decimals of token A.
+/// - `decimals_b` - The number of decimals of token B.
+#[cfg(feature = "floats")]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn limit_order_realized_price(
+    fusion_pool: FusionPoolFacade,
+    limit_order: LimitOrderFacade,
+    tick: TickFacade,
+    decimals_a: u8,
+    decimals_b: u8,
+) -> Result<f64, CoreError> {
+    let quote = decrease_limit_order_quote(fusion_pool, limit_order, tick, limit_order.amount, None, None)?;
+
+    let (filled_input, output) = if limit_order.a_to_b {
+        (limit_order.amount - quote.amount_out_a, quote.amount_out_b)
+    } else {
+        (limit_order.amount - quote.amount_out_b, quote.amount_out_a)
+    };
+
+    if filled_input == 0 || output == 0 {
+        return Ok(tick_index_to_price(limit_order.tick_index, decimals_a, decimals_b));
+    }
+
+    let (amount_a, amount_b) = if limit_order.a_to_b {
+        (filled_input, output)
+    } else {
+        (output, filled_input)
+    };
+
+    Ok(amount_b as f64 / amount_a as f64 * 10f64.powi(decimals_a as i32 - decimals_b as i32))
+}
+
+/// Computes the OLP rewards owed by a pool per filled unit of limit order input, as used in `decrease_limit_order_quote`.
+/// The rewards of `b_to_a` orders are paid in token A and the rewards of `a_to_b` orders in token B.
+/// IMPORTANT: floating point operations can reduce the precision of the result.
+/// ### Parameters
+/// - `pool` - The fusion_pool state.
+///
+/// Returns `olp_fee_owed_a / orders_filled_amount_b` and `olp_fee_owed_b / orders_filled_amount_a`.
+/// A rate is 0.0 if no orders of the corresponding direction are filled.
+#[cfg(feature = "floats")]
+pub fn olp_reward_per_filled_unit(pool: &FusionPoolFacade) -> (f64, f64) {
+    let reward_rate = |fee_owed: u64, filled: u64| if filled == 0 { 0.0 } else { fee_owed as f64 / filled as f64 };
+    (
+        reward_rate(pool.olp_fee_owed_a, pool.orders_filled_amount_b),
+        reward_rate(pool.olp_fee_owed_b, pool.orders_filled_amount_a),
+    )
+}
+
 #[cfg(all(test, not(feature = "wasm")))]
 mod tests {
     use crate::{
-        decrease_limit_order_quote, limit_order_quote_by_input_token, limit_order_quote_by_output_token, price_to_tick_index,
-        sqrt_price_to_tick_index, FusionPoolFacade, LimitOrderFacade, TickFacade, MAX_CLP_REWARD_RATE,
+        decrease_limit_order_quote, input_to_fill_limit_order, limit_order_quote_by_input_token, limit_order_quote_by_output_token,
+        limit_order_realized_price, limit_order_remaining_input, olp_reward_per_filled_unit, price_to_tick_index, sqrt_price_to_tick_index,
+        tick_index_to_price, FusionPoolFacade, LimitOrderFacade, TickFacade, MAX_CLP_REWARD_RATE,
     };
     const FIFTY_PCT: u16 = 5000;
     const ONE_PCT_FEE_RATE: u16 = 10000;
@@ -207,6 +307,8 @@ mod tests {
 
         assert_eq!(quote.amount_out_a, 25_000);
         assert_eq!(quote.amount_out_b, 0);
+        assert_eq!(quote.principal_returned, 25_000);
+        assert_eq!(quote.proceeds, 0);
     }
 
     #[test]
@@ -244,6 +346,8 @@ mod tests {
         assert_eq!(quote.amount_out_b, 10190);
         assert_eq!(quote.reward_a, 0);
         assert_eq!(quote.reward_b, 62);
+        assert_eq!(quote.principal_returned, quote.amount_out_a);
+        assert_eq!(quote.proceeds, quote.amount_out_b - quote.reward_b);
     }
 
     #[test]
@@ -281,6 +385,8 @@ mod tests {
         assert_eq!(quote.amount_out_b, 15000);
         assert_eq!(quote.reward_a, 62);
         assert_eq!(quote.reward_b, 0);
+        assert_eq!(quote.principal_returned, quote.amount_out_b);
+        assert_eq!(quote.proceeds, quote.amount_out_a - quote.reward_a);
     }
 
     #[test]
@@ -318,6 +424,8 @@ mod tests {
         assert_eq!(quote.amount_out_b, 10178);
         assert_eq!(quote.reward_a, 0);
         assert_eq!(quote.reward_b, 50);
+        assert_eq!(quote.principal_returned, 0);
+        assert_eq!(quote.proceeds, 10128);
     }
 
     #[test]
@@ -357,6 +465,91 @@ mod tests {
         assert_eq!(quote.reward_b, 0);
     }
 
+    #[test]
+    fn test_limit_order_realized_price_not_filled() {
+        let price = limit_order_realized_price(
+            FusionPoolFacade::default(),
+            LimitOrderFacade {
+                tick_index: 128,
+                amount: 50_000,
+                a_to_b: true,
+                age: 5,
+            },
+            TickFacade {
+                age: 5,
+                open_orders_input: 100_000,
+                ..TickFacade::default()
+            },
+            6,
+            6,
+        )
+        .unwrap();
+
+        assert_eq!(price, tick_index_to_price(128, 6, 6));
+    }
+
+    #[test]
+    fn test_limit_order_realized_price_semi_filled() {
+        let fusion_pool = FusionPoolFacade {
+            order_protocol_fee_rate: FIFTY_PCT,
+            orders_filled_amount_a: 80_000,
+            olp_fee_owed_b: 500,
+            ..FusionPoolFacade::default()
+        };
+        let limit_order = LimitOrderFacade {
+            tick_index: 128,
+            amount: 50_000,
+            a_to_b: true,
+            age: 5,
+        };
+        let tick = TickFacade {
+            age: 6,
+            part_filled_orders_input: 200_000,
+            part_filled_orders_remaining_input: 120_000,
+            ..TickFacade::default()
+        };
+
+        let price = limit_order_realized_price(fusion_pool, limit_order, tick, 6, 6).unwrap();
+
+        // 20_000 of 50_000 are filled: 20_257 is received at the tick price plus a reward of 125.
+        assert_eq!(price, 20382.0 / 20000.0);
+        assert!(price > tick_index_to_price(128, 6, 6));
+
+        let price = limit_order_realized_price(fusion_pool, limit_order, tick, 9, 6).unwrap();
+        assert!((price - 20382.0 / 20000.0 * 1000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_limit_order_realized_price_fulfilled() {
+        let price = limit_order_realized_price(
+            FusionPoolFacade {
+                order_protocol_fee_rate: FIFTY_PCT,
+                orders_filled_amount_b: 80_000,
+                olp_fee_owed_a: 500,
+                ..FusionPoolFacade::default()
+            },
+            LimitOrderFacade {
+                tick_index: 128,
+                amount: 100_000,
+                a_to_b: false,
+                age: 5,
+            },
+            TickFacade {
+                age: 7,
+                fulfilled_a_to_b_orders_input: 100_000,
+                fulfilled_b_to_a_orders_input: 80_000,
+                ..TickFacade::default()
+            },
+            6,
+            6,
+        )
+        .unwrap();
+
+        // All 100_000 of token B are sold: 98_728 of token A are received at the tick price plus a reward of 625.
+        assert_eq!(price, 100000.0 / 99353.0);
+        assert!(price < tick_index_to_price(128, 6, 6));
+    }
+
     #[test]
     fn test_limit_order_quote_by_input_token() {
         // zero swap fee
@@ -436,4 +629,97 @@ mod tests {
             10_000
         );
     }
+
+    #[test]
+    fn test_input_to_fill_limit_order() {
+        let limit_order = LimitOrderFacade {
+            tick_index: 0,
+            amount: 1_000_000,
+            a_to_b: true,
+            age: 0,
+        };
+        let tick = TickFacade::default();
+        // 1_000_000 / 0.99 = 1_010_101.01, the fee is rounded up.
+        assert_eq!(input_to_fill_limit_order(limit_order, tick, ONE_PCT_FEE_RATE).unwrap(), 1_010_102);
+        assert_eq!(input_to_fill_limit_order(limit_order, tick, 0).unwrap(), 1_000_000);
+
+        // At the tick price of about 4.0, the order of 1_000_000 token B is consumed by about 250_000 token A plus the fee.
+        let limit_order = LimitOrderFacade {
+            tick_index: price_to_tick_index(4.0, 6, 6),
+            a_to_b: false,
+            ..limit_order
+        };
+        let amount_in = input_to_fill_limit_order(limit_order, tick, ONE_PCT_FEE_RATE).unwrap();
+        assert!((252_520..=252_550).contains(&amount_in));
+    }
+
+    #[test]
+    fn test_input_to_fill_semi_filled_limit_order() {
+        let limit_order = LimitOrderFacade {
+            tick_index: 0,
+            amount: 1_000_000,
+            a_to_b: true,
+            age: 5,
+        };
+        // Half of the orders of the tick are filled.
+        let tick = TickFacade {
+            age: 6,
+            part_filled_orders_input: 2_000_000,
+            part_filled_orders_remaining_input: 1_000_000,
+            ..TickFacade::default()
+        };
+        assert_eq!(limit_order_remaining_input(limit_order, tick).unwrap(), 500_000);
+        // 500_000 / 0.99 = 505_050.51, the fee is rounded up.
+        assert_eq!(input_to_fill_limit_order(limit_order, tick, ONE_PCT_FEE_RATE).unwrap(), 505_051);
+
+        // Nothing is left to fill.
+        let tick = TickFacade { age: 7, ..tick };
+        assert_eq!(limit_order_remaining_input(limit_order, tick).unwrap(), 0);
+        assert_eq!(input_to_fill_limit_order(limit_order, tick, ONE_PCT_FEE_RATE).unwrap(), 0);
+
+        // The order is newer than the tick.
+        let tick = TickFacade { age: 4, ..tick };
+        assert!(input_to_fill_limit_order(limit_order, tick, ONE_PCT_FEE_RATE).is_err());
+    }
+
+    #[test]
+    fn test_olp_reward_per_filled_unit() {
+        assert_eq!(olp_reward_per_filled_unit(&FusionPoolFacade::default()), (0.0, 0.0));
+
+        // The pool states of the partially_decrease_semi_filled_* tests.
+        let pool = FusionPoolFacade {
+            order_protocol_fee_rate: FIFTY_PCT,
+            orders_filled_amount_a: 80_000,
+            olp_fee_owed_b: 500,
+            orders_filled_amount_b: 80_000,
+            olp_fee_owed_a: 500,
+            ..FusionPoolFacade::default()
+        };
+        let (reward_a_per_unit, reward_b_per_unit) = olp_reward_per_filled_unit(&pool);
+        assert_eq!(reward_a_per_unit, 0.00625);
+        assert_eq!(reward_b_per_unit, 0.00625);
+
+        // The orders of these tests have 10_000 filled input units in the decreased amount.
+        let limit_order = LimitOrderFacade {
+            tick_index: 128,
+            amount: 50_000,
+            a_to_b: true,
+            age: 5,
+        };
+        let tick = TickFacade {
+            age: 6,
+            part_filled_orders_input: 200_000,
+            part_filled_orders_remaining_input: 120_000,
+            ..TickFacade::default()
+        };
+        let quote = decrease_limit_order_quote(pool, limit_order, tick, 25_000, None, None).unwrap();
+        assert_eq!(quote.reward_b, (10_000.0 * reward_b_per_unit) as u64);
+
+        let limit_order = LimitOrderFacade {
+            a_to_b: false,
+            ..limit_order
+        };
+        let quote = decrease_limit_order_quote(pool, limit_order, tick, 25_000, None, None).unwrap();
+        assert_eq!(quote.reward_a, (10_000.0 * reward_a_per_unit) as u64);
+    }
 }
@@ -16,7 +16,7 @@ use ethnum::U256;
 use crate::{
     order_tick_indexes, position_status, tick_index_to_sqrt_price, try_apply_transfer_fee, try_get_max_amount_with_slippage_tolerance,
     try_get_min_amount_with_slippage_tolerance, try_reverse_apply_transfer_fee, CoreError, DecreaseLiquidityQuote, IncreaseLiquidityQuote,
-    PositionStatus, TransferFee, AMOUNT_EXCEEDS_MAX_U64, ARITHMETIC_OVERFLOW, U128,
+    PositionStatus, TransferFee, AMOUNT_EXCEEDS_MAX_U64, ARITHMETIC_OVERFLOW, BPS_DENOMINATOR, INVALID_SLIPPAGE_TOLERANCE, U128,
 };
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
@@ -49,6 +49,9 @@ pub fn decrease_liquidity_quote(
     transfer_fee_a: Option<TransferFee>,
     transfer_fee_b: Option<TransferFee>,
 ) -> Result<DecreaseLiquidityQuote, CoreError> {
+    if slippage_tolerance_bps > BPS_DENOMINATOR {
+        return Err(INVALID_SLIPPAGE_TOLERANCE);
+    }
     let liquidity_delta: u128 = liquidity_delta.into();
     if liquidity_delta == 0 {
         return Ok(DecreaseLiquidityQuote::default());
@@ -98,6 +101,9 @@ pub fn decrease_liquidity_quote_a(
     transfer_fee_a: Option<TransferFee>,
     transfer_fee_b: Option<TransferFee>,
 ) -> Result<DecreaseLiquidityQuote, CoreError> {
+    if slippage_tolerance_bps > BPS_DENOMINATOR {
+        return Err(INVALID_SLIPPAGE_TOLERANCE);
+    }
     let tick_range = order_tick_indexes(tick_index_1, tick_index_2);
     let token_delta_a = try_reverse_apply_transfer_fee(token_amount_a, transfer_fee_a.unwrap_or_default())?;
 
@@ -151,6 +157,9 @@ pub fn decrease_liquidity_quote_b(
     transfer_fee_a: Option<TransferFee>,
     transfer_fee_b: Option<TransferFee>,
 ) -> Result<DecreaseLiquidityQuote, CoreError> {
+    if slippage_tolerance_bps > BPS_DENOMINATOR {
+        return Err(INVALID_SLIPPAGE_TOLERANCE);
+    }
     let tick_range = order_tick_indexes(tick_index_1, tick_index_2);
     let token_delta_b = try_reverse_apply_transfer_fee(token_amount_b, transfer_fee_b.unwrap_or_default())?;
 
@@ -204,6 +213,9 @@ pub fn increase_liquidity_quote(
     transfer_fee_a: Option<TransferFee>,
     transfer_fee_b: Option<TransferFee>,
 ) -> Result<IncreaseLiquidityQuote, CoreError> {
+    if slippage_tolerance_bps > BPS_DENOMINATOR {
+        return Err(INVALID_SLIPPAGE_TOLERANCE);
+    }
     let liquidity_delta: u128 = liquidity_delta.into();
     if liquidity_delta == 0 {
         return Ok(IncreaseLiquidityQuote::default());
@@ -253,6 +265,9 @@ pub fn increase_liquidity_quote_a(
     transfer_fee_a: Option<TransferFee>,
     transfer_fee_b: Option<TransferFee>,
 ) -> Result<IncreaseLiquidityQuote, CoreError> {
+    if slippage_tolerance_bps > BPS_DENOMINATOR {
+        return Err(INVALID_SLIPPAGE_TOLERANCE);
+    }
     let tick_range = order_tick_indexes(tick_index_1, tick_index_2);
     let token_delta_a = try_apply_transfer_fee(token_amount_a, transfer_fee_a.unwrap_or_default())?;
 
@@ -306,6 +321,9 @@ pub fn increase_liquidity_quote_b(
     transfer_fee_a: Option<TransferFee>,
     transfer_fee_b: Option<TransferFee>,
 ) -> Result<IncreaseLiquidityQuote, CoreError> {
+    if slippage_tolerance_bps > BPS_DENOMINATOR {
+        return Err(INVALID_SLIPPAGE_TOLERANCE);
+    }
     let tick_range = order_tick_indexes(tick_index_1, tick_index_2);
     let token_delta_b = try_apply_transfer_fee(token_amount_b, transfer_fee_b.unwrap_or_default())?;
 
@@ -888,4 +906,26 @@ mod tests {
         assert_eq!(result.token_max_a, 0);
         assert_eq!(result.token_max_b, 0);
     }
+
+    #[test]
+    fn test_liquidity_quotes_invalid_slippage_tolerance() {
+        let sqrt_price = tick_index_to_sqrt_price(0);
+        assert_eq!(decrease_liquidity_quote(0, 10001, sqrt_price, -10, 10, None, None), Err(INVALID_SLIPPAGE_TOLERANCE));
+        assert_eq!(decrease_liquidity_quote_a(1000, 10001, sqrt_price, -10, 10, None, None), Err(INVALID_SLIPPAGE_TOLERANCE));
+        assert_eq!(decrease_liquidity_quote_b(1000, 10001, sqrt_price, -10, 10, None, None), Err(INVALID_SLIPPAGE_TOLERANCE));
+        assert_eq!(increase_liquidity_quote(0, 10001, sqrt_price, -10, 10, None, None), Err(INVALID_SLIPPAGE_TOLERANCE));
+        assert_eq!(increase_liquidity_quote_a(1000, 10001, sqrt_price, -10, 10, None, None), Err(INVALID_SLIPPAGE_TOLERANCE));
+        assert_eq!(increase_liquidity_quote_b(1000, 10001, sqrt_price, -10, 10, None, None), Err(INVALID_SLIPPAGE_TOLERANCE));
+    }
+
+    #[test]
+    fn test_liquidity_quotes_max_slippage_tolerance() {
+        let sqrt_price = tick_index_to_sqrt_price(0);
+        let decrease = decrease_liquidity_quote(1000000, 10000, sqrt_price, -10, 10, None, None).unwrap();
+        assert_eq!(decrease.token_min_a, 0);
+        assert_eq!(decrease.token_min_b, 0);
+        let increase = increase_liquidity_quote(1000000, 10000, sqrt_price, -10, 10, None, None).unwrap();
+        assert_eq!(increase.token_max_a, increase.token_est_a * 2);
+        assert_eq!(increase.token_max_b, increase.token_est_b * 2);
+    }
 }
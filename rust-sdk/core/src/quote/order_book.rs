@@ -7,8 +7,9 @@
 
 use crate::quote::get_next_liquidity;
 use crate::{
-    get_limit_order_output_amount, price_to_sqrt_price, sqrt_price_to_price, tick_index_to_sqrt_price, CoreError, FusionPoolFacade,
-    TickArraySequenceVec, MAX_SQRT_PRICE, MIN_SQRT_PRICE,
+    get_limit_order_output_amount, price_to_sqrt_price, sqrt_price_to_price, tick_index_to_sqrt_price, try_get_amount_delta_a,
+    try_get_amount_delta_b, try_get_next_sqrt_price_from_a, try_get_next_sqrt_price_from_b, try_mul_div, CoreError, FusionPoolFacade,
+    TickArraySequenceVec, TickFacade, AMOUNT_EXCEEDS_MAX_U64, INVALID_ORDER_BOOK_PARAMETERS, MAX_SQRT_PRICE, MIN_SQRT_PRICE,
 };
 
 #[derive(Debug)]
@@ -49,6 +50,75 @@ pub fn get_order_book_side(
     invert_price: bool,
     decimals_a: u8,
     decimals_b: u8,
+) -> Result<Vec<OrderBookEntry>, CoreError> {
+    get_order_book_side_with_depth_target(fusion_pool, tick_sequence, price_step, max_num_entries, None, invert_price, decimals_a, decimals_b)
+}
+
+/// Calculate order book entries with the provided price step, computing the concentrated liquidity amounts
+/// with exact integer math instead of floating point math.
+///
+/// # Parameters
+/// - `fusion_pool`: The fusion_pool state
+/// - `tick_arrays`: The tick sequence
+/// - `price_step` - The price step of an order book. Should be positive for the BID side of an order book and negative for the ASK side.
+/// - `max_num_entries` - The maximum number of entries.
+/// - `invert_price` - Set to true if the provided price step is for inverted pool price.
+/// - `decimals_a` - The number of decimals of token A.
+/// - `decimals_b` - The number of decimals of token B.
+///
+/// # Returns
+/// - Order book entries for one side of the order book, or `AMOUNT_EXCEEDS_MAX_U64` if an amount between two prices overflows.
+pub fn get_order_book_side_exact(
+    fusion_pool: &FusionPoolFacade,
+    tick_sequence: &TickArraySequenceVec,
+    price_step: f64,
+    max_num_entries: u32,
+    invert_price: bool,
+    decimals_a: u8,
+    decimals_b: u8,
+) -> Result<Vec<OrderBookEntry>, CoreError> {
+    order_book_side(fusion_pool, tick_sequence, price_step, max_num_entries, None, invert_price, decimals_a, decimals_b, true)
+}
+
+/// Calculate order book entries with the provided price step until the cumulative depth target is covered.
+///
+/// # Parameters
+/// - `fusion_pool`: The fusion_pool state
+/// - `tick_arrays`: The tick sequence
+/// - `price_step` - The price step of an order book. Should be positive for the BID side of an order book and negative for the ASK side.
+/// - `max_num_entries` - The maximum number of entries.
+/// - `depth_target` - Stops generating entries once the sum of the concentrated and limit order totals reaches this amount.
+/// - `invert_price` - Set to true if the provided price step is for inverted pool price.
+/// - `decimals_a` - The number of decimals of token A.
+/// - `decimals_b` - The number of decimals of token B.
+///
+/// # Returns
+/// - Order book entries for one side of the order book.
+#[allow(clippy::too_many_arguments)]
+pub fn get_order_book_side_with_depth_target(
+    fusion_pool: &FusionPoolFacade,
+    tick_sequence: &TickArraySequenceVec,
+    price_step: f64,
+    max_num_entries: u32,
+    depth_target: Option<u64>,
+    invert_price: bool,
+    decimals_a: u8,
+    decimals_b: u8,
+) -> Result<Vec<OrderBookEntry>, CoreError> {
+    order_book_side(fusion_pool, tick_sequence, price_step, max_num_entries, depth_target, invert_price, decimals_a, decimals_b, false)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn order_book_side(
+    fusion_pool: &FusionPoolFacade,
+    tick_sequence: &TickArraySequenceVec,
+    price_step: f64,
+    max_num_entries: u32,
+    depth_target: Option<u64>,
+    invert_price: bool,
+    decimals_a: u8,
+    decimals_b: u8,
+    exact_amounts: bool,
 ) -> Result<Vec<OrderBookEntry>, CoreError> {
     let price_step_abs = price_step.abs();
     assert!(price_step_abs >= 0.0000000000001, "price_step is too small");
@@ -86,6 +156,10 @@ pub fn get_order_book_side(
             return Ok(order_book_entries);
         }
 
+        if depth_target.is_some_and(|target| concentrated_total + limit_total >= target) {
+            return Ok(order_book_entries);
+        }
+
         next_order_book_price = (next_order_book_price + price_step).clamp(min_price, max_price);
 
         let next_order_book_sqrt_price = u128::from(price_to_sqrt_price(
@@ -134,8 +208,11 @@ pub fn get_order_book_side(
                 next_order_book_sqrt_price.min(next_tick_sqrt_price)
             };
 
-            let (concentrated_amount_a, concentrated_amount_b) =
-                try_get_amount_delta_a_and_b(current_sqrt_price, next_sqrt_price, current_liquidity)?;
+            let (concentrated_amount_a, concentrated_amount_b) = if exact_amounts {
+                try_get_amount_delta_a_and_b_exact(current_sqrt_price, next_sqrt_price, current_liquidity)?
+            } else {
+                try_get_amount_delta_a_and_b(current_sqrt_price, next_sqrt_price, current_liquidity)?
+            };
 
             // Liquidity token is B if a_to_b = true, A otherwise.
             let (concentrated_amount, concentrated_amount_quote) = if a_to_b {
@@ -181,6 +258,268 @@ pub fn get_order_book_side(
     }
 }
 
+/// Calculate order book entries with the provided size step.
+///
+/// An entry is emitted each time the cumulative concentrated and limit order amount crosses a multiple of `size_step`.
+/// The last entry holds the remaining liquidity of the tick sequence if it doesn't fill the whole step.
+///
+/// # Parameters
+/// - `fusion_pool`: The fusion_pool state
+/// - `tick_arrays`: The tick sequence
+/// - `size_step` - The size step of an order book denominated in the liquidity token (B for the BID side and A for the ASK side).
+/// - `max_num_entries` - The maximum number of entries.
+/// - `a_to_b` - True for the BID side of an order book and false for the ASK side.
+/// - `decimals_a` - The number of decimals of token A.
+/// - `decimals_b` - The number of decimals of token B.
+///
+/// # Returns
+/// - Order book entries for one side of the order book. The price of an entry is the marginal price at the end of the bucket.
+/// - `INVALID_ORDER_BOOK_PARAMETERS` if `size_step` is zero or `max_num_entries` is above 100.
+pub fn get_order_book_by_size(
+    fusion_pool: &FusionPoolFacade,
+    tick_sequence: &TickArraySequenceVec,
+    size_step: u64,
+    max_num_entries: u32,
+    a_to_b: bool,
+    decimals_a: u8,
+    decimals_b: u8,
+) -> Result<Vec<OrderBookEntry>, CoreError> {
+    if size_step == 0 || max_num_entries > 100 {
+        return Err(INVALID_ORDER_BOOK_PARAMETERS);
+    }
+
+    let mut current_sqrt_price = fusion_pool.sqrt_price;
+    let mut current_tick_index = fusion_pool.tick_current_index;
+    let mut current_liquidity = fusion_pool.liquidity;
+
+    let mut next_boundary = size_step;
+    let mut book_entry = new_order_book_entry(0, 0, 0, 0, !a_to_b);
+    let mut order_book_entries: Vec<OrderBookEntry> = vec![];
+
+    while order_book_entries.len() < max_num_entries as usize {
+        let next_tick_result = if a_to_b {
+            tick_sequence.prev_initialized_tick(current_tick_index)
+        } else {
+            tick_sequence.next_initialized_tick(current_tick_index)
+        };
+
+        let (next_tick, next_tick_index) = match next_tick_result {
+            Ok(r) => (r.0, r.1),
+            Err(_) => {
+                if book_entry.concentrated_amount > 0 || book_entry.limit_amount > 0 {
+                    book_entry.price = sqrt_price_to_price(current_sqrt_price.into(), decimals_a, decimals_b);
+                    order_book_entries.push(book_entry);
+                }
+                return Ok(order_book_entries);
+            }
+        };
+
+        let next_tick_sqrt_price: u128 = tick_index_to_sqrt_price(next_tick_index).into();
+
+        // Concentrated liquidity between the current price and the next initialized tick.
+        while current_sqrt_price != next_tick_sqrt_price && order_book_entries.len() < max_num_entries as usize {
+            let (amount, amount_quote) = get_liquidity_amounts(current_sqrt_price, next_tick_sqrt_price, current_liquidity, a_to_b)?;
+            let book_total = book_entry.concentrated_total.saturating_add(book_entry.limit_total);
+
+            if book_total.saturating_add(amount) < next_boundary {
+                add_concentrated_amount(&mut book_entry, amount, amount_quote);
+                current_sqrt_price = next_tick_sqrt_price;
+                break;
+            }
+
+            let remaining_amount = next_boundary - book_total;
+            let boundary_sqrt_price: u128 = if a_to_b {
+                u128::from(try_get_next_sqrt_price_from_b(current_sqrt_price.into(), current_liquidity.into(), remaining_amount, false)?)
+                    .max(next_tick_sqrt_price)
+            } else {
+                u128::from(try_get_next_sqrt_price_from_a(current_sqrt_price.into(), current_liquidity.into(), remaining_amount, false)?)
+                    .min(next_tick_sqrt_price)
+            };
+            let (_, amount_quote) = get_liquidity_amounts(current_sqrt_price, boundary_sqrt_price, current_liquidity, a_to_b)?;
+
+            add_concentrated_amount(&mut book_entry, remaining_amount, amount_quote);
+            current_sqrt_price = boundary_sqrt_price;
+            book_entry.price = sqrt_price_to_price(current_sqrt_price.into(), decimals_a, decimals_b);
+            order_book_entries.push(next_order_book_entry(&mut book_entry));
+            // The book can't hold more than u64::MAX of the liquidity token.
+            let Some(boundary) = next_boundary.checked_add(size_step) else {
+                return Ok(order_book_entries);
+            };
+            next_boundary = boundary;
+        }
+
+        if current_sqrt_price != next_tick_sqrt_price {
+            break;
+        }
+
+        // Limit orders of the next initialized tick.
+        if let Some(tick) = next_tick {
+            let swap_in = tick.open_orders_input.saturating_add(tick.part_filled_orders_remaining_input);
+            let swap_out = if swap_in > 0 {
+                get_limit_order_output_amount(swap_in, !a_to_b, current_sqrt_price, false)?
+            } else {
+                0
+            };
+
+            let mut swap_in_remaining = swap_in;
+            while swap_in_remaining > 0 && order_book_entries.len() < max_num_entries as usize {
+                let book_total = book_entry.concentrated_total.saturating_add(book_entry.limit_total);
+                let amount = swap_in_remaining.min(next_boundary.saturating_sub(book_total));
+                let amount_quote = try_mul_div(swap_out, (swap_in - swap_in_remaining + amount).into(), swap_in.into(), false)?
+                    - try_mul_div(swap_out, (swap_in - swap_in_remaining).into(), swap_in.into(), false)?;
+
+                book_entry.limit_amount = book_entry.limit_amount.saturating_add(amount);
+                book_entry.limit_total = book_entry.limit_total.saturating_add(amount);
+                book_entry.limit_amount_quote = book_entry.limit_amount_quote.saturating_add(amount_quote);
+                book_entry.limit_total_quote = book_entry.limit_total_quote.saturating_add(amount_quote);
+                swap_in_remaining -= amount;
+
+                if book_total.saturating_add(amount) == next_boundary {
+                    book_entry.price = sqrt_price_to_price(current_sqrt_price.into(), decimals_a, decimals_b);
+                    order_book_entries.push(next_order_book_entry(&mut book_entry));
+                    let Some(boundary) = next_boundary.checked_add(size_step) else {
+                        return Ok(order_book_entries);
+                    };
+                    next_boundary = boundary;
+                }
+            }
+        }
+
+        current_liquidity = get_next_liquidity(current_liquidity, next_tick.as_ref(), a_to_b);
+        current_tick_index = if a_to_b { next_tick_index - 1 } else { next_tick_index }
+    }
+
+    Ok(order_book_entries)
+}
+
+/// Calculate the active liquidity of a pool at each initialized tick of the tick sequence.
+///
+/// # Parameters
+/// - `tick_sequence`: The tick sequence
+/// - `fusion_pool`: The fusion_pool state
+///
+/// # Returns
+/// - Pairs of an initialized tick index and the liquidity active from this tick up to the next initialized tick,
+///   sorted by tick index.
+pub fn liquidity_curve(tick_sequence: &TickArraySequenceVec, fusion_pool: &FusionPoolFacade) -> Vec<(i32, u128)> {
+    let mut curve: Vec<(i32, u128)> = vec![];
+
+    // Initialized ticks at or below the current tick, walking down from the current liquidity.
+    let mut current_liquidity = fusion_pool.liquidity;
+    let mut current_tick_index = fusion_pool.tick_current_index;
+    while let Ok((Some(tick), tick_index)) = tick_sequence.prev_initialized_tick(current_tick_index) {
+        curve.push((tick_index, current_liquidity));
+        current_liquidity = get_next_liquidity(current_liquidity, Some(&tick), true);
+        current_tick_index = tick_index - 1;
+    }
+    curve.reverse();
+
+    // Initialized ticks above the current tick, walking up from the current liquidity.
+    let mut current_liquidity = fusion_pool.liquidity;
+    let mut current_tick_index = fusion_pool.tick_current_index;
+    while let Ok((Some(tick), tick_index)) = tick_sequence.next_initialized_tick(current_tick_index) {
+        current_liquidity = get_next_liquidity(current_liquidity, Some(&tick), false);
+        curve.push((tick_index, current_liquidity));
+        current_tick_index = tick_index;
+    }
+
+    curve
+}
+
+/// Calculate the total value locked in a pool, in token units.
+///
+/// The concentrated liquidity amounts are summed between consecutive initialized ticks of the tick sequence:
+/// token B below the current price and token A above it. The remaining input of the resting limit orders
+/// is added on top: token B orders below the current price and token A orders above it.
+/// Liquidity outside the tick sequence is not counted.
+///
+/// # Parameters
+/// - `fusion_pool`: The fusion_pool state
+/// - `tick_sequence`: The tick sequence
+///
+/// # Returns
+/// - The amounts of token A and token B locked in the pool.
+pub fn pool_tvl(fusion_pool: &FusionPoolFacade, tick_sequence: &TickArraySequenceVec) -> (u64, u64) {
+    let resting_input = |tick: &TickFacade| tick.open_orders_input.saturating_add(tick.part_filled_orders_remaining_input);
+
+    // Token B below the current price.
+    let mut amount_b: u64 = 0;
+    let mut current_liquidity = fusion_pool.liquidity;
+    let mut current_sqrt_price = fusion_pool.sqrt_price;
+    let mut current_tick_index = fusion_pool.tick_current_index;
+    while let Ok((Some(tick), tick_index)) = tick_sequence.prev_initialized_tick(current_tick_index) {
+        let tick_sqrt_price: u128 = tick_index_to_sqrt_price(tick_index).into();
+        let (_, concentrated_amount_b) = try_get_amount_delta_a_and_b(current_sqrt_price, tick_sqrt_price, current_liquidity).unwrap_or_default();
+        amount_b = amount_b.saturating_add(concentrated_amount_b).saturating_add(resting_input(&tick));
+        current_liquidity = get_next_liquidity(current_liquidity, Some(&tick), true);
+        current_sqrt_price = tick_sqrt_price;
+        current_tick_index = tick_index - 1;
+    }
+
+    // Token A above the current price.
+    let mut amount_a: u64 = 0;
+    let mut current_liquidity = fusion_pool.liquidity;
+    let mut current_sqrt_price = fusion_pool.sqrt_price;
+    let mut current_tick_index = fusion_pool.tick_current_index;
+    while let Ok((Some(tick), tick_index)) = tick_sequence.next_initialized_tick(current_tick_index) {
+        let tick_sqrt_price: u128 = tick_index_to_sqrt_price(tick_index).into();
+        let (concentrated_amount_a, _) = try_get_amount_delta_a_and_b(current_sqrt_price, tick_sqrt_price, current_liquidity).unwrap_or_default();
+        amount_a = amount_a.saturating_add(concentrated_amount_a).saturating_add(resting_input(&tick));
+        current_liquidity = get_next_liquidity(current_liquidity, Some(&tick), false);
+        current_sqrt_price = tick_sqrt_price;
+        current_tick_index = tick_index;
+    }
+
+    (amount_a, amount_b)
+}
+
+fn new_order_book_entry(
+    concentrated_total: u64,
+    concentrated_total_quote: u64,
+    limit_total: u64,
+    limit_total_quote: u64,
+    ask_side: bool,
+) -> OrderBookEntry {
+    OrderBookEntry {
+        concentrated_amount: 0,
+        concentrated_amount_quote: 0,
+        concentrated_total,
+        concentrated_total_quote,
+        limit_amount: 0,
+        limit_amount_quote: 0,
+        limit_total,
+        limit_total_quote,
+        price: 0.0,
+        ask_side,
+    }
+}
+
+/// Replaces the provided entry with an empty one carrying over the totals and returns the filled entry.
+fn next_order_book_entry(book_entry: &mut OrderBookEntry) -> OrderBookEntry {
+    let next_entry = new_order_book_entry(
+        book_entry.concentrated_total,
+        book_entry.concentrated_total_quote,
+        book_entry.limit_total,
+        book_entry.limit_total_quote,
+        book_entry.ask_side,
+    );
+    std::mem::replace(book_entry, next_entry)
+}
+
+fn add_concentrated_amount(book_entry: &mut OrderBookEntry, amount: u64, amount_quote: u64) {
+    book_entry.concentrated_amount = book_entry.concentrated_amount.saturating_add(amount);
+    book_entry.concentrated_amount_quote = book_entry.concentrated_amount_quote.saturating_add(amount_quote);
+    book_entry.concentrated_total = book_entry.concentrated_total.saturating_add(amount);
+    book_entry.concentrated_total_quote = book_entry.concentrated_total_quote.saturating_add(amount_quote);
+}
+
+/// Returns the liquidity token amount and its quote amount between two prices.
+/// Liquidity token is B if a_to_b = true, A otherwise.
+fn get_liquidity_amounts(sqrt_price_1: u128, sqrt_price_2: u128, liquidity: u128, a_to_b: bool) -> Result<(u64, u64), CoreError> {
+    let (amount_a, amount_b) = try_get_amount_delta_a_and_b(sqrt_price_1, sqrt_price_2, liquidity)?;
+    Ok(if a_to_b { (amount_b, amount_a) } else { (amount_a, amount_b) })
+}
+
 const Q64_RESOLUTION: f64 = 18446744073709551616.0;
 
 pub fn try_get_amount_delta_a_and_b(sqrt_price_1_x64: u128, sqrt_price_2_x64: u128, liquidity: u128) -> Result<(u64, u64), CoreError> {
@@ -208,11 +547,65 @@ pub fn try_get_amount_delta_a_and_b(sqrt_price_1_x64: u128, sqrt_price_2_x64: u1
     Ok((a_u64, b_u64))
 }
 
+/// Calculate the token amounts between two sqrt prices with the integer math used by swaps.
+/// Unlike `try_get_amount_delta_a_and_b`, amounts that don't fit into a u64 integer are reported instead of saturating.
+///
+/// # Parameters
+/// - `sqrt_price_1_x64`: The first square root price
+/// - `sqrt_price_2_x64`: The second square root price
+/// - `liquidity`: The liquidity
+///
+/// # Returns
+/// - The token A and token B amounts rounded down, or `AMOUNT_EXCEEDS_MAX_U64` if an amount overflows.
+pub fn try_get_amount_delta_a_and_b_exact(sqrt_price_1_x64: u128, sqrt_price_2_x64: u128, liquidity: u128) -> Result<(u64, u64), CoreError> {
+    let amount_a =
+        try_get_amount_delta_a(sqrt_price_1_x64.into(), sqrt_price_2_x64.into(), liquidity.into(), false).map_err(|_| AMOUNT_EXCEEDS_MAX_U64)?;
+    let amount_b =
+        try_get_amount_delta_b(sqrt_price_1_x64.into(), sqrt_price_2_x64.into(), liquidity.into(), false).map_err(|_| AMOUNT_EXCEEDS_MAX_U64)?;
+    Ok((amount_a, amount_b))
+}
+
+/// Returns the filled fractions of the limit order volume resting in a pool.
+///
+/// # Parameters
+/// - `pool`: The fusion_pool state
+///
+/// # Returns
+/// - The filled fraction of the token A orders (`orders_filled_amount_a / orders_total_amount_a`) and
+///   of the token B orders (`orders_filled_amount_b / orders_total_amount_b`). A side without orders has a fraction of 0.0.
+#[cfg(feature = "floats")]
+pub fn pool_order_fill_progress(pool: &FusionPoolFacade) -> (f64, f64) {
+    let fill_progress = |filled: u64, total: u64| if total == 0 { 0.0 } else { filled as f64 / total as f64 };
+    (
+        fill_progress(pool.orders_filled_amount_a, pool.orders_total_amount_a),
+        fill_progress(pool.orders_filled_amount_b, pool.orders_total_amount_b),
+    )
+}
+
+/// Returns the spread between the best ask and the best bid of an order book.
+///
+/// # Parameters
+/// - `bids`: The BID side entries of the order book
+/// - `asks`: The ASK side entries of the order book
+///
+/// # Returns
+/// - The lowest ask price minus the highest bid price. Entries without liquidity are ignored.
+///   Returns `None` if either side has no liquidity.
+#[cfg(feature = "floats")]
+pub fn order_book_spread(bids: &[OrderBookEntry], asks: &[OrderBookEntry]) -> Option<f64> {
+    let has_liquidity = |entry: &&OrderBookEntry| entry.concentrated_amount > 0 || entry.limit_amount > 0;
+    let best_bid = bids.iter().filter(has_liquidity).map(|entry| entry.price).reduce(f64::max)?;
+    let best_ask = asks.iter().filter(has_liquidity).map(|entry| entry.price).reduce(f64::min)?;
+    Some(best_ask - best_bid)
+}
+
 #[cfg(all(test, not(feature = "wasm")))]
 mod order_book_tests {
     use crate::{
-        get_order_book_side, increase_liquidity_quote_a, increase_liquidity_quote_b, price_to_sqrt_price, sqrt_price_to_tick_index, FusionPoolFacade,
-        TickArrayFacade, TickArraySequenceVec, TickFacade, TICK_ARRAY_SIZE,
+        get_order_book_by_size, get_order_book_side, get_order_book_side_exact, get_order_book_side_with_depth_target, increase_liquidity_quote_a,
+        increase_liquidity_quote_b, liquidity_curve, order_book_spread, pool_order_fill_progress, pool_tvl, price_to_sqrt_price,
+        sqrt_price_to_tick_index, tick_index_to_sqrt_price, try_get_amount_delta_a_and_b, try_get_amount_delta_a_and_b_exact, FusionPoolFacade,
+        TickArrayFacade, TickArraySequenceVec, TickFacade, AMOUNT_EXCEEDS_MAX_U64, INVALID_ORDER_BOOK_PARAMETERS, TICK_ARRAY_SIZE,
     };
 
     fn test_fusion_pool(sqrt_price: u128) -> FusionPoolFacade {
@@ -265,6 +658,99 @@ mod order_book_tests {
         ]
     }
 
+    #[test]
+    fn test_pool_order_fill_progress() {
+        let pool = FusionPoolFacade {
+            orders_total_amount_a: 1_000_000,
+            orders_filled_amount_a: 250_000,
+            orders_total_amount_b: 400,
+            orders_filled_amount_b: 400,
+            ..FusionPoolFacade::default()
+        };
+        assert_eq!(pool_order_fill_progress(&pool), (0.25, 1.0));
+        assert_eq!(pool_order_fill_progress(&FusionPoolFacade::default()), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_order_book_spread() {
+        let fusion_pool = test_fusion_pool(1 << 64);
+        let mut tick_arrays = test_tick_arrays();
+
+        let ask_liquidity = increase_liquidity_quote_a(1_000_000, 0, fusion_pool.sqrt_price.into(), 150, 300, None, None).unwrap();
+        tick_arrays[2].ticks[75].liquidity_net = ask_liquidity.liquidity_delta as i128;
+        tick_arrays[2].ticks[75].initialized = true;
+        tick_arrays[3].ticks[62].liquidity_net = -(ask_liquidity.liquidity_delta as i128);
+        tick_arrays[3].ticks[62].initialized = true;
+
+        let bid_liquidity = increase_liquidity_quote_b(1_000_000, 0, fusion_pool.sqrt_price.into(), -300, -150, None, None).unwrap();
+        tick_arrays[0].ticks[26].liquidity_net = bid_liquidity.liquidity_delta as i128;
+        tick_arrays[0].ticks[26].initialized = true;
+        tick_arrays[1].ticks[13].liquidity_net = -(bid_liquidity.liquidity_delta as i128);
+        tick_arrays[1].ticks[13].initialized = true;
+        let tick_sequence = TickArraySequenceVec::new(tick_arrays, fusion_pool.tick_spacing).unwrap();
+
+        let asks = get_order_book_side(&fusion_pool, &tick_sequence, 0.01, 100, false, 6, 6).unwrap();
+        let bids = get_order_book_side(&fusion_pool, &tick_sequence, -0.01, 100, false, 6, 6).unwrap();
+
+        // The buckets closest to the current price are empty, the liquidity starts at 1.02 and 0.98.
+        assert_eq!(asks[0].concentrated_amount, 0);
+        assert_eq!(bids[0].concentrated_amount, 0);
+        assert!((order_book_spread(&bids, &asks).unwrap() - 0.04).abs() < 1e-9);
+
+        assert_eq!(order_book_spread(&[], &asks), None);
+        assert_eq!(order_book_spread(&bids, &[]), None);
+    }
+
+    #[test]
+    fn test_liquidity_curve() {
+        let mut fusion_pool = test_fusion_pool(1 << 64);
+        let mut tick_arrays = test_tick_arrays();
+        let liquidity = increase_liquidity_quote_a(1_000_000, 0, fusion_pool.sqrt_price.into(), -100, 150, None, None)
+            .unwrap()
+            .liquidity_delta;
+        tick_arrays[1].ticks[38].liquidity_net = liquidity as i128;
+        tick_arrays[1].ticks[38].initialized = true;
+        tick_arrays[2].ticks[75].liquidity_net = -(liquidity as i128);
+        tick_arrays[2].ticks[75].initialized = true;
+        let tick_sequence = TickArraySequenceVec::new(tick_arrays, fusion_pool.tick_spacing).unwrap();
+
+        // The current price is in the position range.
+        fusion_pool.liquidity = liquidity;
+        assert_eq!(liquidity_curve(&tick_sequence, &fusion_pool), vec![(-100, liquidity), (150, 0)]);
+
+        // The current price is below the position range.
+        let mut fusion_pool = test_fusion_pool(price_to_sqrt_price(0.98, 6, 6));
+        fusion_pool.liquidity = 0;
+        assert_eq!(liquidity_curve(&tick_sequence, &fusion_pool), vec![(-100, liquidity), (150, 0)]);
+
+        assert_eq!(liquidity_curve(&TickArraySequenceVec::new(test_tick_arrays(), 2).unwrap(), &fusion_pool), vec![]);
+    }
+
+    #[test]
+    fn test_pool_tvl() {
+        let mut fusion_pool = test_fusion_pool(1 << 64);
+        let mut tick_arrays = test_tick_arrays();
+        let quote = increase_liquidity_quote_a(1_000_000, 0, fusion_pool.sqrt_price.into(), -100, 150, None, None).unwrap();
+        tick_arrays[1].ticks[38].liquidity_net = quote.liquidity_delta as i128;
+        tick_arrays[1].ticks[38].initialized = true;
+        tick_arrays[2].ticks[75].liquidity_net = -(quote.liquidity_delta as i128);
+        tick_arrays[2].ticks[75].initialized = true;
+        fusion_pool.liquidity = quote.liquidity_delta;
+
+        // A token A order at tick 200 and a partially filled token B order at tick -200.
+        tick_arrays[3].ticks[12].open_orders_input = 5_000;
+        tick_arrays[3].ticks[12].initialized = true;
+        tick_arrays[0].ticks[76].part_filled_orders_remaining_input = 7_000;
+        tick_arrays[0].ticks[76].initialized = true;
+        let tick_sequence = TickArraySequenceVec::new(tick_arrays, fusion_pool.tick_spacing).unwrap();
+
+        let (amount_a, amount_b) = pool_tvl(&fusion_pool, &tick_sequence);
+        assert!(amount_a.abs_diff(quote.token_est_a + 5_000) <= 1);
+        assert!(amount_b.abs_diff(quote.token_est_b + 7_000) <= 1);
+
+        assert_eq!(pool_tvl(&test_fusion_pool(1 << 64), &TickArraySequenceVec::new(test_tick_arrays(), 2).unwrap()), (0, 0));
+    }
+
     #[test]
     fn test_order_book_ask_side() {
         let fusion_pool = test_fusion_pool(1 << 64);
@@ -328,6 +814,74 @@ mod order_book_tests {
         assert_eq!(order_book[5].limit_amount_quote, 210801);
     }
 
+    #[test]
+    fn test_order_book_ask_side_with_depth_target() {
+        let fusion_pool = test_fusion_pool(1 << 64);
+        let mut tick_arrays = test_tick_arrays();
+
+        let result = increase_liquidity_quote_a(1_000_000, 0, fusion_pool.sqrt_price.into(), 150, 300, None, None).unwrap();
+        tick_arrays[2].ticks[75].liquidity_net = result.liquidity_delta as i128;
+        tick_arrays[2].ticks[75].initialized = true;
+        tick_arrays[3].ticks[62].liquidity_net = -(result.liquidity_delta as i128);
+        tick_arrays[3].ticks[62].initialized = true;
+        tick_arrays[4].ticks[87].open_orders_input = 100_000;
+        tick_arrays[4].ticks[87].part_filled_orders_remaining_input = 100_000;
+        tick_arrays[4].ticks[87].initialized = true;
+        let tick_sequence = TickArraySequenceVec::new(tick_arrays, fusion_pool.tick_spacing).unwrap();
+
+        // The cumulative depth is 970791 after the third entry.
+        let order_book = get_order_book_side_with_depth_target(&fusion_pool, &tick_sequence, 0.01, 100, Some(950_000), false, 6, 6).unwrap();
+        assert_eq!(order_book.len(), 3);
+        assert_eq!(order_book[2].concentrated_total + order_book[2].limit_total, 970791);
+
+        let order_book = get_order_book_side_with_depth_target(&fusion_pool, &tick_sequence, 0.01, 100, Some(10_000_000), false, 6, 6).unwrap();
+        assert_eq!(order_book.len(), 6);
+
+        let order_book = get_order_book_side_with_depth_target(&fusion_pool, &tick_sequence, 0.01, 100, None, false, 6, 6).unwrap();
+        assert_eq!(order_book.len(), 6);
+    }
+
+    #[test]
+    fn test_amount_delta_a_and_b_exact() {
+        let sqrt_price_1: u128 = tick_index_to_sqrt_price(0).into();
+        let sqrt_price_2: u128 = tick_index_to_sqrt_price(1000).into();
+        let liquidity = 1_000_000_000_000;
+
+        let (amount_a, amount_b) = try_get_amount_delta_a_and_b_exact(sqrt_price_1, sqrt_price_2, liquidity).unwrap();
+        let (float_amount_a, float_amount_b) = try_get_amount_delta_a_and_b(sqrt_price_1, sqrt_price_2, liquidity).unwrap();
+        assert_eq!(amount_a, 48768197581);
+        assert_eq!(amount_b, 51268468376);
+        assert!(amount_a.abs_diff(float_amount_a) <= 1);
+        assert!(amount_b.abs_diff(float_amount_b) <= 1);
+        assert_eq!(try_get_amount_delta_a_and_b_exact(sqrt_price_2, sqrt_price_1, liquidity), Ok((amount_a, amount_b)));
+
+        // The float version saturates while the exact one reports the overflow.
+        assert_eq!(try_get_amount_delta_a_and_b(sqrt_price_1, sqrt_price_2, u128::MAX), Ok((u64::MAX, u64::MAX)));
+        assert_eq!(try_get_amount_delta_a_and_b_exact(sqrt_price_1, sqrt_price_2, u128::MAX), Err(AMOUNT_EXCEEDS_MAX_U64));
+    }
+
+    #[test]
+    fn test_order_book_ask_side_exact() {
+        let fusion_pool = test_fusion_pool(1 << 64);
+        let mut tick_arrays = test_tick_arrays();
+
+        let result = increase_liquidity_quote_a(1_000_000, 0, fusion_pool.sqrt_price.into(), 150, 300, None, None).unwrap();
+        tick_arrays[2].ticks[75].liquidity_net = result.liquidity_delta as i128;
+        tick_arrays[2].ticks[75].initialized = true;
+        tick_arrays[3].ticks[62].liquidity_net = -(result.liquidity_delta as i128);
+        tick_arrays[3].ticks[62].initialized = true;
+        let tick_sequence = TickArraySequenceVec::new(tick_arrays, fusion_pool.tick_spacing).unwrap();
+
+        let order_book = get_order_book_side(&fusion_pool, &tick_sequence, 0.01, 100, false, 6, 6).unwrap();
+        let exact_order_book = get_order_book_side_exact(&fusion_pool, &tick_sequence, 0.01, 100, false, 6, 6).unwrap();
+        assert_eq!(exact_order_book.len(), order_book.len());
+        for (exact_entry, entry) in exact_order_book.iter().zip(&order_book) {
+            assert_eq!(exact_entry.price, entry.price);
+            assert!(exact_entry.concentrated_amount.abs_diff(entry.concentrated_amount) <= 2);
+            assert!(exact_entry.concentrated_amount_quote.abs_diff(entry.concentrated_amount_quote) <= 2);
+        }
+    }
+
     #[test]
     fn test_order_book_ask_side_with_all_initialized_ticks() {
         let fusion_pool = test_fusion_pool(1 << 64);
@@ -388,6 +942,91 @@ mod order_book_tests {
         assert_eq!(order_book[5].limit_amount_quote, 210801);
     }
 
+    #[test]
+    fn test_order_book_by_size_ask_side() {
+        let fusion_pool = test_fusion_pool(1 << 64);
+        let mut tick_arrays = test_tick_arrays();
+
+        let result = increase_liquidity_quote_a(1_000_000, 0, fusion_pool.sqrt_price.into(), 150, 300, None, None).unwrap();
+        tick_arrays[2].ticks[75].liquidity_net = result.liquidity_delta as i128;
+        tick_arrays[2].ticks[75].initialized = true;
+        tick_arrays[3].ticks[62].liquidity_net = -(result.liquidity_delta as i128);
+        tick_arrays[3].ticks[62].initialized = true;
+        tick_arrays[4].ticks[87].open_orders_input = 100_000;
+        tick_arrays[4].ticks[87].part_filled_orders_remaining_input = 100_000;
+        tick_arrays[4].ticks[87].initialized = true;
+        let tick_sequence = TickArraySequenceVec::new(tick_arrays, fusion_pool.tick_spacing).unwrap();
+
+        let order_book_by_price = get_order_book_side(&fusion_pool, &tick_sequence, 0.01, 100, false, 6, 6).unwrap();
+        let order_book_by_size = get_order_book_by_size(&fusion_pool, &tick_sequence, 150_000, 100, false, 6, 6).unwrap();
+
+        assert_eq!(order_book_by_size.len(), 8);
+
+        let mut price = 1.0;
+        let mut concentrated_total = 0;
+        let mut limit_total = 0;
+        for entry in &order_book_by_size[..7] {
+            assert_eq!(entry.concentrated_amount + entry.limit_amount, 150_000);
+            assert!(entry.price > price);
+            assert!(entry.ask_side);
+            price = entry.price;
+            concentrated_total += entry.concentrated_amount;
+            limit_total += entry.limit_amount;
+            assert_eq!(entry.concentrated_total, concentrated_total);
+            assert_eq!(entry.limit_total, limit_total);
+        }
+
+        let last_by_price = order_book_by_price.last().unwrap();
+        let last_by_size = order_book_by_size.last().unwrap();
+        assert!(last_by_size.concentrated_total.abs_diff(last_by_price.concentrated_total) < 10);
+        assert!(last_by_size.concentrated_total_quote.abs_diff(last_by_price.concentrated_total_quote) < 10);
+        assert_eq!(last_by_size.limit_total, last_by_price.limit_total);
+        assert_eq!(last_by_size.limit_total_quote, last_by_price.limit_total_quote);
+    }
+
+    #[test]
+    fn test_order_book_by_size_bid_side() {
+        let fusion_pool = test_fusion_pool(1 << 64);
+        let mut tick_arrays = test_tick_arrays();
+
+        let result = increase_liquidity_quote_b(1_000_000, 0, fusion_pool.sqrt_price.into(), -300, -150, None, None).unwrap();
+        tick_arrays[0].ticks[0].open_orders_input = 100_000;
+        tick_arrays[0].ticks[0].part_filled_orders_remaining_input = 100_000;
+        tick_arrays[0].ticks[0].initialized = true;
+        tick_arrays[0].ticks[26].liquidity_net = result.liquidity_delta as i128;
+        tick_arrays[0].ticks[26].initialized = true;
+        tick_arrays[1].ticks[13].liquidity_net = -(result.liquidity_delta as i128);
+        tick_arrays[1].ticks[13].initialized = true;
+        let tick_sequence = TickArraySequenceVec::new(tick_arrays, fusion_pool.tick_spacing).unwrap();
+
+        let order_book_by_price = get_order_book_side(&fusion_pool, &tick_sequence, -0.01, 100, false, 6, 6).unwrap();
+        let order_book_by_size = get_order_book_by_size(&fusion_pool, &tick_sequence, 100_000, 100, true, 6, 6).unwrap();
+
+        assert_eq!(order_book_by_size.len(), 12);
+
+        let mut price = 1.0;
+        for entry in &order_book_by_size[..11] {
+            assert_eq!(entry.concentrated_amount + entry.limit_amount, 100_000);
+            assert!(entry.price <= price);
+            assert!(!entry.ask_side);
+            price = entry.price;
+        }
+
+        let last_by_price = order_book_by_price.last().unwrap();
+        let last_by_size = order_book_by_size.last().unwrap();
+        assert!(last_by_size.concentrated_total.abs_diff(last_by_price.concentrated_total) < 10);
+        assert_eq!(last_by_size.limit_total, last_by_price.limit_total);
+        assert_eq!(last_by_size.limit_total_quote, last_by_price.limit_total_quote);
+
+        let order_book_by_size = get_order_book_by_size(&fusion_pool, &tick_sequence, 100_000, 3, true, 6, 6).unwrap();
+        assert_eq!(order_book_by_size.len(), 3);
+
+        let zero_step = get_order_book_by_size(&fusion_pool, &tick_sequence, 0, 3, true, 6, 6);
+        let too_many_entries = get_order_book_by_size(&fusion_pool, &tick_sequence, 100_000, 101, true, 6, 6);
+        assert_eq!(zero_step.unwrap_err(), INVALID_ORDER_BOOK_PARAMETERS);
+        assert_eq!(too_many_entries.unwrap_err(), INVALID_ORDER_BOOK_PARAMETERS);
+    }
+
     #[test]
     fn test_order_book_bid_side() {
         let fusion_pool = test_fusion_pool(1 << 64);
@@ -9,14 +9,19 @@
 //
 
 use crate::{
-    get_limit_order_output_amount, sqrt_price_to_tick_index, tick_index_to_sqrt_price, try_apply_swap_fee, try_apply_transfer_fee,
-    try_get_amount_delta_a, try_get_amount_delta_b, try_get_max_amount_with_slippage_tolerance, try_get_min_amount_with_slippage_tolerance,
-    try_get_next_sqrt_price_from_a, try_get_next_sqrt_price_from_b, try_mul_div, try_reverse_apply_swap_fee, try_reverse_apply_transfer_fee,
-    CoreError, ExactInSwapQuote, ExactOutSwapQuote, FusionPoolFacade, TickArraySequence, TickArrays, TickFacade, TransferFee, AMOUNT_EXCEEDS_MAX_U64,
-    ARITHMETIC_OVERFLOW, FEE_RATE_MUL_VALUE, INVALID_SQRT_PRICE_LIMIT_DIRECTION, MAX_SQRT_PRICE, MIN_SQRT_PRICE, SQRT_PRICE_LIMIT_OUT_OF_BOUNDS,
-    ZERO_TRADABLE_AMOUNT,
+    get_limit_order_output_amount, get_tick_array_start_tick_index, sqrt_price_to_tick_index, tick_index_to_sqrt_price, try_apply_swap_fee,
+    try_apply_transfer_fee, try_get_amount_delta_a, try_get_amount_delta_b, try_get_max_amount_with_slippage_tolerance,
+    try_get_min_amount_with_slippage_tolerance, try_get_next_sqrt_price_from_a, try_get_next_sqrt_price_from_b, try_mul_div,
+    try_reverse_apply_swap_fee, try_reverse_apply_transfer_fee, CoreError, ExactInSwapQuote, ExactOutSwapQuote, FusionPoolFacade, SwapDirection,
+    TickArrayFacade, TickArraySequence, TickArraySequenceVec, TickArrays, TickFacade, TransferFee, TwoHopExactInSwapQuote, TwoHopExactOutSwapQuote,
+    AMOUNT_EXCEEDS_MAX_U64, ARITHMETIC_OVERFLOW, BPS_DENOMINATOR, FEE_RATE_MUL_VALUE, INSUFFICIENT_LIQUIDITY, INVALID_SLIPPAGE_TOLERANCE,
+    INVALID_SQRT_PRICE_LIMIT_DIRECTION, INVALID_TICK_SPACING, MAX_CLP_REWARD_RATE, MAX_SQRT_PRICE, MAX_SWAP_TICK_ARRAYS, MAX_TICK_INDEX,
+    MIN_SQRT_PRICE, MIN_TICK_INDEX, PROTOCOL_FEE_RATE_MUL_VALUE, SQRT_PRICE_LIMIT_OUT_OF_BOUNDS, SWAP_INPUT_ABOVE_MAXIMUM, SWAP_OUTPUT_BELOW_MINIMUM,
+    TICK_ARRAY_SIZE, TICK_INDEX_OUT_OF_BOUNDS, ZERO_TRADABLE_AMOUNT,
 };
 
+use ethnum::U256;
+
 #[cfg(feature = "wasm")]
 use fusionamm_macros::wasm_expose;
 
@@ -43,6 +48,60 @@ pub fn swap_quote_by_input_token(
     transfer_fee_a: Option<TransferFee>,
     transfer_fee_b: Option<TransferFee>,
 ) -> Result<ExactInSwapQuote, CoreError> {
+    swap_quote_by_input_token_with_direction(
+        token_in,
+        specified_token_a.into(),
+        slippage_tolerance_bps,
+        fusion_pool,
+        tick_arrays,
+        transfer_fee_a,
+        transfer_fee_b,
+    )
+}
+
+/// Computes the output amount of a swap for an exact input amount.
+///
+/// # Arguments
+/// - `token_in`: The input token amount.
+/// - `direction`: The swap direction. The input token is token A for `AtoB` and token B for `BtoA`.
+/// - `slippage_tolerance`: The slippage tolerance in basis points.
+/// - `fusion_pool`: The fusion_pool state.
+/// - `tick_arrays`: The tick arrays needed for the swap.
+/// - `transfer_fee_a`: The transfer fee for token A.
+/// - `transfer_fee_b`: The transfer fee for token B.
+///
+/// # Returns
+/// The exact input swap quote.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn swap_quote_by_input_token_with_direction(
+    token_in: u64,
+    direction: SwapDirection,
+    slippage_tolerance_bps: u16,
+    fusion_pool: FusionPoolFacade,
+    tick_arrays: TickArrays,
+    transfer_fee_a: Option<TransferFee>,
+    transfer_fee_b: Option<TransferFee>,
+) -> Result<ExactInSwapQuote, CoreError> {
+    swap_quote_by_input_token_in_arrays(token_in, direction, slippage_tolerance_bps, fusion_pool, tick_arrays, transfer_fee_a, transfer_fee_b, false)
+}
+
+/// Same as `swap_quote_by_input_token_with_direction`. If `within_arrays` is set, the swap stops at the last tick
+/// boundary covered by the tick arrays instead of failing, so only a part of the input may be consumed.
+#[allow(clippy::too_many_arguments)]
+fn swap_quote_by_input_token_in_arrays(
+    token_in: u64,
+    direction: SwapDirection,
+    slippage_tolerance_bps: u16,
+    fusion_pool: FusionPoolFacade,
+    tick_arrays: TickArrays,
+    transfer_fee_a: Option<TransferFee>,
+    transfer_fee_b: Option<TransferFee>,
+    within_arrays: bool,
+) -> Result<ExactInSwapQuote, CoreError> {
+    let specified_token_a = bool::from(direction);
+    if slippage_tolerance_bps > BPS_DENOMINATOR {
+        return Err(INVALID_SLIPPAGE_TOLERANCE);
+    }
     let (transfer_fee_in, transfer_fee_out) = if specified_token_a {
         (transfer_fee_a, transfer_fee_b)
     } else {
@@ -51,8 +110,13 @@ pub fn swap_quote_by_input_token(
     let token_in_after_fee = try_apply_transfer_fee(token_in.into(), transfer_fee_in.unwrap_or_default())?;
 
     let tick_sequence = TickArraySequence::new(tick_arrays.into(), fusion_pool.tick_spacing)?;
+    let sqrt_price_limit = if within_arrays {
+        tick_sequence_boundary_sqrt_price(&fusion_pool, &tick_sequence, specified_token_a).ok_or(INSUFFICIENT_LIQUIDITY)?
+    } else {
+        0
+    };
 
-    let swap_result = compute_swap(token_in_after_fee.into(), 0, fusion_pool, tick_sequence, specified_token_a, true)?;
+    let swap_result = compute_swap(token_in_after_fee, sqrt_price_limit, fusion_pool, tick_sequence, specified_token_a, true)?;
 
     let (token_in_after_fees, token_est_out_before_fee) = if specified_token_a {
         (swap_result.token_a, swap_result.token_b)
@@ -72,6 +136,10 @@ pub fn swap_quote_by_input_token(
         token_min_out,
         trade_fee: swap_result.fee_amount,
         next_sqrt_price: swap_result.next_sqrt_price,
+        amount_out_from_limit_orders: swap_result.amount_out_from_limit_orders,
+        amount_out_from_clp: swap_result.amount_out_from_clp,
+        clp_reward: swap_result.clp_reward,
+        olp_reward: swap_result.olp_reward,
     })
 }
 
@@ -98,6 +166,69 @@ pub fn swap_quote_by_output_token(
     transfer_fee_a: Option<TransferFee>,
     transfer_fee_b: Option<TransferFee>,
 ) -> Result<ExactOutSwapQuote, CoreError> {
+    swap_quote_by_output_token_with_direction(
+        token_out,
+        (!specified_token_a).into(),
+        slippage_tolerance_bps,
+        fusion_pool,
+        tick_arrays,
+        transfer_fee_a,
+        transfer_fee_b,
+    )
+}
+
+/// Computes the input amount of a swap for an exact output amount.
+///
+/// # Arguments
+/// - `token_out`: The output token amount.
+/// - `direction`: The swap direction. The output token is token B for `AtoB` and token A for `BtoA`.
+/// - `slippage_tolerance`: The slippage tolerance in basis points.
+/// - `fusion_pool`: The fusion_pool state.
+/// - `tick_arrays`: The tick arrays needed for the swap.
+/// - `transfer_fee_a`: The transfer fee for token A.
+/// - `transfer_fee_b`: The transfer fee for token B.
+///
+/// # Returns
+/// The exact output swap quote.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn swap_quote_by_output_token_with_direction(
+    token_out: u64,
+    direction: SwapDirection,
+    slippage_tolerance_bps: u16,
+    fusion_pool: FusionPoolFacade,
+    tick_arrays: TickArrays,
+    transfer_fee_a: Option<TransferFee>,
+    transfer_fee_b: Option<TransferFee>,
+) -> Result<ExactOutSwapQuote, CoreError> {
+    swap_quote_by_output_token_in_arrays(
+        token_out,
+        direction,
+        slippage_tolerance_bps,
+        fusion_pool,
+        tick_arrays,
+        transfer_fee_a,
+        transfer_fee_b,
+        false,
+    )
+}
+
+/// Same as `swap_quote_by_output_token_with_direction`. If `within_arrays` is set, the swap stops at the last tick
+/// boundary covered by the tick arrays instead of failing, so the output may be lower than requested.
+#[allow(clippy::too_many_arguments)]
+fn swap_quote_by_output_token_in_arrays(
+    token_out: u64,
+    direction: SwapDirection,
+    slippage_tolerance_bps: u16,
+    fusion_pool: FusionPoolFacade,
+    tick_arrays: TickArrays,
+    transfer_fee_a: Option<TransferFee>,
+    transfer_fee_b: Option<TransferFee>,
+    within_arrays: bool,
+) -> Result<ExactOutSwapQuote, CoreError> {
+    let specified_token_a = direction == SwapDirection::BtoA;
+    if slippage_tolerance_bps > BPS_DENOMINATOR {
+        return Err(INVALID_SLIPPAGE_TOLERANCE);
+    }
     let (transfer_fee_in, transfer_fee_out) = if specified_token_a {
         (transfer_fee_b, transfer_fee_a)
     } else {
@@ -106,8 +237,13 @@ pub fn swap_quote_by_output_token(
     let token_out_before_fee = try_reverse_apply_transfer_fee(token_out, transfer_fee_out.unwrap_or_default())?;
 
     let tick_sequence = TickArraySequence::new(tick_arrays.into(), fusion_pool.tick_spacing)?;
+    let sqrt_price_limit = if within_arrays {
+        tick_sequence_boundary_sqrt_price(&fusion_pool, &tick_sequence, !specified_token_a).ok_or(INSUFFICIENT_LIQUIDITY)?
+    } else {
+        0
+    };
 
-    let swap_result = compute_swap(token_out_before_fee.into(), 0, fusion_pool, tick_sequence, !specified_token_a, false)?;
+    let swap_result = compute_swap(token_out_before_fee, sqrt_price_limit, fusion_pool, tick_sequence, !specified_token_a, false)?;
 
     let (token_out_before_fee, token_est_in_after_fee) = if specified_token_a {
         (swap_result.token_a, swap_result.token_b)
@@ -130,11 +266,495 @@ pub fn swap_quote_by_output_token(
     })
 }
 
+/// Checks that the estimated output of a swap quote is at least `min_out`.
+///
+/// Use it to verify a freshly computed quote against a threshold before sending the swap.
+///
+/// # Arguments
+/// - `quote`: The exact input swap quote.
+/// - `min_out`: The minimum acceptable output amount.
+///
+/// # Returns
+/// `SWAP_OUTPUT_BELOW_MINIMUM` if the estimated output is lower than `min_out`.
+pub fn assert_min_out(quote: &ExactInSwapQuote, min_out: u64) -> Result<(), CoreError> {
+    if quote.token_est_out < min_out {
+        return Err(SWAP_OUTPUT_BELOW_MINIMUM);
+    }
+    Ok(())
+}
+
+/// Checks that the estimated input of a swap quote is at most `max_in`.
+///
+/// Use it to verify a freshly computed quote against a threshold before sending the swap.
+///
+/// # Arguments
+/// - `quote`: The exact output swap quote.
+/// - `max_in`: The maximum acceptable input amount.
+///
+/// # Returns
+/// `SWAP_INPUT_ABOVE_MAXIMUM` if the estimated input is greater than `max_in`.
+pub fn assert_max_in(quote: &ExactOutSwapQuote, max_in: u64) -> Result<(), CoreError> {
+    if quote.token_est_in > max_in {
+        return Err(SWAP_INPUT_ABOVE_MAXIMUM);
+    }
+    Ok(())
+}
+
+/// Merges the quotes of an order split across several pools into a single quote.
+///
+/// The amounts and fees are summed, saturating at `u64::MAX`. The combined `next_sqrt_price` is
+/// only meaningful if all quotes end at the same price; otherwise it is set to 0 (not applicable).
+///
+/// # Arguments
+/// - `quotes`: The per-pool exact input swap quotes.
+///
+/// # Returns
+/// The aggregated quote, or a default (zero) quote if `quotes` is empty.
+pub fn aggregate_swap_quotes(quotes: &[ExactInSwapQuote]) -> ExactInSwapQuote {
+    let Some(first) = quotes.first() else {
+        return ExactInSwapQuote::default();
+    };
+
+    let next_sqrt_price = if quotes.iter().all(|quote| quote.next_sqrt_price == first.next_sqrt_price) {
+        first.next_sqrt_price
+    } else {
+        0
+    };
+
+    quotes.iter().fold(
+        ExactInSwapQuote {
+            next_sqrt_price,
+            ..ExactInSwapQuote::default()
+        },
+        |total, quote| ExactInSwapQuote {
+            token_in: total.token_in.saturating_add(quote.token_in),
+            token_est_out: total.token_est_out.saturating_add(quote.token_est_out),
+            token_min_out: total.token_min_out.saturating_add(quote.token_min_out),
+            trade_fee: total.trade_fee.saturating_add(quote.trade_fee),
+            next_sqrt_price,
+            amount_out_from_limit_orders: total.amount_out_from_limit_orders.saturating_add(quote.amount_out_from_limit_orders),
+            amount_out_from_clp: total.amount_out_from_clp.saturating_add(quote.amount_out_from_clp),
+            clp_reward: total.clp_reward.saturating_add(quote.clp_reward),
+            olp_reward: total.olp_reward.saturating_add(quote.olp_reward),
+        },
+    )
+}
+
+/// Computes the fee growth per unit of liquidity credited to in-range liquidity providers by a swap.
+///
+/// Mirrors the on-chain fee growth accounting: the liquidity provider part of the swap fee is
+/// scaled by 2^64 and divided by the in-range liquidity.
+///
+/// # Arguments
+/// - `quote`: The exact input swap quote.
+/// - `in_range_liquidity`: The pool liquidity the swap is executed against.
+///
+/// # Returns
+/// The fee growth increment in the input token as a Q64.64 value, or 0 if there is no liquidity.
+pub fn swap_lp_fee_per_liquidity(quote: &ExactInSwapQuote, in_range_liquidity: u128) -> u128 {
+    if in_range_liquidity == 0 {
+        return 0;
+    }
+    ((quote.clp_reward as u128) << 64) / in_range_liquidity
+}
+
+/// Computes the realized exchange rate of a swap, including all fees and the price impact.
+/// IMPORTANT: floating point operations can reduce the precision of the result.
+///
+/// # Arguments
+/// - `quote`: The exact input swap quote.
+/// - `decimals_in`: The number of decimals of the input token.
+/// - `decimals_out`: The number of decimals of the output token.
+///
+/// # Returns
+/// The estimated output token amount per one input token, or 0 if the input amount is zero.
+#[cfg(feature = "floats")]
+pub fn effective_rate(quote: &ExactInSwapQuote, decimals_in: u8, decimals_out: u8) -> f64 {
+    if quote.token_in == 0 {
+        return 0.0;
+    }
+    quote.token_est_out as f64 / quote.token_in as f64 * 10f64.powi(decimals_in as i32 - decimals_out as i32)
+}
+
+/// Computes the breakeven exchange rate of a swap, including the token transfer fees.
+/// IMPORTANT: floating point operations can reduce the precision of the result.
+///
+/// The quote must be computed without transfer fees, otherwise they are applied twice. The input amount is
+/// grossed up by the transfer fee of the input token, and the output amount is reduced by the transfer fee
+/// of the output token.
+///
+/// # Arguments
+/// - `quote`: The exact input swap quote, computed without transfer fees.
+/// - `transfer_fee_in`: The transfer fee of the input token.
+/// - `transfer_fee_out`: The transfer fee of the output token.
+/// - `decimals_in`: The number of decimals of the input token.
+/// - `decimals_out`: The number of decimals of the output token.
+///
+/// # Returns
+/// The output token amount received per one input token sent, or 0 if the input amount is zero.
+#[cfg(feature = "floats")]
+pub fn swap_breakeven_price(
+    quote: &ExactInSwapQuote,
+    transfer_fee_in: Option<TransferFee>,
+    transfer_fee_out: Option<TransferFee>,
+    decimals_in: u8,
+    decimals_out: u8,
+) -> f64 {
+    if quote.token_in == 0 {
+        return 0.0;
+    }
+    let token_sent = match transfer_fee_in {
+        Some(transfer_fee) => try_reverse_apply_transfer_fee(quote.token_in, transfer_fee).unwrap_or(u64::MAX),
+        None => quote.token_in,
+    };
+    let token_received = match transfer_fee_out {
+        Some(transfer_fee) => try_apply_transfer_fee(quote.token_est_out, transfer_fee).unwrap_or(0),
+        None => quote.token_est_out,
+    };
+    token_received as f64 / token_sent as f64 * 10f64.powi(decimals_in as i32 - decimals_out as i32)
+}
+
+/// Computes the output amount of a two-hop swap for an exact input amount.
+///
+/// The intermediate token is transferred directly from the first pool vault to the second one,
+/// so its transfer fee is applied exactly once between the legs.
+///
+/// # Arguments
+/// - `token_in`: The input token amount.
+/// - `a_to_b_one`: The swap direction in the first pool.
+/// - `a_to_b_two`: The swap direction in the second pool.
+/// - `slippage_tolerance`: The slippage tolerance in basis points. Only applied to the final output.
+/// - `fusion_pool_one`: The first fusion_pool state.
+/// - `tick_arrays_one`: The tick arrays needed for the first swap.
+/// - `fusion_pool_two`: The second fusion_pool state.
+/// - `tick_arrays_two`: The tick arrays needed for the second swap.
+/// - `transfer_fee_input`: The transfer fee for the input token.
+/// - `transfer_fee_intermediate`: The transfer fee for the intermediate token.
+/// - `transfer_fee_output`: The transfer fee for the output token.
+///
+/// # Returns
+/// The exact input and the estimated output amounts for the two-hop swap transaction.
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn swap_quote_two_hop_by_input_token(
+    token_in: u64,
+    a_to_b_one: bool,
+    a_to_b_two: bool,
+    slippage_tolerance_bps: u16,
+    fusion_pool_one: FusionPoolFacade,
+    tick_arrays_one: TickArrays,
+    fusion_pool_two: FusionPoolFacade,
+    tick_arrays_two: TickArrays,
+    transfer_fee_input: Option<TransferFee>,
+    transfer_fee_intermediate: Option<TransferFee>,
+    transfer_fee_output: Option<TransferFee>,
+) -> Result<TwoHopExactInSwapQuote, CoreError> {
+    if slippage_tolerance_bps > BPS_DENOMINATOR {
+        return Err(INVALID_SLIPPAGE_TOLERANCE);
+    }
+    let (transfer_fee_a_one, transfer_fee_b_one) = if a_to_b_one {
+        (transfer_fee_input, None)
+    } else {
+        (None, transfer_fee_input)
+    };
+    let quote_one = swap_quote_by_input_token_in_arrays(
+        token_in,
+        a_to_b_one.into(),
+        0,
+        fusion_pool_one,
+        tick_arrays_one,
+        transfer_fee_a_one,
+        transfer_fee_b_one,
+        true,
+    )?;
+    if quote_one.token_est_out == 0 || !is_input_consumed(quote_one.token_in, token_in, transfer_fee_input)? {
+        return Err(INSUFFICIENT_LIQUIDITY);
+    }
+
+    let (transfer_fee_a_two, transfer_fee_b_two) = if a_to_b_two {
+        (transfer_fee_intermediate, transfer_fee_output)
+    } else {
+        (transfer_fee_output, transfer_fee_intermediate)
+    };
+    let quote_two = swap_quote_by_input_token_in_arrays(
+        quote_one.token_est_out,
+        a_to_b_two.into(),
+        0,
+        fusion_pool_two,
+        tick_arrays_two,
+        transfer_fee_a_two,
+        transfer_fee_b_two,
+        true,
+    )?;
+    if quote_two.token_est_out == 0 || !is_input_consumed(quote_two.token_in, quote_one.token_est_out, transfer_fee_intermediate)? {
+        return Err(INSUFFICIENT_LIQUIDITY);
+    }
+
+    let token_min_out = try_get_min_amount_with_slippage_tolerance(quote_two.token_est_out, slippage_tolerance_bps)?;
+
+    Ok(TwoHopExactInSwapQuote {
+        token_in: quote_one.token_in,
+        token_est_intermediate: quote_one.token_est_out,
+        token_est_out: quote_two.token_est_out,
+        token_min_out,
+        trade_fee_one: quote_one.trade_fee,
+        trade_fee_two: quote_two.trade_fee,
+        next_sqrt_price_one: quote_one.next_sqrt_price,
+        next_sqrt_price_two: quote_two.next_sqrt_price,
+    })
+}
+
+/// Computes the input amount of a two-hop swap for an exact output amount.
+///
+/// The second leg is quoted first to derive the required intermediate amount, which is then
+/// used as the exact output of the first leg. The intermediate token is transferred directly
+/// from the first pool vault to the second one, so its transfer fee is applied exactly once.
+///
+/// # Arguments
+/// - `token_out`: The output token amount.
+/// - `a_to_b_one`: The swap direction in the first pool.
+/// - `a_to_b_two`: The swap direction in the second pool.
+/// - `slippage_tolerance`: The slippage tolerance in basis points. Only applied to the maximum input.
+/// - `fusion_pool_one`: The first fusion_pool state.
+/// - `tick_arrays_one`: The tick arrays needed for the first swap.
+/// - `fusion_pool_two`: The second fusion_pool state.
+/// - `tick_arrays_two`: The tick arrays needed for the second swap.
+/// - `transfer_fee_input`: The transfer fee for the input token.
+/// - `transfer_fee_intermediate`: The transfer fee for the intermediate token.
+/// - `transfer_fee_output`: The transfer fee for the output token.
+///
+/// # Returns
+/// The exact output and the estimated input amounts for the two-hop swap transaction.
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn swap_quote_two_hop_by_output_token(
+    token_out: u64,
+    a_to_b_one: bool,
+    a_to_b_two: bool,
+    slippage_tolerance_bps: u16,
+    fusion_pool_one: FusionPoolFacade,
+    tick_arrays_one: TickArrays,
+    fusion_pool_two: FusionPoolFacade,
+    tick_arrays_two: TickArrays,
+    transfer_fee_input: Option<TransferFee>,
+    transfer_fee_intermediate: Option<TransferFee>,
+    transfer_fee_output: Option<TransferFee>,
+) -> Result<TwoHopExactOutSwapQuote, CoreError> {
+    if slippage_tolerance_bps > BPS_DENOMINATOR {
+        return Err(INVALID_SLIPPAGE_TOLERANCE);
+    }
+    let (transfer_fee_a_two, transfer_fee_b_two) = if a_to_b_two {
+        (transfer_fee_intermediate, transfer_fee_output)
+    } else {
+        (transfer_fee_output, transfer_fee_intermediate)
+    };
+    let quote_two = swap_quote_by_output_token_in_arrays(
+        token_out,
+        a_to_b_two.into(),
+        0,
+        fusion_pool_two,
+        tick_arrays_two,
+        transfer_fee_a_two,
+        transfer_fee_b_two,
+        true,
+    )?;
+    if quote_two.token_out < token_out {
+        return Err(INSUFFICIENT_LIQUIDITY);
+    }
+
+    let (transfer_fee_a_one, transfer_fee_b_one) = if a_to_b_one {
+        (transfer_fee_input, None)
+    } else {
+        (None, transfer_fee_input)
+    };
+    let quote_one = swap_quote_by_output_token_in_arrays(
+        quote_two.token_est_in,
+        a_to_b_one.into(),
+        0,
+        fusion_pool_one,
+        tick_arrays_one,
+        transfer_fee_a_one,
+        transfer_fee_b_one,
+        true,
+    )?;
+    if quote_one.token_out < quote_two.token_est_in {
+        return Err(INSUFFICIENT_LIQUIDITY);
+    }
+
+    let token_max_in = try_get_max_amount_with_slippage_tolerance(quote_one.token_est_in, slippage_tolerance_bps)?;
+
+    Ok(TwoHopExactOutSwapQuote {
+        token_out: quote_two.token_out,
+        token_est_intermediate: quote_two.token_est_in,
+        token_est_in: quote_one.token_est_in,
+        token_max_in,
+        trade_fee_one: quote_one.trade_fee,
+        trade_fee_two: quote_two.trade_fee,
+        next_sqrt_price_one: quote_one.next_sqrt_price,
+        next_sqrt_price_two: quote_two.next_sqrt_price,
+    })
+}
+
+/// Returns the sqrt price of the last tick boundary covered by the tick sequence in the swap direction,
+/// or `None` if the price is already there.
+fn tick_sequence_boundary_sqrt_price<const SIZE: usize>(
+    fusion_pool: &FusionPoolFacade,
+    tick_sequence: &TickArraySequence<SIZE>,
+    a_to_b: bool,
+) -> Option<u128> {
+    let boundary_tick_index = if a_to_b {
+        tick_sequence.start_index()
+    } else {
+        tick_sequence.end_index()
+    };
+    let sqrt_price: u128 = tick_index_to_sqrt_price(boundary_tick_index).into();
+    if a_to_b && sqrt_price >= fusion_pool.sqrt_price || !a_to_b && sqrt_price <= fusion_pool.sqrt_price {
+        None
+    } else {
+        Some(sqrt_price)
+    }
+}
+
+/// Returns `true` if a swap leg consumed all of its input, comparing the amounts received by the pool
+/// after the transfer fee, since reversing the fee may round the quoted input down.
+fn is_input_consumed(quoted_token_in: u64, token_in: u64, transfer_fee: Option<TransferFee>) -> Result<bool, CoreError> {
+    let transfer_fee = transfer_fee.unwrap_or_default();
+    Ok(try_apply_transfer_fee(quoted_token_in, transfer_fee)? >= try_apply_transfer_fee(token_in, transfer_fee)?)
+}
+
+/// Computes the maximum swap input that can be consumed without leaving the provided tick arrays.
+///
+/// # Arguments
+/// - `fusion_pool`: The fusion_pool state.
+/// - `tick_arrays`: The tick arrays available for the swap.
+/// - `a_to_b`: The swap direction.
+///
+/// # Returns
+/// The input amount, including the swap fee, that moves the price to the last tick boundary covered by the tick arrays.
+/// A larger input would fail with `INVALID_TICK_ARRAY_SEQUENCE`.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn max_swap_input_within_arrays(fusion_pool: FusionPoolFacade, tick_arrays: TickArrays, a_to_b: bool) -> Result<u64, CoreError> {
+    let tick_sequence = TickArraySequence::new(tick_arrays.into(), fusion_pool.tick_spacing)?;
+    let Some(sqrt_price_limit) = tick_sequence_boundary_sqrt_price(&fusion_pool, &tick_sequence, a_to_b) else {
+        return Ok(0);
+    };
+
+    let swap_result = compute_swap(u64::MAX, sqrt_price_limit, fusion_pool, tick_sequence, a_to_b, true)?;
+    Ok(if a_to_b { swap_result.token_a } else { swap_result.token_b })
+}
+
+/// Computes the swap input required to move the pool price to a target tick.
+///
+/// # Arguments
+/// - `fusion_pool`: The fusion_pool state.
+/// - `tick_arrays`: The tick arrays covering the price range between the current and the target tick.
+/// - `target_tick_index`: The tick index the price is moved to.
+/// - `a_to_b`: The swap direction. The target tick must be below the current price for `true` and above it for `false`.
+///
+/// # Returns
+/// The input amount, including the swap fee, that moves the price to the sqrt price of the target tick.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn input_to_reach_tick(fusion_pool: FusionPoolFacade, tick_arrays: TickArrays, target_tick_index: i32, a_to_b: bool) -> Result<u64, CoreError> {
+    if !(MIN_TICK_INDEX..=MAX_TICK_INDEX).contains(&target_tick_index) {
+        return Err(TICK_INDEX_OUT_OF_BOUNDS);
+    }
+
+    let sqrt_price_limit: u128 = tick_index_to_sqrt_price(target_tick_index.into()).into();
+    if a_to_b && sqrt_price_limit >= fusion_pool.sqrt_price || !a_to_b && sqrt_price_limit <= fusion_pool.sqrt_price {
+        return Err(INVALID_SQRT_PRICE_LIMIT_DIRECTION);
+    }
+
+    let tick_sequence = TickArraySequence::new(tick_arrays.into(), fusion_pool.tick_spacing)?;
+    let swap_result = compute_swap(u64::MAX, sqrt_price_limit, fusion_pool, tick_sequence, a_to_b, true)?;
+    Ok(if a_to_b { swap_result.token_a } else { swap_result.token_b })
+}
+
+/// Computes a sqrt price limit for `compute_swap` from a slippage tolerance.
+///
+/// The price moves down for a swap from A to B and up for a swap from B to A by the tolerance.
+///
+/// # Arguments
+/// - `current_sqrt_price`: The current sqrt price of the pool.
+/// - `slippage_bps`: The slippage tolerance in basis points.
+/// - `a_to_b`: The swap direction.
+///
+/// # Returns
+/// The sqrt price limit, clamped to the minimum and maximum sqrt price.
+pub fn sqrt_price_limit_from_slippage(current_sqrt_price: u128, slippage_bps: u16, a_to_b: bool) -> u128 {
+    let price_factor = if a_to_b {
+        BPS_DENOMINATOR.saturating_sub(slippage_bps)
+    } else {
+        BPS_DENOMINATOR.saturating_add(slippage_bps)
+    };
+    // The price is the square of the sqrt price, so the sqrt price moves by the square root of the factor.
+    let sqrt_price_squared = U256::from(current_sqrt_price) * U256::from(current_sqrt_price);
+    let sqrt_price_limit = sqrt_u256(sqrt_price_squared * U256::from(price_factor) / U256::from(BPS_DENOMINATOR));
+    if sqrt_price_limit > U256::from(MAX_SQRT_PRICE) {
+        MAX_SQRT_PRICE
+    } else {
+        sqrt_price_limit.as_u128().max(MIN_SQRT_PRICE)
+    }
+}
+
+/// Computes the start tick indexes of the tick arrays traversed by an exact input swap.
+///
+/// # Arguments
+/// - `fusion_pool`: The fusion_pool state.
+/// - `tick_sequence`: The tick arrays available around the current price.
+/// - `token_in`: The input token amount.
+/// - `a_to_b`: The swap direction.
+///
+/// # Returns
+/// The start tick indexes ordered in the swap direction, starting with the tick array of the current tick.
+/// At most `MAX_SWAP_TICK_ARRAYS` tick arrays are returned. If the swap can't be computed, all available
+/// tick arrays up to this limit are returned. Fails with `INVALID_TICK_SPACING` if the tick spacing is zero.
+pub fn required_tick_arrays_for_swap(
+    fusion_pool: &FusionPoolFacade,
+    tick_sequence: &TickArraySequenceVec,
+    token_in: u64,
+    a_to_b: bool,
+) -> Result<Vec<i32>, CoreError> {
+    if fusion_pool.tick_spacing == 0 {
+        return Err(INVALID_TICK_SPACING);
+    }
+    let tick_array_offset = TICK_ARRAY_SIZE as i32 * fusion_pool.tick_spacing as i32;
+    let direction = if a_to_b { -1 } else { 1 };
+    let current_start_index = get_tick_array_start_tick_index(fusion_pool.tick_current_index, fusion_pool.tick_spacing);
+
+    let tick_arrays: Vec<TickArrayFacade> = (0..MAX_SWAP_TICK_ARRAYS as i32)
+        .map(|i| current_start_index + direction * i * tick_array_offset)
+        .map_while(|start_tick_index| tick_sequence.tick_arrays.iter().find(|x| x.start_tick_index == start_tick_index).cloned())
+        .collect();
+    let start_tick_indexes: Vec<i32> = tick_arrays.iter().map(|x| x.start_tick_index).collect();
+
+    let swap_result =
+        TickArraySequence::new(std::array::from_fn::<_, MAX_SWAP_TICK_ARRAYS, _>(|i| tick_arrays.get(i).cloned()), fusion_pool.tick_spacing)
+            .and_then(|swap_tick_sequence| compute_swap(token_in, 0, *fusion_pool, swap_tick_sequence, a_to_b, true));
+
+    match swap_result {
+        Ok(swap_result) => {
+            let end_tick_index = sqrt_price_to_tick_index(swap_result.next_sqrt_price.into());
+            let end_start_index = get_tick_array_start_tick_index(end_tick_index, fusion_pool.tick_spacing);
+            let num_tick_arrays = ((end_start_index - current_start_index) * direction / tick_array_offset + 1) as usize;
+            Ok(start_tick_indexes.into_iter().take(num_tick_arrays).collect())
+        }
+        Err(_) => Ok(start_tick_indexes),
+    }
+}
+
 pub struct SwapResult {
     pub token_a: u64,
     pub token_b: u64,
     pub fee_amount: u64,
     pub next_sqrt_price: u128,
+    /// The output amount filled by limit orders.
+    pub amount_out_from_limit_orders: u64,
+    /// The output amount filled by the concentrated liquidity.
+    pub amount_out_from_clp: u64,
+    /// The part of the swap fee paid to concentrated liquidity providers.
+    pub clp_reward: u64,
+    /// The part of the swap fee paid to the filled limit orders.
+    pub olp_reward: u64,
 }
 
 /// Computes the amounts of tokens A and B based on the current FusionPool state and tick sequence.
@@ -164,6 +784,44 @@ pub fn compute_swap<const SIZE: usize>(
     tick_sequence: TickArraySequence<SIZE>,
     a_to_b: bool,
     specified_input: bool,
+) -> Result<SwapResult, CoreError> {
+    compute_swap_in_sequence(token_amount, sqrt_price_limit, fusion_pool, &tick_sequence, a_to_b, specified_input)
+}
+
+/// Computes the amounts of tokens A and B based on the current FusionPool state and a heap-backed tick sequence.
+///
+/// Same as `compute_swap`, but the tick sequence may hold any number of tick arrays, so the quote is not
+/// limited by the size of a fixed tick array sequence.
+///
+/// # Arguments
+/// - `token_amount`: The input or output amount specified for the swap. Must be non-zero.
+/// - `sqrt_price_limit`: The price limit for the swap represented as a square root. If set to `0`,
+///   it defaults to the minimum or maximum sqrt price based on the direction of the swap.
+/// - `fusion_pool`: The current state of the FusionPool AMM, including liquidity, price, and tick information.
+/// - `tick_sequence`: A sequence of ticks used to determine price levels during the swap process.
+/// - `a_to_b`: The swap direction.
+/// - `specified_input`: Determines if `token_amount` represents the input or the output amount.
+///
+/// # Returns
+/// A `Result` containing a `SwapResult` struct if the swap is successful, or an `ErrorCode` if the computation fails.
+pub fn compute_swap_dynamic(
+    token_amount: u64,
+    sqrt_price_limit: u128,
+    fusion_pool: FusionPoolFacade,
+    tick_sequence: &TickArraySequenceVec,
+    a_to_b: bool,
+    specified_input: bool,
+) -> Result<SwapResult, CoreError> {
+    compute_swap_in_sequence(token_amount, sqrt_price_limit, fusion_pool, tick_sequence, a_to_b, specified_input)
+}
+
+fn compute_swap_in_sequence<T: SwapTickSequence>(
+    token_amount: u64,
+    sqrt_price_limit: u128,
+    fusion_pool: FusionPoolFacade,
+    tick_sequence: &T,
+    a_to_b: bool,
+    specified_input: bool,
 ) -> Result<SwapResult, CoreError> {
     let sqrt_price_limit = if sqrt_price_limit == 0 {
         if a_to_b {
@@ -193,13 +851,14 @@ pub fn compute_swap<const SIZE: usize>(
     let mut current_tick_index = fusion_pool.tick_current_index;
     let mut current_liquidity = fusion_pool.liquidity;
     let mut fee_amount = 0;
+    let mut amount_out_from_limit_orders = 0u64;
+    let mut amount_out_from_clp = 0u64;
+    let mut clp_reward = 0u64;
+    let mut olp_reward = 0u64;
 
     while amount_remaining > 0 && sqrt_price_limit != current_sqrt_price {
-        let (next_tick, next_tick_index) = if a_to_b {
-            tick_sequence.prev_initialized_tick(current_tick_index)?
-        } else {
-            tick_sequence.next_initialized_tick(current_tick_index)?
-        };
+        let (next_tick, next_tick_index) = tick_sequence.next_swap_tick(current_tick_index, a_to_b)?;
+        let next_tick = next_tick.as_ref();
         let next_tick_sqrt_price: u128 = tick_index_to_sqrt_price(next_tick_index.into()).into();
         let target_sqrt_price = if a_to_b {
             next_tick_sqrt_price.max(sqrt_price_limit)
@@ -218,6 +877,10 @@ pub fn compute_swap<const SIZE: usize>(
         )?;
 
         fee_amount += step_quote.fee_amount;
+        // The protocol takes its share of the fee and the rest goes to the concentrated liquidity providers.
+        clp_reward += step_quote.fee_amount
+            - try_mul_div(step_quote.fee_amount, fusion_pool.protocol_fee_rate as u128, PROTOCOL_FEE_RATE_MUL_VALUE as u128, false)?;
+        amount_out_from_clp = amount_out_from_clp.checked_add(step_quote.amount_out).ok_or(ARITHMETIC_OVERFLOW)?;
 
         if specified_input {
             amount_remaining = amount_remaining
@@ -240,6 +903,20 @@ pub fn compute_swap<const SIZE: usize>(
                 fill_limit_orders(next_tick, next_tick_sqrt_price, a_to_b, specified_input, amount_remaining, fusion_pool.fee_rate)?;
 
             fee_amount += limit_swap_computation.fee_amount;
+            // The order protocol fee is taken first, then the rest is split between concentrated liquidity providers and limit orders.
+            let order_lp_fee = limit_swap_computation.fee_amount
+                - try_mul_div(
+                    limit_swap_computation.fee_amount,
+                    fusion_pool.order_protocol_fee_rate as u128,
+                    PROTOCOL_FEE_RATE_MUL_VALUE as u128,
+                    false,
+                )?;
+            let order_clp_reward = try_mul_div(order_lp_fee, fusion_pool.clp_reward_rate as u128, MAX_CLP_REWARD_RATE as u128, false)?;
+            clp_reward += order_clp_reward;
+            olp_reward += order_lp_fee - order_clp_reward;
+            amount_out_from_limit_orders = amount_out_from_limit_orders
+                .checked_add(limit_swap_computation.amount_out)
+                .ok_or(ARITHMETIC_OVERFLOW)?;
 
             if specified_input {
                 amount_remaining = amount_remaining
@@ -280,6 +957,10 @@ pub fn compute_swap<const SIZE: usize>(
         token_b,
         fee_amount,
         next_sqrt_price: current_sqrt_price,
+        amount_out_from_limit_orders,
+        amount_out_from_clp,
+        clp_reward,
+        olp_reward,
     })
 }
 
@@ -301,6 +982,47 @@ pub(crate) fn get_next_liquidity(current_liquidity: u128, next_tick: Option<&Tic
 
 // Private functions
 
+/// Computes the integer square root of a U256 value, rounded down.
+fn sqrt_u256(value: U256) -> U256 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = U256::ONE << ((256 - value.leading_zeros()).div_ceil(2));
+    loop {
+        let y = (x + value / x) >> 1;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// The tick lookup shared by the fixed-size and the heap-backed tick sequences.
+trait SwapTickSequence {
+    fn next_swap_tick(&self, tick_index: i32, a_to_b: bool) -> Result<(Option<TickFacade>, i32), CoreError>;
+}
+
+impl<const SIZE: usize> SwapTickSequence for TickArraySequence<SIZE> {
+    fn next_swap_tick(&self, tick_index: i32, a_to_b: bool) -> Result<(Option<TickFacade>, i32), CoreError> {
+        let (tick, tick_index) = if a_to_b {
+            self.prev_initialized_tick(tick_index)?
+        } else {
+            self.next_initialized_tick(tick_index)?
+        };
+        Ok((tick.copied(), tick_index))
+    }
+}
+
+impl SwapTickSequence for TickArraySequenceVec {
+    fn next_swap_tick(&self, tick_index: i32, a_to_b: bool) -> Result<(Option<TickFacade>, i32), CoreError> {
+        if a_to_b {
+            self.prev_initialized_tick(tick_index)
+        } else {
+            self.next_initialized_tick(tick_index)
+        }
+    }
+}
+
 #[derive(PartialEq, Debug, Default)]
 pub struct LimitSwapComputation {
     pub amount_in: u64,
@@ -537,6 +1259,15 @@ mod tests {
         .into()
     }
 
+    fn test_empty_tick_arrays() -> TickArrays {
+        [0, 176, 352, -176, -352]
+            .map(|start_tick_index| TickArrayFacade {
+                start_tick_index,
+                ticks: [TickFacade::default(); TICK_ARRAY_SIZE],
+            })
+            .into()
+    }
+
     fn test_tick_arrays_with_orders() -> TickArrays {
         [
             test_tick_array_with_orders(0),
@@ -641,6 +1372,196 @@ mod tests {
         assert_eq!(result.next_sqrt_price, 18431993317065449817);
     }
 
+    #[test]
+    fn test_exact_in_output_split_with_orders_only() {
+        for a_to_b in [true, false] {
+            let result = swap_quote_by_input_token(
+                85000,
+                a_to_b,
+                1000,
+                test_fusion_pool_with_zero_liquidity(1 << 64),
+                test_tick_arrays_with_orders(),
+                None,
+                None,
+            )
+            .unwrap();
+            assert_eq!(result.amount_out_from_limit_orders, result.token_est_out);
+            assert_eq!(result.amount_out_from_clp, 0);
+        }
+    }
+
+    #[test]
+    fn test_exact_in_output_split_without_orders() {
+        let result = swap_quote_by_input_token(1000, true, 1000, test_fusion_pool(1 << 64, true), test_tick_arrays(), None, None).unwrap();
+        assert_eq!(result.amount_out_from_clp, result.token_est_out);
+        assert_eq!(result.amount_out_from_limit_orders, 0);
+    }
+
+    #[test]
+    fn test_assert_min_out() {
+        let quote = ExactInSwapQuote {
+            token_est_out: 1000,
+            token_min_out: 900,
+            ..ExactInSwapQuote::default()
+        };
+        assert_eq!(assert_min_out(&quote, 999), Ok(()));
+        assert_eq!(assert_min_out(&quote, 1000), Ok(()));
+        assert_eq!(assert_min_out(&quote, 1001), Err(SWAP_OUTPUT_BELOW_MINIMUM));
+    }
+
+    #[test]
+    fn test_assert_max_in() {
+        let quote = ExactOutSwapQuote {
+            token_est_in: 1000,
+            token_max_in: 1100,
+            ..ExactOutSwapQuote::default()
+        };
+        assert_eq!(assert_max_in(&quote, 1001), Ok(()));
+        assert_eq!(assert_max_in(&quote, 1000), Ok(()));
+        assert_eq!(assert_max_in(&quote, 999), Err(SWAP_INPUT_ABOVE_MAXIMUM));
+    }
+
+    #[test]
+    fn test_aggregate_swap_quotes() {
+        let quote_one = ExactInSwapQuote {
+            token_in: 1000,
+            token_est_out: 950,
+            token_min_out: 900,
+            trade_fee: 3,
+            next_sqrt_price: 1 << 64,
+            ..ExactInSwapQuote::default()
+        };
+        let quote_two = ExactInSwapQuote {
+            token_in: 500,
+            token_est_out: 480,
+            token_min_out: 450,
+            trade_fee: 2,
+            next_sqrt_price: 2 << 64,
+            ..ExactInSwapQuote::default()
+        };
+
+        let result = aggregate_swap_quotes(&[quote_one, quote_two]);
+        assert_eq!(result.token_in, 1500);
+        assert_eq!(result.token_est_out, 1430);
+        assert_eq!(result.token_min_out, 1350);
+        assert_eq!(result.trade_fee, 5);
+        assert_eq!(result.next_sqrt_price, 0);
+
+        assert_eq!(aggregate_swap_quotes(&[quote_one, quote_one]).next_sqrt_price, 1 << 64);
+        assert_eq!(aggregate_swap_quotes(&[]), ExactInSwapQuote::default());
+    }
+
+    #[test]
+    fn test_swap_lp_fee_per_liquidity() {
+        let quote = ExactInSwapQuote {
+            clp_reward: 300,
+            ..ExactInSwapQuote::default()
+        };
+        assert_eq!(swap_lp_fee_per_liquidity(&quote, 1000), (3 << 64) / 10);
+        assert_eq!(swap_lp_fee_per_liquidity(&quote, 300), 1 << 64);
+        assert_eq!(swap_lp_fee_per_liquidity(&quote, 0), 0);
+
+        let quote = ExactInSwapQuote {
+            clp_reward: u64::MAX,
+            ..ExactInSwapQuote::default()
+        };
+        assert_eq!(swap_lp_fee_per_liquidity(&quote, 1), (u64::MAX as u128) << 64);
+    }
+
+    #[cfg(feature = "floats")]
+    #[test]
+    fn test_effective_rate() {
+        use crate::sqrt_price_to_price;
+        use approx::assert_relative_eq;
+
+        let fusion_pool = test_fusion_pool(1 << 64, true);
+        let mid_price = sqrt_price_to_price(fusion_pool.sqrt_price.into(), 9, 6);
+
+        // A small swap only pays the 0.3% swap fee.
+        let small_quote = swap_quote_by_input_token(1000, true, 0, fusion_pool, test_tick_arrays(), None, None).unwrap();
+        let small_rate = effective_rate(&small_quote, 9, 6);
+        assert_relative_eq!(small_rate, mid_price, max_relative = 0.005);
+
+        // A large swap moves the price, so the rate gets worse.
+        let large_quote = swap_quote_by_input_token(1_000_000, true, 0, fusion_pool, test_tick_arrays(), None, None).unwrap();
+        let large_rate = effective_rate(&large_quote, 9, 6);
+        assert!(large_rate < small_rate);
+        assert!(large_rate < mid_price * 0.99);
+
+        assert_eq!(effective_rate(&ExactInSwapQuote::default(), 9, 6), 0.0);
+    }
+
+    #[cfg(feature = "floats")]
+    #[test]
+    fn test_swap_breakeven_price() {
+        use approx::assert_relative_eq;
+
+        let fusion_pool = test_fusion_pool(1 << 64, true);
+        let quote = swap_quote_by_input_token(1000, true, 0, fusion_pool, test_tick_arrays(), None, None).unwrap();
+
+        let breakeven = swap_breakeven_price(&quote, None, None, 9, 6);
+        assert_eq!(breakeven, effective_rate(&quote, 9, 6));
+
+        // A 1% transfer fee on the output token lowers the breakeven by 1%.
+        let breakeven_with_fee = swap_breakeven_price(&quote, None, Some(TransferFee::new(100)), 9, 6);
+        assert_relative_eq!(breakeven_with_fee, breakeven * 0.99, max_relative = 0.002);
+        assert!(breakeven_with_fee < breakeven);
+
+        // The transfer fee of the input token makes the swap more expensive as well.
+        assert!(swap_breakeven_price(&quote, Some(TransferFee::new(100)), None, 9, 6) < breakeven);
+
+        assert_eq!(swap_breakeven_price(&ExactInSwapQuote::default(), None, None, 9, 6), 0.0);
+    }
+
+    #[test]
+    fn test_swap_direction_conversions() {
+        assert_eq!(SwapDirection::from(true), SwapDirection::AtoB);
+        assert_eq!(SwapDirection::from(false), SwapDirection::BtoA);
+        assert!(bool::from(SwapDirection::AtoB));
+        assert!(!bool::from(SwapDirection::BtoA));
+    }
+
+    #[test]
+    fn test_quotes_with_direction() {
+        let fusion_pool = test_fusion_pool(1 << 64, true);
+        for (a_to_b, direction) in [(true, SwapDirection::AtoB), (false, SwapDirection::BtoA)] {
+            assert_eq!(
+                swap_quote_by_input_token(1000, a_to_b, 1000, fusion_pool, test_tick_arrays(), None, None),
+                swap_quote_by_input_token_with_direction(1000, direction, 1000, fusion_pool, test_tick_arrays(), None, None)
+            );
+            // The specified token of an exact output quote is the output token.
+            assert_eq!(
+                swap_quote_by_output_token(1000, !a_to_b, 1000, fusion_pool, test_tick_arrays(), None, None),
+                swap_quote_by_output_token_with_direction(1000, direction, 1000, fusion_pool, test_tick_arrays(), None, None)
+            );
+        }
+    }
+
+    #[test]
+    fn test_exact_in_reward_split_with_orders() {
+        let fusion_pool = FusionPoolFacade {
+            order_protocol_fee_rate: 2000,
+            clp_reward_rate: 2500,
+            ..test_fusion_pool_with_zero_liquidity(1 << 64)
+        };
+        let result = swap_quote_by_input_token(5000, true, 1000, fusion_pool, test_tick_arrays_with_orders(), None, None).unwrap();
+        let lp_fee = result.trade_fee - result.trade_fee * 2000 / 10000;
+        assert!(result.trade_fee > 0);
+        assert_eq!(result.clp_reward + result.olp_reward, lp_fee);
+        assert_eq!(result.clp_reward, lp_fee * 2500 / 10000);
+    }
+
+    #[test]
+    fn test_exact_in_reward_split_without_orders() {
+        let fusion_pool = FusionPoolFacade {
+            protocol_fee_rate: 1000,
+            ..test_fusion_pool(1 << 64, true)
+        };
+        let result = swap_quote_by_input_token(1000, true, 1000, fusion_pool, test_tick_arrays(), None, None).unwrap();
+        assert_eq!(result.clp_reward, result.trade_fee - result.trade_fee * 1000 / 10000);
+        assert_eq!(result.olp_reward, 0);
+    }
+
     #[test]
     /// The test is equal to swap_manager::swap_with_limit_orders_tests::test_for_swap_quote_zero_liquidity_a_to_b_exact_out() in FusionAMM program.
     fn test_exact_out_a_to_b_with_orders() {
@@ -680,6 +1601,112 @@ mod tests {
         assert_eq!(result.next_sqrt_price, 18463352785753515702);
     }
 
+    #[test]
+    fn test_required_tick_arrays_for_swap() {
+        let fusion_pool = test_fusion_pool(1 << 64, true);
+        let tick_arrays: [Option<TickArrayFacade>; 6] = test_tick_arrays().into();
+        let tick_sequence = TickArraySequenceVec::new(tick_arrays.into_iter().flatten().collect(), fusion_pool.tick_spacing).unwrap();
+
+        assert_eq!(required_tick_arrays_for_swap(&fusion_pool, &tick_sequence, 1000, false), Ok(vec![0]));
+        assert_eq!(required_tick_arrays_for_swap(&fusion_pool, &tick_sequence, 1_000_000, false), Ok(vec![0, 176]));
+        assert_eq!(required_tick_arrays_for_swap(&fusion_pool, &tick_sequence, 2_000_000, false), Ok(vec![0, 176, 352]));
+        assert_eq!(required_tick_arrays_for_swap(&fusion_pool, &tick_sequence, 1000, true), Ok(vec![0, -176]));
+        assert_eq!(required_tick_arrays_for_swap(&fusion_pool, &tick_sequence, 2_000_000, true), Ok(vec![0, -176, -352]));
+
+        let fusion_pool = FusionPoolFacade {
+            tick_spacing: 0,
+            ..fusion_pool
+        };
+        assert_eq!(required_tick_arrays_for_swap(&fusion_pool, &tick_sequence, 1000, true), Err(INVALID_TICK_SPACING));
+    }
+
+    #[test]
+    fn test_compute_swap_dynamic_matches_fixed_sequence() {
+        let fusion_pool = test_fusion_pool(1 << 64, false);
+        let tick_arrays: [Option<TickArrayFacade>; 6] = test_tick_arrays().into();
+        let fixed_sequence = TickArraySequence::new(tick_arrays, fusion_pool.tick_spacing).unwrap();
+        let dynamic_sequence =
+            TickArraySequenceVec::new([-528, -352, -176, 0, 176, 352, 528].map(test_tick_array).to_vec(), fusion_pool.tick_spacing).unwrap();
+
+        for (amount, a_to_b, specified_input) in [(1000, true, true), (3000, true, false), (1000, false, true), (3000, false, false)] {
+            let fixed = compute_swap(amount, 0, fusion_pool, fixed_sequence.clone(), a_to_b, specified_input).unwrap();
+            let dynamic = compute_swap_dynamic(amount, 0, fusion_pool, &dynamic_sequence, a_to_b, specified_input).unwrap();
+            assert_eq!(dynamic.token_a, fixed.token_a);
+            assert_eq!(dynamic.token_b, fixed.token_b);
+            assert_eq!(dynamic.fee_amount, fixed.fee_amount);
+            assert_eq!(dynamic.next_sqrt_price, fixed.next_sqrt_price);
+        }
+
+        // The five tick arrays hold insufficient liquidity for this swap, the seven tick arrays don't.
+        assert_eq!(compute_swap(3429, 0, fusion_pool, fixed_sequence, true, true).err(), Some(INVALID_TICK_ARRAY_SEQUENCE));
+        let dynamic = compute_swap_dynamic(3429, 0, fusion_pool, &dynamic_sequence, true, true).unwrap();
+        assert_eq!(dynamic.token_a, 3429);
+    }
+
+    #[test]
+    fn test_max_swap_input_within_arrays() {
+        let fusion_pool = test_fusion_pool(1 << 64, false);
+        for a_to_b in [true, false] {
+            let max_input = max_swap_input_within_arrays(fusion_pool, test_tick_arrays(), a_to_b).unwrap();
+            let tick_sequence = TickArraySequence::new(test_tick_arrays().into(), fusion_pool.tick_spacing).unwrap();
+            let result = compute_swap(max_input, 0, fusion_pool, tick_sequence.clone(), a_to_b, true).unwrap();
+            assert_eq!(if a_to_b { result.token_a } else { result.token_b }, max_input);
+            assert_eq!(compute_swap(max_input + 1, 0, fusion_pool, tick_sequence, a_to_b, true).err(), Some(INVALID_TICK_ARRAY_SEQUENCE));
+        }
+        assert_eq!(max_swap_input_within_arrays(fusion_pool, test_tick_arrays(), true).unwrap(), 3428);
+    }
+
+    #[test]
+    fn test_input_to_reach_tick() {
+        let fusion_pool = test_fusion_pool(1 << 64, true);
+        let input = input_to_reach_tick(fusion_pool, test_tick_arrays(), 176, false).unwrap();
+
+        let tick_sequence = TickArraySequence::new(test_tick_arrays().into(), fusion_pool.tick_spacing).unwrap();
+        let result = compute_swap(input, 0, fusion_pool, tick_sequence, false, true).unwrap();
+        assert_eq!(result.token_b, input);
+        assert_eq!(result.next_sqrt_price, u128::from(tick_index_to_sqrt_price(176)));
+
+        assert_eq!(input_to_reach_tick(fusion_pool, test_tick_arrays(), 176, true).err(), Some(INVALID_SQRT_PRICE_LIMIT_DIRECTION));
+        assert_eq!(input_to_reach_tick(fusion_pool, test_tick_arrays(), 0, false).err(), Some(INVALID_SQRT_PRICE_LIMIT_DIRECTION));
+        assert_eq!(input_to_reach_tick(fusion_pool, test_tick_arrays(), MAX_TICK_INDEX + 1, false).err(), Some(TICK_INDEX_OUT_OF_BOUNDS));
+    }
+
+    #[test]
+    fn test_next_tick_index_at_tick_boundary() {
+        let fusion_pool = test_fusion_pool(1 << 64, true);
+        let input = input_to_reach_tick(fusion_pool, test_tick_arrays(), -176, true).unwrap();
+
+        let quote = swap_quote_by_input_token(input, true, 0, fusion_pool, test_tick_arrays(), None, None).unwrap();
+        assert_eq!(quote.next_sqrt_price, u128::from(tick_index_to_sqrt_price(-176)));
+        // The price is exactly at the boundary, so it maps to the boundary tick rather than the one below.
+        assert_eq!(quote.next_tick_index(), -176);
+
+        let quote = swap_quote_by_output_token(quote.token_est_out, false, 0, fusion_pool, test_tick_arrays(), None, None).unwrap();
+        assert_eq!(quote.next_tick_index(), sqrt_price_to_tick_index(quote.next_sqrt_price));
+        assert_eq!(quote.next_tick_index(), -176);
+    }
+
+    #[test]
+    fn test_sqrt_price_limit_from_slippage() {
+        // A 1% price move is a sqrt(0.99) or sqrt(1.01) sqrt price move.
+        assert_eq!(sqrt_price_limit_from_slippage(1 << 64, 100, true), 18354278608861996862);
+        assert_eq!(sqrt_price_limit_from_slippage(1 << 64, 100, false), 18538748355542988169);
+        assert_eq!(sqrt_price_limit_from_slippage(1 << 64, 0, true), 1 << 64);
+
+        // The whole price range is covered by large tolerances.
+        assert_eq!(sqrt_price_limit_from_slippage(1 << 64, 10000, true), MIN_SQRT_PRICE);
+        assert_eq!(sqrt_price_limit_from_slippage(MIN_SQRT_PRICE, 500, true), MIN_SQRT_PRICE);
+        assert_eq!(sqrt_price_limit_from_slippage(MAX_SQRT_PRICE, 500, false), MAX_SQRT_PRICE);
+    }
+
+    #[test]
+    fn test_sqrt_u256() {
+        assert_eq!(sqrt_u256(U256::ZERO), U256::ZERO);
+        assert_eq!(sqrt_u256(U256::from(15u8)), U256::from(3u8));
+        assert_eq!(sqrt_u256(U256::from(16u8)), U256::from(4u8));
+        assert_eq!(sqrt_u256(U256::MAX), U256::from(u128::MAX));
+    }
+
     #[test]
     fn test_swap_quote_throws_if_tick_array_sequence_holds_insufficient_liquidity() {
         let result_3428 = swap_quote_by_input_token(3428, true, 0, test_fusion_pool(1 << 64, false), test_tick_arrays(), None, None).unwrap();
@@ -687,4 +1714,312 @@ mod tests {
         assert_eq!(result_3428.token_in, 3428);
         assert!(matches!(result_3429, Err(INVALID_TICK_ARRAY_SEQUENCE)));
     }
+
+    #[test]
+    fn test_swap_quote_invalid_slippage_tolerance() {
+        let exact_in = swap_quote_by_input_token(1000, true, 10001, test_fusion_pool(1 << 64, true), test_tick_arrays(), None, None);
+        let exact_out = swap_quote_by_output_token(1000, true, 10001, test_fusion_pool(1 << 64, true), test_tick_arrays(), None, None);
+        assert_eq!(exact_in, Err(INVALID_SLIPPAGE_TOLERANCE));
+        assert_eq!(exact_out, Err(INVALID_SLIPPAGE_TOLERANCE));
+    }
+
+    #[test]
+    fn test_swap_quote_max_slippage_tolerance() {
+        let exact_in = swap_quote_by_input_token(1000, true, 10000, test_fusion_pool(1 << 64, true), test_tick_arrays(), None, None).unwrap();
+        let exact_out = swap_quote_by_output_token(1000, true, 10000, test_fusion_pool(1 << 64, true), test_tick_arrays(), None, None).unwrap();
+        assert_eq!(exact_in.token_min_out, 0);
+        assert_eq!(exact_out.token_max_in, exact_out.token_est_in * 2);
+    }
+
+    #[test]
+    fn test_two_hop_exact_out_round_trips_exact_in() {
+        let exact_in = swap_quote_two_hop_by_input_token(
+            1000,
+            true,
+            true,
+            1000,
+            test_fusion_pool(1 << 64, true),
+            test_tick_arrays(),
+            test_fusion_pool(1 << 64, true),
+            test_tick_arrays(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let exact_out = swap_quote_two_hop_by_output_token(
+            exact_in.token_est_out,
+            true,
+            true,
+            1000,
+            test_fusion_pool(1 << 64, true),
+            test_tick_arrays(),
+            test_fusion_pool(1 << 64, true),
+            test_tick_arrays(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(exact_out.token_out, exact_in.token_est_out);
+        assert_eq!(exact_out.token_est_intermediate, exact_in.token_est_intermediate);
+        assert_eq!(exact_out.token_est_in, exact_in.token_in);
+        assert_eq!(exact_out.token_max_in, 1100);
+    }
+
+    #[test]
+    fn test_two_hop_exact_out_with_transfer_fees() {
+        let transfer_fee = Some(TransferFee::new(100));
+        let result = swap_quote_two_hop_by_output_token(
+            1000,
+            true,
+            false,
+            1000,
+            test_fusion_pool(1 << 64, true),
+            test_tick_arrays(),
+            test_fusion_pool(1 << 64, true),
+            test_tick_arrays(),
+            transfer_fee,
+            transfer_fee,
+            transfer_fee,
+        )
+        .unwrap();
+        assert_eq!(result.token_out, 1000);
+        assert!(result.token_est_intermediate > 1000);
+        assert!(result.token_est_in > result.token_est_intermediate);
+        assert_eq!(result.token_max_in, try_get_max_amount_with_slippage_tolerance(result.token_est_in, 1000).unwrap());
+    }
+
+    #[test]
+    fn test_two_hop_exact_out_fails_without_liquidity() {
+        let result_one = swap_quote_two_hop_by_output_token(
+            1000,
+            true,
+            true,
+            1000,
+            test_fusion_pool_with_zero_liquidity(1 << 64),
+            test_empty_tick_arrays(),
+            test_fusion_pool(1 << 64, true),
+            test_tick_arrays(),
+            None,
+            None,
+            None,
+        );
+        let result_two = swap_quote_two_hop_by_output_token(
+            1000,
+            true,
+            true,
+            1000,
+            test_fusion_pool(1 << 64, true),
+            test_tick_arrays(),
+            test_fusion_pool_with_zero_liquidity(1 << 64),
+            test_empty_tick_arrays(),
+            None,
+            None,
+            None,
+        );
+        assert_eq!(result_one, Err(INSUFFICIENT_LIQUIDITY));
+        assert_eq!(result_two, Err(INSUFFICIENT_LIQUIDITY));
+    }
+
+    #[test]
+    fn test_two_hop_exact_in_fails_without_liquidity() {
+        let result_one = swap_quote_two_hop_by_input_token(
+            1000,
+            true,
+            true,
+            1000,
+            test_fusion_pool_with_zero_liquidity(1 << 64),
+            test_empty_tick_arrays(),
+            test_fusion_pool(1 << 64, true),
+            test_tick_arrays(),
+            None,
+            None,
+            None,
+        );
+        let result_two = swap_quote_two_hop_by_input_token(
+            1000,
+            true,
+            true,
+            1000,
+            test_fusion_pool(1 << 64, true),
+            test_tick_arrays(),
+            test_fusion_pool_with_zero_liquidity(1 << 64),
+            test_empty_tick_arrays(),
+            None,
+            None,
+            None,
+        );
+        assert_eq!(result_one, Err(INSUFFICIENT_LIQUIDITY));
+        assert_eq!(result_two, Err(INSUFFICIENT_LIQUIDITY));
+    }
+
+    #[test]
+    fn test_two_hop_exact_in_fails_with_short_output() {
+        // The first leg can't consume all of its input within its tick arrays.
+        let result_short = swap_quote_two_hop_by_input_token(
+            3429,
+            true,
+            true,
+            1000,
+            test_fusion_pool(1 << 64, false),
+            test_tick_arrays(),
+            test_fusion_pool(1 << 64, true),
+            test_tick_arrays(),
+            None,
+            None,
+            None,
+        );
+        // The input is too small to produce any output.
+        let result_zero = swap_quote_two_hop_by_input_token(
+            1,
+            true,
+            true,
+            1000,
+            test_fusion_pool(1 << 64, true),
+            test_tick_arrays(),
+            test_fusion_pool(1 << 64, true),
+            test_tick_arrays(),
+            None,
+            None,
+            None,
+        );
+        assert_eq!(result_short, Err(INSUFFICIENT_LIQUIDITY));
+        assert_eq!(result_zero, Err(INSUFFICIENT_LIQUIDITY));
+    }
+}
+
+#[cfg(all(test, not(feature = "wasm")))]
+mod proptests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::{TickArrayFacade, MAX_ORDER_PROTOCOL_FEE_RATE, MAX_PROTOCOL_FEE_RATE, TICK_ARRAY_SIZE};
+
+    const TICK_SPACING: u16 = 2;
+    const TICK_ARRAY_START_INDEXES: [i32; 6] = [-528, -352, -176, 0, 176, 352];
+
+    /// A pool priced within the middle tick arrays, so that most swaps stay within the tick array sequence.
+    fn fusion_pool_strategy() -> impl Strategy<Value = FusionPoolFacade> {
+        (
+            -176i32..352,
+            0u128..1 << 32,
+            1_000_000u128..1_000_000_000_000,
+            0u16..=30_000,
+            0..=MAX_PROTOCOL_FEE_RATE,
+            0..=MAX_ORDER_PROTOCOL_FEE_RATE,
+            0..=MAX_CLP_REWARD_RATE,
+        )
+            .prop_map(|(tick_index, price_offset, liquidity, fee_rate, protocol_fee_rate, order_protocol_fee_rate, clp_reward_rate)| {
+                // The sqrt price spacing between two adjacent ticks around tick 0 is larger than 2^32.
+                let sqrt_price = u128::from(tick_index_to_sqrt_price(tick_index.into())) + price_offset;
+                FusionPoolFacade {
+                    tick_spacing: TICK_SPACING,
+                    fee_rate,
+                    protocol_fee_rate,
+                    order_protocol_fee_rate,
+                    clp_reward_rate,
+                    liquidity,
+                    sqrt_price,
+                    tick_current_index: sqrt_price_to_tick_index(sqrt_price.into()),
+                    ..FusionPoolFacade::default()
+                }
+            })
+    }
+
+    /// Tick arrays whose initialized ticks add liquidity below and remove it above tick 0, with optional limit orders.
+    /// The liquidity net is small enough for the pool liquidity to stay positive across the whole sequence.
+    /// The sequence is built in the test body, since it's too large to be generated by a strategy.
+    fn test_tick_sequence(liquidity_net: i128, limit_amount: u64) -> TickArraySequence<6> {
+        let tick_arrays = TICK_ARRAY_START_INDEXES.map(|start_tick_index| {
+            let tick = TickFacade {
+                initialized: true,
+                liquidity_net: if start_tick_index < 0 { liquidity_net } else { -liquidity_net },
+                part_filled_orders_input: limit_amount,
+                part_filled_orders_remaining_input: limit_amount,
+                ..TickFacade::default()
+            };
+            Some(TickArrayFacade {
+                start_tick_index,
+                ticks: [tick; TICK_ARRAY_SIZE],
+            })
+        });
+        TickArraySequence::new(tick_arrays, TICK_SPACING).unwrap()
+    }
+
+    fn input_and_output(result: &SwapResult, a_to_b: bool) -> (u64, u64) {
+        if a_to_b {
+            (result.token_a, result.token_b)
+        } else {
+            (result.token_b, result.token_a)
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_compute_swap_exact_in_invariants(
+            fusion_pool in fusion_pool_strategy(),
+            liquidity_net in 0i128..1000,
+            limit_amount in 0u64..100_000,
+            amount in 1u64..10_000_000,
+            a_to_b: bool,
+        ) {
+            let tick_sequence = test_tick_sequence(liquidity_net, limit_amount);
+            let result = compute_swap(amount, 0, fusion_pool, tick_sequence, a_to_b, true);
+            prop_assume!(result.is_ok());
+            let result = result.unwrap();
+            let (token_in, token_out) = input_and_output(&result, a_to_b);
+
+            prop_assert_eq!(token_in, amount);
+            prop_assert!(result.fee_amount <= token_in);
+            prop_assert!(result.clp_reward + result.olp_reward <= result.fee_amount);
+            prop_assert_eq!(result.amount_out_from_clp + result.amount_out_from_limit_orders, token_out);
+            if a_to_b {
+                prop_assert!(result.next_sqrt_price <= fusion_pool.sqrt_price);
+            } else {
+                prop_assert!(result.next_sqrt_price >= fusion_pool.sqrt_price);
+            }
+        }
+
+        #[test]
+        fn test_compute_swap_exact_out_invariants(
+            fusion_pool in fusion_pool_strategy(),
+            liquidity_net in 0i128..1000,
+            limit_amount in 0u64..100_000,
+            amount in 1u64..10_000_000,
+            a_to_b: bool,
+        ) {
+            let tick_sequence = test_tick_sequence(liquidity_net, limit_amount);
+            let result = compute_swap(amount, 0, fusion_pool, tick_sequence, a_to_b, false);
+            prop_assume!(result.is_ok());
+            let result = result.unwrap();
+            let (token_in, token_out) = input_and_output(&result, a_to_b);
+
+            prop_assert_eq!(token_out, amount);
+            prop_assert!(result.fee_amount <= token_in);
+            prop_assert!(result.clp_reward + result.olp_reward <= result.fee_amount);
+            prop_assert_eq!(result.amount_out_from_clp + result.amount_out_from_limit_orders, token_out);
+        }
+
+        #[test]
+        fn test_compute_swap_exact_in_exact_out_round_trip(
+            fusion_pool in fusion_pool_strategy(),
+            liquidity_net in 0i128..1000,
+            limit_amount in 0u64..100_000,
+            amount in 1u64..10_000_000,
+            a_to_b: bool,
+        ) {
+            let tick_sequence = test_tick_sequence(liquidity_net, limit_amount);
+            let exact_in = compute_swap(amount, 0, fusion_pool, tick_sequence.clone(), a_to_b, true);
+            prop_assume!(exact_in.is_ok());
+            let (_, token_out) = input_and_output(&exact_in.unwrap(), a_to_b);
+            prop_assume!(token_out > 0);
+
+            // Buying back the same output never costs more than the input of the exact in swap.
+            let exact_out = compute_swap(token_out, 0, fusion_pool, tick_sequence, a_to_b, false).unwrap();
+            let (token_in, round_trip_out) = input_and_output(&exact_out, a_to_b);
+            prop_assert_eq!(round_trip_out, token_out);
+            prop_assert!(token_in <= amount, "exact out input {} exceeds exact in input {}", token_in, amount);
+        }
+    }
 }
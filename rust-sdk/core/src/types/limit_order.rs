@@ -24,4 +24,8 @@ pub struct LimitOrderDecreaseQuote {
     pub amount_out_b: u64,
     pub reward_a: u64,
     pub reward_b: u64,
+    /// The unfilled part of the decreased amount, returned in the order input token (before transfer fees).
+    pub principal_returned: u64,
+    /// The filled part of the decreased amount converted to the order output token (before transfer fees, without rewards).
+    pub proceeds: u64,
 }
@@ -32,3 +32,17 @@ pub struct IncreaseLiquidityQuote {
     pub token_max_a: u64,
     pub token_max_b: u64,
 }
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct TokenAmounts {
+    pub amount_a: u64,
+    pub amount_b: u64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct DepositSwap {
+    pub a_to_b: bool,
+    pub amount_in: u64,
+}
@@ -13,6 +13,11 @@
 #[cfg(feature = "wasm")]
 use fusionamm_macros::wasm_expose;
 
+use crate::{
+    sqrt_price_to_tick_index, CoreError, INVALID_FEE_RATE, MAX_CLP_REWARD_RATE, MAX_FEE_RATE, MAX_ORDER_PROTOCOL_FEE_RATE, MAX_PROTOCOL_FEE_RATE,
+    MAX_SQRT_PRICE, MIN_SQRT_PRICE, SQRT_PRICE_OUT_OF_BOUNDS, TICK_INDEX_INCONSISTENT_WITH_SQRT_PRICE,
+};
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "wasm", wasm_expose)]
 pub struct FusionPoolFacade {
@@ -33,3 +38,135 @@ pub struct FusionPoolFacade {
     pub olp_fee_owed_a: u64,
     pub olp_fee_owed_b: u64,
 }
+
+/// Check a fusion pool for internal consistency before using it in quotes.
+///
+/// # Parameters
+/// - `pool` - The fusion pool to check
+///
+/// # Returns
+/// - `Ok`: If the sqrt price is within bounds, the current tick index is within one tick of the sqrt price
+///   and the fee rates are within their maxima
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn validate_fusion_pool(pool: FusionPoolFacade) -> Result<(), CoreError> {
+    if pool.sqrt_price < MIN_SQRT_PRICE || pool.sqrt_price > MAX_SQRT_PRICE {
+        return Err(SQRT_PRICE_OUT_OF_BOUNDS);
+    }
+
+    // The current tick index is one less than the tick index of the sqrt price
+    // if the price has been moved to a tick boundary by an A to B swap.
+    let expected_tick_index = sqrt_price_to_tick_index(pool.sqrt_price.into());
+    if (pool.tick_current_index - expected_tick_index).abs() > 1 {
+        return Err(TICK_INDEX_INCONSISTENT_WITH_SQRT_PRICE);
+    }
+
+    if pool.fee_rate > MAX_FEE_RATE
+        || pool.protocol_fee_rate > MAX_PROTOCOL_FEE_RATE
+        || pool.order_protocol_fee_rate > MAX_ORDER_PROTOCOL_FEE_RATE
+        || pool.clp_reward_rate > MAX_CLP_REWARD_RATE
+    {
+        return Err(INVALID_FEE_RATE);
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, not(feature = "wasm")))]
+mod tests {
+    use super::*;
+    use crate::tick_index_to_sqrt_price;
+
+    fn test_pool() -> FusionPoolFacade {
+        FusionPoolFacade {
+            tick_spacing: 64,
+            fee_rate: 3000,
+            protocol_fee_rate: 2500,
+            clp_reward_rate: 10000,
+            order_protocol_fee_rate: 10000,
+            sqrt_price: tick_index_to_sqrt_price(100).into(),
+            tick_current_index: 100,
+            ..FusionPoolFacade::default()
+        }
+    }
+
+    #[test]
+    fn test_validate_valid_fusion_pool() {
+        assert_eq!(validate_fusion_pool(test_pool()), Ok(()));
+        assert_eq!(
+            validate_fusion_pool(FusionPoolFacade {
+                tick_current_index: 99,
+                ..test_pool()
+            }),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_validate_fusion_pool_sqrt_price_out_of_bounds() {
+        let pool = FusionPoolFacade {
+            sqrt_price: MIN_SQRT_PRICE - 1,
+            ..test_pool()
+        };
+        assert_eq!(validate_fusion_pool(pool), Err(SQRT_PRICE_OUT_OF_BOUNDS));
+        let pool = FusionPoolFacade {
+            sqrt_price: MAX_SQRT_PRICE + 1,
+            ..test_pool()
+        };
+        assert_eq!(validate_fusion_pool(pool), Err(SQRT_PRICE_OUT_OF_BOUNDS));
+    }
+
+    #[test]
+    fn test_validate_fusion_pool_inconsistent_tick_index() {
+        let pool = FusionPoolFacade {
+            tick_current_index: 102,
+            ..test_pool()
+        };
+        assert_eq!(validate_fusion_pool(pool), Err(TICK_INDEX_INCONSISTENT_WITH_SQRT_PRICE));
+        let pool = FusionPoolFacade {
+            tick_current_index: 98,
+            ..test_pool()
+        };
+        assert_eq!(validate_fusion_pool(pool), Err(TICK_INDEX_INCONSISTENT_WITH_SQRT_PRICE));
+    }
+
+    #[test]
+    fn test_validate_fusion_pool_invalid_fee_rate() {
+        let pool = FusionPoolFacade {
+            fee_rate: MAX_FEE_RATE,
+            ..test_pool()
+        };
+        assert_eq!(validate_fusion_pool(pool), Ok(()));
+        let pool = FusionPoolFacade {
+            fee_rate: MAX_FEE_RATE + 1,
+            ..test_pool()
+        };
+        assert_eq!(validate_fusion_pool(pool), Err(INVALID_FEE_RATE));
+    }
+
+    #[test]
+    fn test_validate_fusion_pool_invalid_protocol_fee_rate() {
+        let pool = FusionPoolFacade {
+            protocol_fee_rate: MAX_PROTOCOL_FEE_RATE + 1,
+            ..test_pool()
+        };
+        assert_eq!(validate_fusion_pool(pool), Err(INVALID_FEE_RATE));
+    }
+
+    #[test]
+    fn test_validate_fusion_pool_invalid_order_protocol_fee_rate() {
+        let pool = FusionPoolFacade {
+            order_protocol_fee_rate: MAX_ORDER_PROTOCOL_FEE_RATE + 1,
+            ..test_pool()
+        };
+        assert_eq!(validate_fusion_pool(pool), Err(INVALID_FEE_RATE));
+    }
+
+    #[test]
+    fn test_validate_fusion_pool_invalid_clp_reward_rate() {
+        let pool = FusionPoolFacade {
+            clp_reward_rate: MAX_CLP_REWARD_RATE + 1,
+            ..test_pool()
+        };
+        assert_eq!(validate_fusion_pool(pool), Err(INVALID_FEE_RATE));
+    }
+}
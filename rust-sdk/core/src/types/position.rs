@@ -40,3 +40,41 @@ pub struct PositionFacade {
     pub fee_growth_checkpoint_b: u128,
     pub fee_owed_b: u64,
 }
+
+#[cfg(not(feature = "wasm"))]
+pub type PositionFacades = Vec<PositionFacade>;
+
+#[cfg(feature = "wasm")]
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+
+#[cfg(feature = "wasm")]
+use js_sys::Array;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "PositionFacade[]")]
+    pub type PositionFacades;
+}
+
+#[cfg(feature = "wasm")]
+impl Debug for PositionFacades {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{:?}", JsValue::from(self))
+    }
+}
+
+#[cfg(feature = "wasm")]
+impl From<PositionFacades> for Vec<PositionFacade> {
+    fn from(val: PositionFacades) -> Self {
+        let val = JsValue::from(val);
+        if !val.is_array() {
+            return Vec::new();
+        }
+        let array: Array = val.unchecked_into();
+        array.iter().filter_map(|item| serde_wasm_bindgen::from_value(item).ok()).collect()
+    }
+}
@@ -10,9 +10,36 @@
 
 #![allow(non_snake_case)]
 
+use crate::{format_token_amount, sqrt_price_to_tick_index};
 #[cfg(feature = "wasm")]
 use fusionamm_macros::wasm_expose;
 
+/// The direction of a swap.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub enum SwapDirection {
+    /// Swap token A for token B.
+    AtoB,
+    /// Swap token B for token A.
+    BtoA,
+}
+
+impl From<bool> for SwapDirection {
+    fn from(a_to_b: bool) -> Self {
+        if a_to_b {
+            SwapDirection::AtoB
+        } else {
+            SwapDirection::BtoA
+        }
+    }
+}
+
+impl From<SwapDirection> for bool {
+    fn from(direction: SwapDirection) -> Self {
+        direction == SwapDirection::AtoB
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "wasm", wasm_expose)]
 pub struct ExactInSwapQuote {
@@ -21,6 +48,30 @@ pub struct ExactInSwapQuote {
     pub token_min_out: u64,
     pub trade_fee: u64,
     pub next_sqrt_price: u128,
+    /// The part of the output (before transfer fees) filled by resting limit orders.
+    pub amount_out_from_limit_orders: u64,
+    /// The part of the output (before transfer fees) filled by the concentrated liquidity.
+    pub amount_out_from_clp: u64,
+    /// The part of the trade fee paid to concentrated liquidity providers.
+    pub clp_reward: u64,
+    /// The part of the trade fee paid to limit order providers.
+    pub olp_reward: u64,
+}
+
+impl ExactInSwapQuote {
+    /// Formats the input amount and the estimated output amount as decimal strings.
+    pub fn format(&self, decimals_in: u8, decimals_out: u8) -> (String, String) {
+        (format_token_amount(self.token_in, decimals_in), format_token_amount(self.token_est_out, decimals_out))
+    }
+
+    /// Returns the tick index of the pool price after the swap.
+    ///
+    /// This is the highest tick whose sqrt price is lower than or equal to `next_sqrt_price`, so a price
+    /// exactly at a tick boundary maps to the boundary tick. Note that the program stores the boundary tick
+    /// minus one when an A to B swap ends exactly at a tick boundary, since the tick is crossed.
+    pub fn next_tick_index(&self) -> i32 {
+        sqrt_price_to_tick_index(self.next_sqrt_price.into())
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
@@ -32,3 +83,54 @@ pub struct ExactOutSwapQuote {
     pub trade_fee: u64,
     pub next_sqrt_price: u128,
 }
+
+impl ExactOutSwapQuote {
+    /// Returns the tick index of the pool price after the swap.
+    ///
+    /// Uses the same rounding as `ExactInSwapQuote::next_tick_index`: a price exactly at a tick boundary
+    /// maps to the boundary tick.
+    pub fn next_tick_index(&self) -> i32 {
+        sqrt_price_to_tick_index(self.next_sqrt_price.into())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct TwoHopExactInSwapQuote {
+    pub token_in: u64,
+    pub token_est_intermediate: u64,
+    pub token_est_out: u64,
+    pub token_min_out: u64,
+    pub trade_fee_one: u64,
+    pub trade_fee_two: u64,
+    pub next_sqrt_price_one: u128,
+    pub next_sqrt_price_two: u128,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct TwoHopExactOutSwapQuote {
+    pub token_out: u64,
+    pub token_est_intermediate: u64,
+    pub token_est_in: u64,
+    pub token_max_in: u64,
+    pub trade_fee_one: u64,
+    pub trade_fee_two: u64,
+    pub next_sqrt_price_one: u128,
+    pub next_sqrt_price_two: u128,
+}
+
+#[cfg(all(test, not(feature = "wasm")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_exact_in_swap_quote() {
+        let quote = ExactInSwapQuote {
+            token_in: 2_500_000_000,
+            token_est_out: 1_234,
+            ..ExactInSwapQuote::default()
+        };
+        assert_eq!(quote.format(9, 6), ("2.500000000".to_string(), "0.001234".to_string()));
+    }
+}
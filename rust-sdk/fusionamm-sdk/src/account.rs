@@ -10,13 +10,20 @@
 
 use serde::Deserialize;
 use serde_json::from_value;
+use solana_account::Account;
 use solana_account_decoder::UiAccountData;
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::rpc_request::RpcError;
 use solana_client::{nonblocking::rpc_client::RpcClient, rpc_request::TokenAccountsFilter};
 use solana_program::pubkey::Pubkey;
 use solana_program::rent::Rent;
 use solana_program::sysvar::SysvarId;
+use std::future::Future;
+use std::time::{Duration, Instant};
 use std::{error::Error, str::FromStr};
 
+use crate::{RPC_RETRIES, RPC_RETRY_BACKOFF};
+
 #[derive(Debug, Clone)]
 pub struct ParsedTokenAccount {
     pub pubkey: Pubkey,
@@ -75,7 +82,241 @@ pub(crate) async fn get_token_accounts_for_owner(
 }
 
 pub(crate) async fn get_rent(rpc: &RpcClient) -> Result<Rent, Box<dyn Error>> {
-    let rent = rpc.get_account(&Rent::id()).await?;
+    let rent = fetch_account(rpc, &Rent::id()).await?;
     let rent: Rent = bincode::deserialize(&rent.data)?;
     Ok(rent)
 }
+
+/// Whether an RPC error is worth retrying: transport and IO failures, and JSON-RPC server errors
+/// such as an unhealthy or lagging node. Invalid requests and other client errors fail immediately.
+pub(crate) fn is_transient_rpc_error(error: &ClientError) -> bool {
+    match error.kind() {
+        ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_) => true,
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => (-32099..=-32000).contains(code) || *code == -32603,
+        _ => false,
+    }
+}
+
+/// Runs an RPC request, retrying up to `retries` times if it fails with a transient error.
+/// The delay between attempts starts at `backoff` and doubles with every retry.
+async fn with_retry<T, F, Fut>(retries: u32, backoff: Duration, request: F) -> Result<T, ClientError>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T, ClientError>>,
+{
+    let mut attempt = 0;
+    loop {
+        match request().await {
+            Err(e) if attempt < retries && is_transient_rpc_error(&e) => {
+                tokio::time::sleep(backoff.saturating_mul(1 << attempt.min(16))).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Fetches an account, retrying up to `retries` times if the RPC request fails with a transient error.
+/// The delay between attempts starts at `backoff` and doubles with every retry.
+/// Missing accounts are reported immediately without retrying.
+///
+/// The account is requested with getMultipleAccounts because `RpcClient::get_account_with_commitment`
+/// wraps every failure in a `ForUser` error, which hides whether it is transient.
+pub(crate) async fn get_account_with_retry(rpc: &RpcClient, pubkey: &Pubkey, retries: u32, backoff: Duration) -> Result<Account, Box<dyn Error>> {
+    let accounts = get_multiple_accounts_with_retry(rpc, &[*pubkey], retries, backoff).await?;
+    accounts
+        .into_iter()
+        .next()
+        .flatten()
+        .ok_or(format!("Account {} not found", pubkey).into())
+}
+
+/// Fetches several accounts in one request, retrying up to `retries` times if it fails with a transient error.
+/// Missing accounts are returned as `None`, like `RpcClient::get_multiple_accounts`.
+pub(crate) async fn get_multiple_accounts_with_retry(
+    rpc: &RpcClient,
+    pubkeys: &[Pubkey],
+    retries: u32,
+    backoff: Duration,
+) -> Result<Vec<Option<Account>>, Box<dyn Error>> {
+    Ok(with_retry(retries, backoff, || rpc.get_multiple_accounts(pubkeys)).await?)
+}
+
+/// Fetches an account using the currently selected RPC retry configuration.
+pub(crate) async fn fetch_account(rpc: &RpcClient, pubkey: &Pubkey) -> Result<Account, Box<dyn Error>> {
+    let retries = *RPC_RETRIES.try_lock()?;
+    let backoff = *RPC_RETRY_BACKOFF.try_lock()?;
+    get_account_with_retry(rpc, pubkey, retries, backoff).await
+}
+
+/// Fetches several accounts using the currently selected RPC retry configuration.
+pub(crate) async fn fetch_multiple_accounts(rpc: &RpcClient, pubkeys: &[Pubkey]) -> Result<Vec<Option<Account>>, Box<dyn Error>> {
+    let retries = *RPC_RETRIES.try_lock()?;
+    let backoff = *RPC_RETRY_BACKOFF.try_lock()?;
+    get_multiple_accounts_with_retry(rpc, pubkeys, retries, backoff).await
+}
+
+/// The delay between two polls of `wait_for_account`.
+const WAIT_FOR_ACCOUNT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Polls an account until its decoded state satisfies a predicate.
+///
+/// This is useful to wait for the effects of a transaction sent with a low commitment level,
+/// for example to wait until a position is visible with the expected liquidity.
+/// A missing account and transient RPC errors are polled again until the timeout.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `address` - The public key of the account to poll.
+/// * `decode` - The function decoding the account data, e.g. `Position::from_bytes`.
+/// * `predicate` - The condition the decoded account must satisfy.
+/// * `timeout` - The maximum time to wait for the condition.
+///
+/// # Returns
+///
+/// A `Result` containing the decoded account on success.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The account data cannot be decoded.
+/// - The condition is not satisfied within the timeout.
+pub async fn wait_for_account<T, E, F>(
+    rpc: &RpcClient,
+    address: Pubkey,
+    decode: fn(&[u8]) -> Result<T, E>,
+    predicate: F,
+    timeout: Duration,
+) -> Result<T, Box<dyn Error>>
+where
+    E: Error + 'static,
+    F: Fn(&T) -> bool,
+{
+    let start = Instant::now();
+    loop {
+        if let Ok(response) = rpc.get_account_with_commitment(&address, rpc.commitment()).await {
+            if let Some(account) = response.value {
+                let decoded = decode(&account.data)?;
+                if predicate(&decoded) {
+                    return Ok(decoded);
+                }
+            }
+        }
+        if start.elapsed() >= timeout {
+            return Err(format!("Account {} did not reach the expected state in {} ms", address, timeout.as_millis()).into());
+        }
+        tokio::time::sleep(WAIT_FOR_ACCOUNT_INTERVAL.min(timeout)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{setup_ata_with_amount, setup_fusion_pool, setup_mint, setup_position, RpcContext};
+    use crate::{fetch_fusion_pool_tick_arrays, increase_liquidity_instructions, reset_configuration, set_rpc_retries, IncreaseLiquidityParam};
+    use fusionamm_client::{get_position_address, Position};
+    use serial_test::serial;
+    use solana_program_test::tokio;
+    use solana_signer::Signer;
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_account_with_retry() {
+        let ctx = RpcContext::new().await;
+        let mint_a = setup_mint(&ctx).await.unwrap();
+        let mint_b = setup_mint(&ctx).await.unwrap();
+        let pool_address = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 300).await.unwrap();
+
+        ctx.fail_next_get_account_requests(1);
+        let result = get_account_with_retry(&ctx.rpc, &pool_address, 0, Duration::ZERO).await;
+        assert!(result.is_err());
+
+        ctx.fail_next_get_account_requests(2);
+        let result = get_account_with_retry(&ctx.rpc, &pool_address, 2, Duration::from_millis(1)).await;
+        assert_eq!(result.unwrap().owner, fusionamm_client::FUSIONAMM_ID);
+
+        // A permanent error is returned without retrying.
+        ctx.reject_next_get_account_requests(1);
+        let result = get_account_with_retry(&ctx.rpc, &pool_address, 2, Duration::from_millis(1)).await;
+        assert!(result.is_err());
+        let result = get_account_with_retry(&ctx.rpc, &pool_address, 0, Duration::ZERO).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_multiple_accounts_with_retry() {
+        let ctx = RpcContext::new().await;
+        let mint_a = setup_mint(&ctx).await.unwrap();
+        let mint_b = setup_mint(&ctx).await.unwrap();
+
+        ctx.fail_next_get_account_requests(1);
+        let result = get_multiple_accounts_with_retry(&ctx.rpc, &[mint_a, mint_b], 0, Duration::ZERO).await;
+        assert!(result.is_err());
+
+        ctx.fail_next_get_account_requests(2);
+        let accounts = get_multiple_accounts_with_retry(&ctx.rpc, &[mint_a, mint_b, Pubkey::new_unique()], 2, Duration::from_millis(1))
+            .await
+            .unwrap();
+        assert_eq!(accounts.len(), 3);
+        assert_eq!(accounts[0].as_ref().unwrap().owner, spl_token::ID);
+        assert_eq!(accounts[1].as_ref().unwrap().owner, spl_token::ID);
+        assert!(accounts[2].is_none());
+
+        ctx.reject_next_get_account_requests(1);
+        let result = get_multiple_accounts_with_retry(&ctx.rpc, &[mint_a, mint_b], 2, Duration::from_millis(1)).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_instruction_build_survives_transient_rpc_error() {
+        let ctx = RpcContext::new().await;
+        let mint_a = setup_mint(&ctx).await.unwrap();
+        let mint_b = setup_mint(&ctx).await.unwrap();
+        let pool_address = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 300).await.unwrap();
+        set_rpc_retries(1, Duration::from_millis(1)).unwrap();
+
+        ctx.fail_next_get_account_requests(1);
+        let result = fetch_fusion_pool_tick_arrays(&ctx.rpc, pool_address, 1).await;
+
+        reset_configuration().unwrap();
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_wait_for_account() {
+        let ctx = RpcContext::new().await;
+        let mint_a = setup_mint(&ctx).await.unwrap();
+        let mint_b = setup_mint(&ctx).await.unwrap();
+        setup_ata_with_amount(&ctx, mint_a, 1_000_000_000).await.unwrap();
+        setup_ata_with_amount(&ctx, mint_b, 1_000_000_000).await.unwrap();
+        let pool_address = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 300).await.unwrap();
+        let position_mint = setup_position(&ctx, pool_address, Some((-128, 128)), None).await.unwrap();
+        let position_address = get_position_address(&position_mint).unwrap().0;
+
+        // The position is empty, so the condition is never satisfied.
+        let result = wait_for_account(&ctx.rpc, position_address, Position::from_bytes, |p| p.liquidity > 0, Duration::from_millis(10)).await;
+        assert!(result.is_err());
+
+        let liquidity_ix = increase_liquidity_instructions(
+            &ctx.rpc,
+            position_mint,
+            IncreaseLiquidityParam::Liquidity(1_000_000),
+            Some(100),
+            Some(ctx.signer.pubkey()),
+        )
+        .await
+        .unwrap();
+        ctx.send_transaction_with_signers(liquidity_ix.instructions, liquidity_ix.additional_signers.iter().collect())
+            .await
+            .unwrap();
+
+        let position = wait_for_account(&ctx.rpc, position_address, Position::from_bytes, |p| p.liquidity > 0, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(position.liquidity, 1_000_000);
+    }
+}
@@ -9,7 +9,7 @@
 //
 
 use solana_program::pubkey::Pubkey;
-use std::{error::Error, sync::Mutex};
+use std::{error::Error, sync::Mutex, time::Duration};
 
 /// The default funder for the FusionPools program.
 pub const DEFAULT_FUNDER: Pubkey = Pubkey::new_from_array([0; 32]);
@@ -35,6 +35,19 @@ pub fn set_slippage_tolerance_bps(tolerance: u16) -> Result<(), Box<dyn Error>>
     Ok(())
 }
 
+/// The default number of tick arrays on each side of the current price fetched for a swap.
+pub const DEFAULT_TICK_ARRAY_RADIUS: u16 = 2;
+
+/// The currently selected number of tick arrays on each side of the current price fetched for a swap.
+/// At most `MAX_SWAP_TICK_ARRAYS` tick arrays are included in a swap, favouring the ones in the swap direction.
+pub static TICK_ARRAY_RADIUS: Mutex<u16> = Mutex::new(DEFAULT_TICK_ARRAY_RADIUS);
+
+/// Sets the currently selected number of tick arrays on each side of the current price fetched for a swap.
+pub fn set_tick_array_radius(radius: u16) -> Result<(), Box<dyn Error>> {
+    *TICK_ARRAY_RADIUS.try_lock()? = radius;
+    Ok(())
+}
+
 /// Defines the strategy for handling SOL wrapping in a transaction.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum NativeMintWrappingStrategy {
@@ -65,11 +78,48 @@ pub fn set_native_mint_wrapping_strategy(strategy: NativeMintWrappingStrategy) -
     Ok(())
 }
 
+/// The default number of retries for account fetches failing with a transient RPC error.
+pub const DEFAULT_RPC_RETRIES: u32 = 3;
+
+/// The default delay before the first retry of an account fetch. It doubles with every retry.
+pub const DEFAULT_RPC_RETRY_BACKOFF: Duration = Duration::from_millis(200);
+
+/// The currently selected number of retries for account fetches.
+pub static RPC_RETRIES: Mutex<u32> = Mutex::new(DEFAULT_RPC_RETRIES);
+
+/// The currently selected delay before the first retry of an account fetch.
+pub static RPC_RETRY_BACKOFF: Mutex<Duration> = Mutex::new(DEFAULT_RPC_RETRY_BACKOFF);
+
+/// Sets the currently selected number of retries and initial backoff for account fetches.
+pub fn set_rpc_retries(retries: u32, backoff: Duration) -> Result<(), Box<dyn Error>> {
+    *RPC_RETRIES.try_lock()? = retries;
+    *RPC_RETRY_BACKOFF.try_lock()? = backoff;
+    Ok(())
+}
+
+/// The default for whether transfer fees are estimated conservatively.
+pub const DEFAULT_CONSERVATIVE_TRANSFER_FEE: bool = false;
+
+/// Whether transfer fees are estimated conservatively. If enabled, the higher of the fees of the current
+/// and the next epoch is applied, so that a transaction landing after an epoch boundary doesn't underestimate
+/// a newly scheduled transfer fee.
+pub static CONSERVATIVE_TRANSFER_FEE: Mutex<bool> = Mutex::new(DEFAULT_CONSERVATIVE_TRANSFER_FEE);
+
+/// Sets whether transfer fees are estimated conservatively.
+pub fn set_conservative_transfer_fee(conservative: bool) -> Result<(), Box<dyn Error>> {
+    *CONSERVATIVE_TRANSFER_FEE.try_lock()? = conservative;
+    Ok(())
+}
+
 /// Resets the configuration to its default values.
 pub fn reset_configuration() -> Result<(), Box<dyn Error>> {
     *FUNDER.try_lock()? = DEFAULT_FUNDER;
     *NATIVE_MINT_WRAPPING_STRATEGY.try_lock()? = DEFAULT_NATIVE_MINT_WRAPPING_STRATEGY;
     *SLIPPAGE_TOLERANCE_BPS.try_lock()? = DEFAULT_SLIPPAGE_TOLERANCE_BPS;
+    *TICK_ARRAY_RADIUS.try_lock()? = DEFAULT_TICK_ARRAY_RADIUS;
+    *RPC_RETRIES.try_lock()? = DEFAULT_RPC_RETRIES;
+    *RPC_RETRY_BACKOFF.try_lock()? = DEFAULT_RPC_RETRY_BACKOFF;
+    *CONSERVATIVE_TRANSFER_FEE.try_lock()? = DEFAULT_CONSERVATIVE_TRANSFER_FEE;
     Ok(())
 }
 
@@ -106,6 +156,31 @@ mod tests {
         reset_configuration().unwrap();
     }
 
+    #[test]
+    #[serial]
+    fn test_set_tick_array_radius() {
+        set_tick_array_radius(1).unwrap();
+        assert_eq!(*TICK_ARRAY_RADIUS.lock().unwrap(), 1);
+        reset_configuration().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn test_set_rpc_retries() {
+        set_rpc_retries(5, Duration::from_millis(50)).unwrap();
+        assert_eq!(*RPC_RETRIES.lock().unwrap(), 5);
+        assert_eq!(*RPC_RETRY_BACKOFF.lock().unwrap(), Duration::from_millis(50));
+        reset_configuration().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn test_set_conservative_transfer_fee() {
+        set_conservative_transfer_fee(true).unwrap();
+        assert!(*CONSERVATIVE_TRANSFER_FEE.lock().unwrap());
+        reset_configuration().unwrap();
+    }
+
     #[test]
     #[serial]
     fn test_reset_configuration() {
@@ -113,5 +188,9 @@ mod tests {
         assert_eq!(*FUNDER.lock().unwrap(), Pubkey::default());
         assert_eq!(*NATIVE_MINT_WRAPPING_STRATEGY.lock().unwrap(), NativeMintWrappingStrategy::Keypair);
         assert_eq!(*SLIPPAGE_TOLERANCE_BPS.lock().unwrap(), 100);
+        assert_eq!(*TICK_ARRAY_RADIUS.lock().unwrap(), 2);
+        assert_eq!(*RPC_RETRIES.lock().unwrap(), 3);
+        assert_eq!(*RPC_RETRY_BACKOFF.lock().unwrap(), Duration::from_millis(200));
+        assert!(!*CONSERVATIVE_TRANSFER_FEE.lock().unwrap());
     }
 }
@@ -22,10 +22,10 @@ use solana_program::sysvar::SysvarId;
 use solana_program::{instruction::Instruction, pubkey::Pubkey};
 use solana_sdk_ids::system_program;
 use solana_signer::Signer;
-use spl_token_2022::extension::StateWithExtensions;
+use spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
 use spl_token_2022::state::Mint;
 
-use crate::{get_account_data_size, get_rent, order_mints, FUNDER};
+use crate::{fetch_multiple_accounts, get_account_data_size, get_rent, order_mints, FUNDER};
 
 /// Represents the instructions and metadata for creating a pool.
 pub struct CreatePoolInstructions {
@@ -42,6 +42,55 @@ pub struct CreatePoolInstructions {
     pub additional_signers: Vec<Keypair>,
 }
 
+/// Mint extensions that can only be used in a pool if the mint has a token badge.
+const TOKEN_BADGE_EXTENSIONS: [ExtensionType; 4] = [
+    ExtensionType::PermanentDelegate,
+    ExtensionType::TransferHook,
+    ExtensionType::MintCloseAuthority,
+    ExtensionType::DefaultAccountState,
+];
+
+fn get_token_badge_extensions(mint: &StateWithExtensions<Mint>) -> Result<Vec<ExtensionType>, Box<dyn Error>> {
+    let extensions = mint.get_extension_types()?;
+    Ok(TOKEN_BADGE_EXTENSIONS
+        .into_iter()
+        .filter(|extension| extensions.contains(extension))
+        .collect())
+}
+
+/// Expected price volatility of a token pair, used to suggest pool parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VolatilityTier {
+    /// Pegged pairs such as stablecoins or liquid staking tokens.
+    Stable,
+    /// Established pairs with moderate price movement.
+    Standard,
+    /// Exotic or long-tail pairs with large price movement.
+    Volatile,
+}
+
+/// Returns the recommended `(tick_spacing, fee_rate)` pair for a pool of the given volatility tier.
+///
+/// The fee rate is expressed in hundredths of a basis point (`1_000_000` = 100%):
+/// - `Stable` - tick spacing 1 and 0.01% fee.
+/// - `Standard` - tick spacing 64 and 0.3% fee.
+/// - `Volatile` - tick spacing 128 and 1% fee.
+///
+/// # Arguments
+///
+/// * `volatility_hint` - The expected price volatility of the token pair.
+///
+/// # Returns
+///
+/// A `(tick_spacing, fee_rate)` tuple that can be passed to `create_fusion_pool_instructions`.
+pub fn suggested_pool_params(volatility_hint: VolatilityTier) -> (u16, u16) {
+    match volatility_hint {
+        VolatilityTier::Stable => (1, 100),
+        VolatilityTier::Standard => (64, 3000),
+        VolatilityTier::Volatile => (128, 10000),
+    }
+}
+
 /// Creates the necessary instructions to initialize a Concentrated Liquidity Pool (CLMM).
 ///
 /// # Arguments
@@ -68,6 +117,7 @@ pub struct CreatePoolInstructions {
 /// - The funder account is invalid.
 /// - Token mints are not found or have invalid data.
 /// - The token mint order does not match the canonical byte order.
+/// - A token mint has extensions that require a token badge, but the token badge account does not exist.
 /// - Any RPC request to the blockchain fails.
 ///
 /// # Example
@@ -123,13 +173,16 @@ pub async fn create_fusion_pool_instructions(
     if funder == Pubkey::default() {
         return Err("Funder must be provided".into());
     }
-    if order_mints(token_a, token_b)[0] != token_a {
+    if order_mints(token_a, token_b)?[0] != token_a {
         return Err("Token order needs to be flipped to match the canonical ordering (i.e. sorted on the byte repr. of the mint pubkeys)".into());
     }
 
     let rent = get_rent(rpc).await?;
 
-    let account_infos = rpc.get_multiple_accounts(&[token_a, token_b]).await?;
+    let token_badge_a = get_token_badge_address(&token_a)?.0;
+    let token_badge_b = get_token_badge_address(&token_b)?.0;
+
+    let account_infos = fetch_multiple_accounts(rpc, &[token_a, token_b, token_badge_a, token_badge_b]).await?;
     let mint_a_info = account_infos[0].as_ref().ok_or(format!("Mint {} not found", token_a))?;
     let mint_a = StateWithExtensions::<Mint>::unpack(&mint_a_info.data)?;
     let decimals_a = mint_a.base.decimals;
@@ -139,11 +192,23 @@ pub async fn create_fusion_pool_instructions(
     let decimals_b = mint_b.base.decimals;
     let token_program_b = mint_b_info.owner;
 
+    for (mint_address, mint, token_badge, token_badge_info) in [
+        (token_a, &mint_a, token_badge_a, &account_infos[2]),
+        (token_b, &mint_b, token_badge_b, &account_infos[3]),
+    ] {
+        let extensions = get_token_badge_extensions(mint)?;
+        if !extensions.is_empty() && token_badge_info.is_none() {
+            return Err(format!(
+                "Token badge {} is missing for mint {} with extensions that require a badge: {:?}",
+                token_badge, mint_address, extensions
+            )
+            .into());
+        }
+    }
+
     let initial_sqrt_price: u128 = price_to_sqrt_price(initial_price, decimals_a, decimals_b);
 
     let pool_address = get_fusion_pool_address(&token_a, &token_b, tick_spacing)?.0;
-    let token_badge_a = get_token_badge_address(&token_a)?.0;
-    let token_badge_b = get_token_badge_address(&token_b)?.0;
 
     let token_vault_a = Keypair::new();
     let token_vault_b = Keypair::new();
@@ -212,6 +277,8 @@ pub async fn create_fusion_pool_instructions(
 #[cfg(test)]
 mod tests {
     use crate::tests::{setup_mint, setup_mint_te, setup_mint_te_fee, RpcContext};
+    use fusionamm_core::{FEE_RATE_MUL_VALUE, FULL_RANGE_ONLY_TICK_SPACING_THRESHOLD};
+    use spl_token_2022::extension::ExtensionType;
 
     use super::*;
     use serial_test::serial;
@@ -221,6 +288,19 @@ mod tests {
         FusionPool::from_bytes(&account.data).map_err(|e| e.into())
     }
 
+    #[test]
+    fn test_suggested_pool_params() {
+        assert_eq!(suggested_pool_params(VolatilityTier::Stable), (1, 100));
+        assert_eq!(suggested_pool_params(VolatilityTier::Standard), (64, 3000));
+        assert_eq!(suggested_pool_params(VolatilityTier::Volatile), (128, 10000));
+
+        for tier in [VolatilityTier::Stable, VolatilityTier::Standard, VolatilityTier::Volatile] {
+            let (tick_spacing, fee_rate) = suggested_pool_params(tier);
+            assert!(tick_spacing < FULL_RANGE_ONLY_TICK_SPACING_THRESHOLD);
+            assert!((fee_rate as u32) < FEE_RATE_MUL_VALUE);
+        }
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_error_if_no_funder() {
@@ -392,4 +472,24 @@ mod tests {
         assert_eq!(64, pool_after.tick_spacing);
         assert_eq!(300, pool_after.fee_rate);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_error_if_token_badge_missing() {
+        let ctx = RpcContext::new().await;
+        let mint = setup_mint(&ctx).await.unwrap();
+        let mint_te = setup_mint_te(&ctx, &[ExtensionType::TransferFeeConfig, ExtensionType::PermanentDelegate])
+            .await
+            .unwrap();
+        let (mint_a, mint_b) = if mint < mint_te { (mint, mint_te) } else { (mint_te, mint) };
+
+        let result = create_fusion_pool_instructions(&ctx.rpc, mint_a, mint_b, 64, 300, Some(1.0), Some(ctx.signer.pubkey())).await;
+
+        let error = result.err().unwrap().to_string();
+        let token_badge = get_token_badge_address(&mint_te).unwrap().0;
+        assert!(error.contains(&token_badge.to_string()));
+        assert!(error.contains(&mint_te.to_string()));
+        assert!(error.contains("PermanentDelegate"));
+        assert!(!error.contains("TransferFeeConfig"));
+    }
 }
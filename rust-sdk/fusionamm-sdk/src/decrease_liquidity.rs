@@ -9,10 +9,13 @@
 //
 
 use crate::{
-    token::{get_current_transfer_fee, prepare_token_accounts_instructions, TokenAccountStrategy},
-    FUNDER, SLIPPAGE_TOLERANCE_BPS,
+    fetch_account, fetch_multiple_accounts,
+    token::{
+        get_current_transfer_fee, get_transfer_hook_extra_accounts, prepare_token_accounts_instructions, RemainingAccounts, TokenAccountStrategy,
+    },
+    CONSERVATIVE_TRANSFER_FEE, FUNDER, SLIPPAGE_TOLERANCE_BPS,
 };
-use fusionamm_client::{get_position_address, get_tick_array_address, FusionPool, Position, TickArray};
+use fusionamm_client::{get_position_address, get_tick_array_address, AccountsType, FusionPool, Position, TickArray};
 use fusionamm_client::{ClosePosition, CollectFees, CollectFeesInstructionArgs, DecreaseLiquidity, DecreaseLiquidityInstructionArgs};
 use fusionamm_core::{
     collect_fees_quote, decrease_liquidity_quote, decrease_liquidity_quote_a, decrease_liquidity_quote_b, get_tick_array_start_tick_index,
@@ -24,7 +27,6 @@ use solana_keypair::Keypair;
 use solana_pubkey::Pubkey;
 use spl_associated_token_account::get_associated_token_address_with_program_id;
 use std::{collections::HashSet, error::Error};
-// TODO: support transfer hooks
 
 /// Represents the parameters for decreasing liquidity in a pool.
 ///
@@ -78,6 +80,8 @@ pub struct DecreaseLiquidityInstruction {
 /// * `instructions` - A vector of `Instruction` objects required to execute the decrease liquidity operation.
 /// * `additional_signers` - A vector of `Keypair` objects representing additional signers required for the instructions.
 ///
+/// If the decrease would withdraw no tokens, e.g. for a zero liquidity delta, no instructions are returned.
+///
 /// # Errors
 ///
 /// This function will return an error if:
@@ -127,23 +131,23 @@ pub async fn decrease_liquidity_instructions(
     }
 
     let position_address = get_position_address(&position_mint_address)?.0;
-    let position_info = rpc.get_account(&position_address).await?;
+    let position_info = fetch_account(rpc, &position_address).await?;
     let position = Position::from_bytes(&position_info.data)?;
 
-    let pool_info = rpc.get_account(&position.fusion_pool).await?;
+    let pool_info = fetch_account(rpc, &position.fusion_pool).await?;
     let pool = FusionPool::from_bytes(&pool_info.data)?;
 
-    let mint_infos = rpc
-        .get_multiple_accounts(&[pool.token_mint_a, pool.token_mint_b, position_mint_address])
-        .await?;
+    let mint_infos = fetch_multiple_accounts(rpc, &[pool.token_mint_a, pool.token_mint_b, position_mint_address]).await?;
 
     let mint_a_info = mint_infos[0].as_ref().ok_or("Token A mint info not found")?;
     let mint_b_info = mint_infos[1].as_ref().ok_or("Token B mint info not found")?;
     let position_mint_info = mint_infos[2].as_ref().ok_or("Position mint info not found")?;
 
     let current_epoch = rpc.get_epoch_info().await?.epoch;
-    let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), current_epoch);
-    let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), current_epoch);
+
+    let conservative_transfer_fee = *CONSERVATIVE_TRANSFER_FEE.try_lock()?;
+    let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), current_epoch, conservative_transfer_fee);
+    let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), current_epoch, conservative_transfer_fee);
 
     let quote = match param {
         DecreaseLiquidityParam::TokenA(amount) => decrease_liquidity_quote_a(
@@ -175,6 +179,15 @@ pub async fn decrease_liquidity_instructions(
         ),
     }?;
 
+    // Nothing would be withdrawn, so skip the instruction that would fail on-chain.
+    if quote.token_est_a == 0 && quote.token_est_b == 0 {
+        return Ok(DecreaseLiquidityInstruction {
+            quote,
+            instructions: vec![],
+            additional_signers: vec![],
+        });
+    }
+
     let mut instructions: Vec<Instruction> = Vec::new();
 
     let lower_tick_array_start_index = get_tick_array_start_tick_index(position.tick_lower_index, pool.tick_spacing);
@@ -205,6 +218,34 @@ pub async fn decrease_liquidity_instructions(
         .get(&pool.token_mint_b)
         .ok_or("Token B owner account not found")?;
 
+    let mut remaining_accounts = RemainingAccounts::default();
+    remaining_accounts.add(
+        AccountsType::TransferHookA,
+        get_transfer_hook_extra_accounts(
+            rpc,
+            pool.token_mint_a,
+            mint_a_info,
+            pool.token_vault_a,
+            *token_owner_account_a,
+            position.fusion_pool,
+            quote.token_est_a,
+        )
+        .await?,
+    );
+    remaining_accounts.add(
+        AccountsType::TransferHookB,
+        get_transfer_hook_extra_accounts(
+            rpc,
+            pool.token_mint_b,
+            mint_b_info,
+            pool.token_vault_b,
+            *token_owner_account_b,
+            position.fusion_pool,
+            quote.token_est_b,
+        )
+        .await?,
+    );
+
     instructions.push(
         DecreaseLiquidity {
             fusion_pool: position.fusion_pool,
@@ -223,12 +264,15 @@ pub async fn decrease_liquidity_instructions(
             tick_array_lower: lower_tick_array_address,
             tick_array_upper: upper_tick_array_address,
         }
-        .instruction(DecreaseLiquidityInstructionArgs {
-            liquidity_amount: quote.liquidity_delta,
-            token_min_a: quote.token_min_a,
-            token_min_b: quote.token_min_b,
-            remaining_accounts_info: None,
-        }),
+        .instruction_with_remaining_accounts(
+            DecreaseLiquidityInstructionArgs {
+                liquidity_amount: quote.liquidity_delta,
+                token_min_a: quote.token_min_a,
+                token_min_b: quote.token_min_b,
+                remaining_accounts_info: remaining_accounts.info(),
+            },
+            &remaining_accounts.accounts,
+        ),
     );
 
     instructions.extend(token_accounts.cleanup_instructions);
@@ -266,7 +310,8 @@ pub struct ClosePositionInstruction {
 ///
 /// This function collects all fees and rewards, removes any remaining liquidity, and closes
 /// the position. It returns the necessary instructions, quotes for fees and rewards, and the
-/// liquidity quote for the closed position.
+/// liquidity quote for the closed position. Unless `force` is set, a position that still holds
+/// liquidity or uncollected fees is not closed.
 ///
 /// # Arguments
 ///
@@ -274,6 +319,8 @@ pub struct ClosePositionInstruction {
 /// * `position_mint_address` - The public key of the NFT mint address representing the position to be closed.
 /// * `slippage_tolerance_bps` - An optional slippage tolerance in basis points. Defaults to the global slippage tolerance if not provided.
 /// * `authority` - An optional public key of the account authorizing the transaction. Defaults to the global funder if not provided.
+/// * `force` - If true, the remaining liquidity is removed and the fees are collected before closing the position.
+///   If false, an error is returned for a position that isn't empty.
 ///
 /// # Returns
 ///
@@ -291,6 +338,7 @@ pub struct ClosePositionInstruction {
 /// This function will return an error if:
 /// - The `authority` account is invalid or missing.
 /// - The position, token mint, or reward accounts are not found or have invalid data.
+/// - The position holds liquidity or uncollected fees and `force` is false.
 /// - Any RPC request to the blockchain fails.
 ///
 /// # Example
@@ -314,6 +362,7 @@ pub struct ClosePositionInstruction {
 ///         position_mint_address,
 ///         Some(100),
 ///         Some(wallet.pubkey()),
+///         true,
 ///     )
 ///     .await
 ///     .unwrap();
@@ -328,6 +377,7 @@ pub async fn close_position_instructions(
     position_mint_address: Pubkey,
     slippage_tolerance_bps: Option<u16>,
     authority: Option<Pubkey>,
+    force: bool,
 ) -> Result<ClosePositionInstruction, Box<dyn Error>> {
     let slippage_tolerance_bps = slippage_tolerance_bps.unwrap_or(*SLIPPAGE_TOLERANCE_BPS.try_lock()?);
     let authority = authority.unwrap_or(*FUNDER.try_lock()?);
@@ -336,23 +386,23 @@ pub async fn close_position_instructions(
     }
 
     let position_address = get_position_address(&position_mint_address)?.0;
-    let position_info = rpc.get_account(&position_address).await?;
+    let position_info = fetch_account(rpc, &position_address).await?;
     let position = Position::from_bytes(&position_info.data)?;
 
-    let pool_info = rpc.get_account(&position.fusion_pool).await?;
+    let pool_info = fetch_account(rpc, &position.fusion_pool).await?;
     let pool = FusionPool::from_bytes(&pool_info.data)?;
 
-    let mint_infos = rpc
-        .get_multiple_accounts(&[pool.token_mint_a, pool.token_mint_b, position_mint_address])
-        .await?;
+    let mint_infos = fetch_multiple_accounts(rpc, &[pool.token_mint_a, pool.token_mint_b, position_mint_address]).await?;
 
     let mint_a_info = mint_infos[0].as_ref().ok_or("Token A mint info not found")?;
     let mint_b_info = mint_infos[1].as_ref().ok_or("Token B mint info not found")?;
     let position_mint_info = mint_infos[2].as_ref().ok_or("Position mint info not found")?;
 
     let current_epoch = rpc.get_epoch_info().await?.epoch;
-    let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), current_epoch);
-    let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), current_epoch);
+
+    let conservative_transfer_fee = *CONSERVATIVE_TRANSFER_FEE.try_lock()?;
+    let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), current_epoch, conservative_transfer_fee);
+    let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), current_epoch, conservative_transfer_fee);
 
     let quote = decrease_liquidity_quote(
         position.liquidity,
@@ -371,7 +421,7 @@ pub async fn close_position_instructions(
     let lower_tick_array_address = get_tick_array_address(&position.fusion_pool, lower_tick_array_start_index)?.0;
     let upper_tick_array_address = get_tick_array_address(&position.fusion_pool, upper_tick_array_start_index)?.0;
 
-    let tick_array_infos = rpc.get_multiple_accounts(&[lower_tick_array_address, upper_tick_array_address]).await?;
+    let tick_array_infos = fetch_multiple_accounts(rpc, &[lower_tick_array_address, upper_tick_array_address]).await?;
 
     let lower_tick_array_info = tick_array_infos[0].as_ref().ok_or("Lower tick array info not found")?;
     let lower_tick_array = TickArray::from_bytes(&lower_tick_array_info.data)?;
@@ -392,6 +442,10 @@ pub async fn close_position_instructions(
         transfer_fee_b,
     )?;
 
+    if !force && (position.liquidity > 0 || fees_quote.fee_owed_a > 0 || fees_quote.fee_owed_b > 0) {
+        return Err(format!("Position {} holds liquidity or uncollected fees, set force to close it anyway", position_address).into());
+    }
+
     let mut required_mints: HashSet<TokenAccountStrategy> = HashSet::new();
 
     if quote.liquidity_delta > 0 || fees_quote.fee_owed_a > 0 || fees_quote.fee_owed_b > 0 {
@@ -404,16 +458,46 @@ pub async fn close_position_instructions(
     let mut instructions: Vec<Instruction> = Vec::new();
     instructions.extend(token_accounts.create_instructions);
 
+    // The token accounts are only prepared if there are tokens to withdraw.
     let token_owner_account_a = token_accounts
         .token_account_addresses
         .get(&pool.token_mint_a)
-        .ok_or("Token A owner account not found")?;
+        .ok_or("Token A owner account not found");
     let token_owner_account_b = token_accounts
         .token_account_addresses
         .get(&pool.token_mint_b)
-        .ok_or("Token B owner account not found")?;
+        .ok_or("Token B owner account not found");
 
     if quote.liquidity_delta > 0 {
+        let (token_owner_account_a, token_owner_account_b) = (token_owner_account_a?, token_owner_account_b?);
+        let mut remaining_accounts = RemainingAccounts::default();
+        remaining_accounts.add(
+            AccountsType::TransferHookA,
+            get_transfer_hook_extra_accounts(
+                rpc,
+                pool.token_mint_a,
+                mint_a_info,
+                pool.token_vault_a,
+                *token_owner_account_a,
+                position.fusion_pool,
+                quote.token_est_a,
+            )
+            .await?,
+        );
+        remaining_accounts.add(
+            AccountsType::TransferHookB,
+            get_transfer_hook_extra_accounts(
+                rpc,
+                pool.token_mint_b,
+                mint_b_info,
+                pool.token_vault_b,
+                *token_owner_account_b,
+                position.fusion_pool,
+                quote.token_est_b,
+            )
+            .await?,
+        );
+
         instructions.push(
             DecreaseLiquidity {
                 fusion_pool: position.fusion_pool,
@@ -432,16 +516,48 @@ pub async fn close_position_instructions(
                 tick_array_lower: lower_tick_array_address,
                 tick_array_upper: upper_tick_array_address,
             }
-            .instruction(DecreaseLiquidityInstructionArgs {
-                liquidity_amount: quote.liquidity_delta,
-                token_min_a: quote.token_min_a,
-                token_min_b: quote.token_min_b,
-                remaining_accounts_info: None,
-            }),
+            .instruction_with_remaining_accounts(
+                DecreaseLiquidityInstructionArgs {
+                    liquidity_amount: quote.liquidity_delta,
+                    token_min_a: quote.token_min_a,
+                    token_min_b: quote.token_min_b,
+                    remaining_accounts_info: remaining_accounts.info(),
+                },
+                &remaining_accounts.accounts,
+            ),
         );
     }
 
     if fees_quote.fee_owed_a > 0 || fees_quote.fee_owed_b > 0 {
+        let (token_owner_account_a, token_owner_account_b) = (token_owner_account_a?, token_owner_account_b?);
+        let mut remaining_accounts = RemainingAccounts::default();
+        remaining_accounts.add(
+            AccountsType::TransferHookA,
+            get_transfer_hook_extra_accounts(
+                rpc,
+                pool.token_mint_a,
+                mint_a_info,
+                pool.token_vault_a,
+                *token_owner_account_a,
+                position.fusion_pool,
+                fees_quote.fee_owed_a,
+            )
+            .await?,
+        );
+        remaining_accounts.add(
+            AccountsType::TransferHookB,
+            get_transfer_hook_extra_accounts(
+                rpc,
+                pool.token_mint_b,
+                mint_b_info,
+                pool.token_vault_b,
+                *token_owner_account_b,
+                position.fusion_pool,
+                fees_quote.fee_owed_b,
+            )
+            .await?,
+        );
+
         instructions.push(
             CollectFees {
                 fusion_pool: position.fusion_pool,
@@ -458,9 +574,12 @@ pub async fn close_position_instructions(
                 token_program_b: mint_b_info.owner,
                 memo_program: spl_memo::ID,
             }
-            .instruction(CollectFeesInstructionArgs {
-                remaining_accounts_info: None,
-            }),
+            .instruction_with_remaining_accounts(
+                CollectFeesInstructionArgs {
+                    remaining_accounts_info: remaining_accounts.info(),
+                },
+                &remaining_accounts.accounts,
+            ),
         );
     }
 
@@ -708,6 +827,39 @@ mod tests {
         });
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_decrease_liquidity_zero_delta() {
+        let ctx = RpcContext::new().await;
+        let mint_a = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+        let mint_b = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+        setup_ata_with_amount(&ctx, mint_a, 1_000_000).await.unwrap();
+        setup_ata_with_amount(&ctx, mint_b, 1_000_000).await.unwrap();
+        let (mint_a, mint_b) = if mint_a < mint_b { (mint_a, mint_b) } else { (mint_b, mint_a) };
+        let pool_pubkey = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 300).await.unwrap();
+        let position_mint = setup_position(&ctx, pool_pubkey, Some((-100, 100)), None).await.unwrap();
+
+        let inc_ix = increase_liquidity_instructions(
+            &ctx.rpc,
+            position_mint,
+            IncreaseLiquidityParam::Liquidity(100_000),
+            Some(100),
+            Some(ctx.signer.pubkey()),
+        )
+        .await
+        .unwrap();
+        ctx.send_transaction_with_signers(inc_ix.instructions, vec![]).await.unwrap();
+
+        let dec_ix =
+            decrease_liquidity_instructions(&ctx.rpc, position_mint, DecreaseLiquidityParam::Liquidity(0), Some(100), Some(ctx.signer.pubkey()))
+                .await
+                .unwrap();
+
+        assert_eq!(dec_ix.quote.liquidity_delta, 0);
+        assert!(dec_ix.instructions.is_empty());
+        assert!(dec_ix.additional_signers.is_empty());
+    }
+
     #[rstest]
     #[case("A-B",    "equally centered", -100, 100)]
     #[case("A-B",    "one sided A",      -100, -1)]
@@ -761,7 +913,7 @@ mod tests {
         let before_a = get_token_balance(&ctx.rpc, if swapped { user_atas[mkey_b] } else { user_atas[mkey_a] }).await?;
         let before_b = get_token_balance(&ctx.rpc, if swapped { user_atas[mkey_a] } else { user_atas[mkey_b] }).await?;
 
-        let close_ix = close_position_instructions(&ctx.rpc, position_mint, Some(100), Some(ctx.signer.pubkey())).await?;
+        let close_ix = close_position_instructions(&ctx.rpc, position_mint, Some(100), Some(ctx.signer.pubkey()), true).await?;
         let signers: Vec<&Keypair> = close_ix.additional_signers.iter().collect();
         ctx.send_transaction_with_signers(close_ix.instructions.clone(), signers).await?;
 
@@ -790,6 +942,47 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_close_position_requires_force_if_not_empty() -> Result<(), Box<dyn Error>> {
+        let ctx = RpcContext::new().await;
+        let mint_a = setup_mint_with_decimals(&ctx, 9).await?;
+        let mint_b = setup_mint_with_decimals(&ctx, 9).await?;
+        let (mint_a, mint_b) = if mint_a < mint_b { (mint_a, mint_b) } else { (mint_b, mint_a) };
+        setup_ata_with_amount(&ctx, mint_a, 1_000_000_000).await?;
+        setup_ata_with_amount(&ctx, mint_b, 1_000_000_000).await?;
+
+        let pool_pubkey = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 300).await?;
+
+        let empty_position_mint = setup_position(&ctx, pool_pubkey, Some((-128, 128)), None).await?;
+        let close_ix = close_position_instructions(&ctx.rpc, empty_position_mint, Some(100), Some(ctx.signer.pubkey()), false).await?;
+        ctx.send_transaction_with_signers(close_ix.instructions, close_ix.additional_signers.iter().collect())
+            .await?;
+
+        let position_mint = setup_position(&ctx, pool_pubkey, Some((-128, 128)), None).await?;
+        let inc_ix = increase_liquidity_instructions(
+            &ctx.rpc,
+            position_mint,
+            IncreaseLiquidityParam::Liquidity(100_000),
+            Some(100),
+            Some(ctx.signer.pubkey()),
+        )
+        .await?;
+        ctx.send_transaction_with_signers(inc_ix.instructions, vec![]).await?;
+
+        let res = close_position_instructions(&ctx.rpc, position_mint, Some(100), Some(ctx.signer.pubkey()), false).await;
+        assert!(res.is_err(), "Expected error when closing a position with liquidity without force");
+
+        let close_ix = close_position_instructions(&ctx.rpc, position_mint, Some(100), Some(ctx.signer.pubkey()), true).await?;
+        assert_eq!(close_ix.quote.liquidity_delta, 100_000);
+        ctx.send_transaction_with_signers(close_ix.instructions, close_ix.additional_signers.iter().collect())
+            .await?;
+
+        let position_address = get_position_address(&position_mint)?.0;
+        assert!(maybe_fetch_position(&ctx.rpc, position_address).await?.is_none());
+        Ok(())
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_close_position_fails_if_missing_mint() -> Result<(), Box<dyn Error>> {
@@ -797,7 +990,7 @@ mod tests {
 
         let bogus_mint = Pubkey::new_unique();
 
-        let res = close_position_instructions(&ctx.rpc, bogus_mint, Some(100), Some(ctx.signer.pubkey())).await;
+        let res = close_position_instructions(&ctx.rpc, bogus_mint, Some(100), Some(ctx.signer.pubkey()), true).await;
 
         assert!(res.is_err(), "Expected error when position mint doesn't exist");
 
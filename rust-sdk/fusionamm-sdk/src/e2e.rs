@@ -179,7 +179,7 @@ impl TestContext {
         let token_a_before = Account::unpack(&before_infos[0].as_ref().unwrap().data)?;
         let token_b_before = Account::unpack(&before_infos[1].as_ref().unwrap().data)?;
 
-        let close_position = close_position_instructions(&self.ctx.rpc, position_mint, None, Some(self.ctx.signer.pubkey())).await?;
+        let close_position = close_position_instructions(&self.ctx.rpc, position_mint, None, Some(self.ctx.signer.pubkey()), true).await?;
         self.ctx
             .send_transaction_with_signers(close_position.instructions, close_position.additional_signers.iter().collect())
             .await?;
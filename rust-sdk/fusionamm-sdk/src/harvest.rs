@@ -9,10 +9,13 @@
 //
 
 use crate::{
-    token::{get_current_transfer_fee, prepare_token_accounts_instructions, TokenAccountStrategy},
-    FUNDER,
+    account::{fetch_account, fetch_multiple_accounts},
+    token::{
+        get_current_transfer_fee, get_transfer_hook_extra_accounts, prepare_token_accounts_instructions, RemainingAccounts, TokenAccountStrategy,
+    },
+    CONSERVATIVE_TRANSFER_FEE, FUNDER,
 };
-use fusionamm_client::{get_position_address, get_tick_array_address, FusionPool, Position, TickArray};
+use fusionamm_client::{get_position_address, get_tick_array_address, AccountsType, FusionPool, Position, TickArray};
 use fusionamm_client::{CollectFees, CollectFeesInstructionArgs, UpdateFees};
 use fusionamm_core::{collect_fees_quote, get_tick_array_start_tick_index, get_tick_index_in_array, CollectFeesQuote};
 use solana_client::nonblocking::rpc_client::RpcClient;
@@ -22,8 +25,6 @@ use solana_pubkey::Pubkey;
 use spl_associated_token_account::get_associated_token_address_with_program_id;
 use std::{collections::HashSet, error::Error};
 
-// TODO: support transfer hooks
-
 /// Represents the instructions and quotes for harvesting a position.
 ///
 /// This struct contains the instructions required to harvest a position, along with detailed
@@ -105,23 +106,23 @@ pub async fn harvest_position_instructions(
     }
 
     let position_address = get_position_address(&position_mint_address)?.0;
-    let position_info = rpc.get_account(&position_address).await?;
+    let position_info = fetch_account(rpc, &position_address).await?;
     let position = Position::from_bytes(&position_info.data)?;
 
-    let pool_info = rpc.get_account(&position.fusion_pool).await?;
+    let pool_info = fetch_account(rpc, &position.fusion_pool).await?;
     let pool = FusionPool::from_bytes(&pool_info.data)?;
 
-    let mint_infos = rpc
-        .get_multiple_accounts(&[pool.token_mint_a, pool.token_mint_b, position_mint_address])
-        .await?;
+    let mint_infos = fetch_multiple_accounts(rpc, &[pool.token_mint_a, pool.token_mint_b, position_mint_address]).await?;
 
     let mint_a_info = mint_infos[0].as_ref().ok_or("Token A mint info not found")?;
     let mint_b_info = mint_infos[1].as_ref().ok_or("Token B mint info not found")?;
     let position_mint_info = mint_infos[2].as_ref().ok_or("Position mint info not found")?;
 
     let current_epoch = rpc.get_epoch_info().await?.epoch;
-    let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), current_epoch);
-    let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), current_epoch);
+
+    let conservative_transfer_fee = *CONSERVATIVE_TRANSFER_FEE.try_lock()?;
+    let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), current_epoch, conservative_transfer_fee);
+    let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), current_epoch, conservative_transfer_fee);
 
     let lower_tick_array_start_index = get_tick_array_start_tick_index(position.tick_lower_index, pool.tick_spacing);
     let upper_tick_array_start_index = get_tick_array_start_tick_index(position.tick_upper_index, pool.tick_spacing);
@@ -130,7 +131,7 @@ pub async fn harvest_position_instructions(
     let lower_tick_array_address = get_tick_array_address(&position.fusion_pool, lower_tick_array_start_index)?.0;
     let upper_tick_array_address = get_tick_array_address(&position.fusion_pool, upper_tick_array_start_index)?.0;
 
-    let tick_array_infos = rpc.get_multiple_accounts(&[lower_tick_array_address, upper_tick_array_address]).await?;
+    let tick_array_infos = fetch_multiple_accounts(rpc, &[lower_tick_array_address, upper_tick_array_address]).await?;
 
     let lower_tick_array_info = tick_array_infos[0].as_ref().ok_or("Lower tick array info not found")?;
     let lower_tick_array = TickArray::from_bytes(&lower_tick_array_info.data)?;
@@ -185,6 +186,34 @@ pub async fn harvest_position_instructions(
             .get(&pool.token_mint_b)
             .ok_or("Token B owner account not found")?;
 
+        let mut remaining_accounts = RemainingAccounts::default();
+        remaining_accounts.add(
+            AccountsType::TransferHookA,
+            get_transfer_hook_extra_accounts(
+                rpc,
+                pool.token_mint_a,
+                mint_a_info,
+                pool.token_vault_a,
+                *token_owner_account_a,
+                position.fusion_pool,
+                fees_quote.fee_owed_a,
+            )
+            .await?,
+        );
+        remaining_accounts.add(
+            AccountsType::TransferHookB,
+            get_transfer_hook_extra_accounts(
+                rpc,
+                pool.token_mint_b,
+                mint_b_info,
+                pool.token_vault_b,
+                *token_owner_account_b,
+                position.fusion_pool,
+                fees_quote.fee_owed_b,
+            )
+            .await?,
+        );
+
         instructions.push(
             CollectFees {
                 fusion_pool: position.fusion_pool,
@@ -201,9 +230,12 @@ pub async fn harvest_position_instructions(
                 token_program_b: mint_b_info.owner,
                 memo_program: spl_memo::ID,
             }
-            .instruction(CollectFeesInstructionArgs {
-                remaining_accounts_info: None,
-            }),
+            .instruction_with_remaining_accounts(
+                CollectFeesInstructionArgs {
+                    remaining_accounts_info: remaining_accounts.info(),
+                },
+                &remaining_accounts.accounts,
+            ),
         );
     }
     instructions.extend(token_accounts.cleanup_instructions);
@@ -220,22 +252,31 @@ mod tests {
     use std::collections::HashMap;
     use std::error::Error;
 
-    use fusionamm_client::{get_position_address, Position};
+    use fusionamm_client::{
+        decode_instruction, get_fusion_pools_config_address, get_position_address, get_token_badge_address, AccountsType, DecodedFusionInstruction,
+        InitializeTokenBadge, Position, RemainingAccountsInfo, RemainingAccountsSlice,
+    };
     use serial_test::serial;
     use solana_client::nonblocking::rpc_client::RpcClient;
     use solana_program_test::tokio;
     use spl_token::state::Account as TokenAccount;
-    use spl_token_2022::{extension::StateWithExtensionsOwned, state::Account as TokenAccount2022, ID as TOKEN_2022_PROGRAM_ID};
+    use spl_token_2022::{
+        extension::{ExtensionType, StateWithExtensionsOwned},
+        state::Account as TokenAccount2022,
+        ID as TOKEN_2022_PROGRAM_ID,
+    };
+    use spl_transfer_hook_interface::get_extra_account_metas_address;
 
     use crate::{
-        harvest_position_instructions, increase_liquidity_instructions, swap_instructions,
+        harvest_position_instructions, increase_liquidity_instructions, order_mints, swap_instructions,
         tests::{
             setup_ata_te, setup_ata_with_amount, setup_fusion_pool, setup_mint_te, setup_mint_te_fee, setup_mint_with_decimals, setup_position,
-            RpcContext, SetupAtaConfig,
+            setup_transfer_hook_validation_account, RpcContext, SetupAtaConfig, TEST_TRANSFER_HOOK_PROGRAM_ID,
         },
         HarvestPositionInstruction, IncreaseLiquidityParam, SwapType,
     };
     use rstest::rstest;
+    use solana_instruction::AccountMeta;
     use solana_keypair::Keypair;
     use solana_program::program_pack::Pack;
     use solana_pubkey::Pubkey;
@@ -428,4 +469,77 @@ mod tests {
             verify_harvest_position(&ctx, &harvest_ix, *ata_a, *ata_b, position_mint).await.unwrap();
         });
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_harvest_position_with_transfer_hook() -> Result<(), Box<dyn Error>> {
+        let ctx = RpcContext::new().await;
+
+        let mint_hook = setup_mint_te(&ctx, &[ExtensionType::TransferHook]).await?;
+        let mint_other = setup_mint_with_decimals(&ctx, 6).await?;
+        let extra_account = setup_transfer_hook_validation_account(&ctx, mint_hook).await?;
+
+        // Mints with a transfer hook require a token badge.
+        ctx.send_transaction(vec![InitializeTokenBadge {
+            fusion_pools_config: get_fusion_pools_config_address()?.0,
+            token_badge_authority: ctx.signer.pubkey(),
+            token_mint: mint_hook,
+            token_badge: get_token_badge_address(&mint_hook)?.0,
+            funder: ctx.signer.pubkey(),
+            system_program: solana_program::system_program::id(),
+        }
+        .instruction()])
+            .await?;
+
+        let [mint_a, mint_b] = order_mints(mint_hook, mint_other)?;
+        let pool_pubkey = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 300).await?;
+        let position_mint = setup_position(&ctx, pool_pubkey, Some((-128, 128)), None).await?;
+
+        // The test hook program can't execute transfers, so the owed fees are written directly to the position.
+        let position_address = get_position_address(&position_mint)?.0;
+        let mut position_account = ctx.rpc.get_account(&position_address).await?;
+        let fee_owed_a_offset = 8 + 2 + 32 + 32 + 16 + 4 + 4 + 16;
+        let fee_owed_b_offset = fee_owed_a_offset + 8 + 16;
+        position_account.data[fee_owed_a_offset..fee_owed_a_offset + 8].copy_from_slice(&1_000u64.to_le_bytes());
+        position_account.data[fee_owed_b_offset..fee_owed_b_offset + 8].copy_from_slice(&2_000u64.to_le_bytes());
+        ctx.set_account(position_address, position_account).await;
+        let position = fetch_position(&ctx.rpc, position_address).await?;
+        assert_eq!((position.fee_owed_a, position.fee_owed_b), (1_000, 2_000));
+
+        let harvest_ix = harvest_position_instructions(&ctx.rpc, position_mint, Some(ctx.signer.pubkey())).await?;
+        let collect_ix = harvest_ix.instructions.iter().find(|ix| {
+            let accounts: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+            matches!(decode_instruction(&ix.data, &accounts), Some(DecodedFusionInstruction::CollectFees { .. }))
+        });
+        let collect_ix = collect_ix.ok_or("CollectFees instruction not found")?;
+        let accounts: Vec<Pubkey> = collect_ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        let Some(DecodedFusionInstruction::CollectFees { args, .. }) = decode_instruction(&collect_ix.data, &accounts) else {
+            unreachable!();
+        };
+
+        let accounts_type = if mint_a == mint_hook {
+            AccountsType::TransferHookA
+        } else {
+            AccountsType::TransferHookB
+        };
+        assert_eq!(
+            args.remaining_accounts_info,
+            Some(RemainingAccountsInfo {
+                slices: vec![RemainingAccountsSlice { accounts_type, length: 3 }],
+            })
+        );
+
+        // The extra accounts are appended after the accounts of the instruction.
+        let validation_address = get_extra_account_metas_address(&mint_hook, &TEST_TRANSFER_HOOK_PROGRAM_ID);
+        assert_eq!(
+            collect_ix.accounts[collect_ix.accounts.len() - 3..],
+            [
+                AccountMeta::new(extra_account, false),
+                AccountMeta::new_readonly(TEST_TRANSFER_HOOK_PROGRAM_ID, false),
+                AccountMeta::new_readonly(validation_address, false),
+            ]
+        );
+
+        Ok(())
+    }
 }
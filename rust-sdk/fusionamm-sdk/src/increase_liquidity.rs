@@ -8,13 +8,16 @@
 // See the LICENSE file in the project root for license information.
 //
 
-use crate::get_rent;
+use crate::message::{estimate_transaction_size, MAX_TRANSACTION_SIZE};
+use crate::{fetch_account, fetch_multiple_accounts, get_rent};
 use crate::{
-    token::{get_current_transfer_fee, prepare_token_accounts_instructions, TokenAccountStrategy},
-    FUNDER, SLIPPAGE_TOLERANCE_BPS,
+    token::{
+        get_current_transfer_fee, get_transfer_hook_extra_accounts, prepare_token_accounts_instructions, RemainingAccounts, TokenAccountStrategy,
+    },
+    CONSERVATIVE_TRANSFER_FEE, FUNDER, SLIPPAGE_TOLERANCE_BPS,
 };
 use fusionamm_client::{
-    get_position_address, get_tick_array_address, FusionPool, InitializeTickArray, InitializeTickArrayInstructionArgs, OpenPosition,
+    get_position_address, get_tick_array_address, AccountsType, FusionPool, InitializeTickArray, InitializeTickArrayInstructionArgs, OpenPosition,
     OpenPositionInstructionArgs, Position, TickArray, FP_NFT_UPDATE_AUTH,
 };
 use fusionamm_client::{IncreaseLiquidity, IncreaseLiquidityInstructionArgs};
@@ -31,6 +34,7 @@ use solana_pubkey::Pubkey;
 use solana_signer::Signer;
 use spl_associated_token_account::get_associated_token_address_with_program_id;
 use spl_token_2022::state::Mint;
+use std::collections::HashMap;
 use std::error::Error;
 
 pub enum PriceOrTickIndex {
@@ -38,8 +42,6 @@ pub enum PriceOrTickIndex {
     Price(f64),
 }
 
-// TODO: support transfer hooks
-
 fn get_increase_liquidity_quote(
     param: IncreaseLiquidityParam,
     slippage_tolerance_bps: u16,
@@ -192,23 +194,23 @@ pub async fn increase_liquidity_instructions(
     }
 
     let position_address = get_position_address(&position_mint_address)?.0;
-    let position_info = rpc.get_account(&position_address).await?;
+    let position_info = fetch_account(rpc, &position_address).await?;
     let position = Position::from_bytes(&position_info.data)?;
 
-    let pool_info = rpc.get_account(&position.fusion_pool).await?;
+    let pool_info = fetch_account(rpc, &position.fusion_pool).await?;
     let pool = FusionPool::from_bytes(&pool_info.data)?;
 
-    let mint_infos = rpc
-        .get_multiple_accounts(&[pool.token_mint_a, pool.token_mint_b, position_mint_address])
-        .await?;
+    let mint_infos = fetch_multiple_accounts(rpc, &[pool.token_mint_a, pool.token_mint_b, position_mint_address]).await?;
 
     let mint_a_info = mint_infos[0].as_ref().ok_or("Token A mint info not found")?;
     let mint_b_info = mint_infos[1].as_ref().ok_or("Token B mint info not found")?;
     let position_mint_info = mint_infos[2].as_ref().ok_or("Position mint info not found")?;
 
     let current_epoch = rpc.get_epoch_info().await?.epoch;
-    let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), current_epoch);
-    let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), current_epoch);
+
+    let conservative_transfer_fee = *CONSERVATIVE_TRANSFER_FEE.try_lock()?;
+    let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), current_epoch, conservative_transfer_fee);
+    let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), current_epoch, conservative_transfer_fee);
 
     let quote = get_increase_liquidity_quote(
         param,
@@ -220,15 +222,6 @@ pub async fn increase_liquidity_instructions(
         transfer_fee_b,
     )?;
 
-    let mut instructions: Vec<Instruction> = Vec::new();
-
-    let lower_tick_array_start_index = get_tick_array_start_tick_index(position.tick_lower_index, pool.tick_spacing);
-    let upper_tick_array_start_index = get_tick_array_start_tick_index(position.tick_upper_index, pool.tick_spacing);
-
-    let position_token_account_address = get_associated_token_address_with_program_id(&authority, &position_mint_address, &position_mint_info.owner);
-    let lower_tick_array_address = get_tick_array_address(&position.fusion_pool, lower_tick_array_start_index)?.0;
-    let upper_tick_array_address = get_tick_array_address(&position.fusion_pool, upper_tick_array_start_index)?.0;
-
     let token_accounts = prepare_token_accounts_instructions(
         rpc,
         authority,
@@ -239,8 +232,6 @@ pub async fn increase_liquidity_instructions(
     )
     .await?;
 
-    instructions.extend(token_accounts.create_instructions);
-
     let token_owner_account_a = token_accounts
         .token_account_addresses
         .get(&pool.token_mint_a)
@@ -250,32 +241,24 @@ pub async fn increase_liquidity_instructions(
         .get(&pool.token_mint_b)
         .ok_or("Token B owner account not found")?;
 
+    let mut instructions: Vec<Instruction> = Vec::new();
+    instructions.extend(token_accounts.create_instructions);
     instructions.push(
-        IncreaseLiquidity {
-            fusion_pool: position.fusion_pool,
-            token_program_a: mint_a_info.owner,
-            token_program_b: mint_b_info.owner,
-            memo_program: spl_memo::ID,
-            position_authority: authority,
-            position: position_address,
-            position_token_account: position_token_account_address,
-            token_mint_a: pool.token_mint_a,
-            token_mint_b: pool.token_mint_b,
-            token_owner_account_a: *token_owner_account_a,
-            token_owner_account_b: *token_owner_account_b,
-            token_vault_a: pool.token_vault_a,
-            token_vault_b: pool.token_vault_b,
-            tick_array_lower: lower_tick_array_address,
-            tick_array_upper: upper_tick_array_address,
-        }
-        .instruction(IncreaseLiquidityInstructionArgs {
-            liquidity_amount: quote.liquidity_delta,
-            token_max_a: quote.token_max_a,
-            token_max_b: quote.token_max_b,
-            remaining_accounts_info: None,
-        }),
+        increase_liquidity_instruction(
+            rpc,
+            &position,
+            position_mint_address,
+            position_mint_info,
+            &pool,
+            mint_a_info,
+            mint_b_info,
+            &quote,
+            *token_owner_account_a,
+            *token_owner_account_b,
+            authority,
+        )
+        .await?,
     );
-
     instructions.extend(token_accounts.cleanup_instructions);
 
     Ok(IncreaseLiquidityInstruction {
@@ -285,6 +268,255 @@ pub async fn increase_liquidity_instructions(
     })
 }
 
+/// The size in bytes kept free in each transaction returned by `increase_liquidity_many_instructions`
+/// for the compute budget and Jito tip instructions added when the transaction is sent.
+const SENDER_INSTRUCTIONS_SIZE: usize = 160;
+
+/// Represents the instructions and quotes for increasing liquidity in multiple positions.
+#[derive(Debug)]
+pub struct IncreaseLiquidityManyInstruction {
+    /// The computed quotes for increasing liquidity, in the order of the requested positions.
+    pub quotes: Vec<IncreaseLiquidityQuote>,
+
+    /// The instructions split into transactions, which must be executed in order. The token accounts
+    /// are created in the first transaction and cleaned up in the last one.
+    pub instructions: Vec<Vec<Instruction>>,
+
+    /// A vector of `Keypair` objects representing additional signers required for the instructions.
+    pub additional_signers: Vec<Keypair>,
+}
+
+/// Generates instructions to increase liquidity for multiple existing positions.
+///
+/// The quotes are computed as in `increase_liquidity_instructions`. The token accounts of all the
+/// positions are prepared once, so the accounts of the mints shared by several positions are only
+/// created and funded once. The instructions are packed in order into as few transactions as
+/// possible, each one fitting the transaction size limit with room left for the compute budget and
+/// tip instructions added by the sender.
+///
+/// Each position takes an `IncreaseLiquidityParam` rather than a bare token amount, since an amount
+/// alone doesn't tell which token it is denominated in. Use `IncreaseLiquidityParam::Liquidity`
+/// to increase the positions by a liquidity amount.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to a Solana RPC client for fetching necessary accounts and pool data.
+/// * `requests` - The NFT mint addresses of the positions and the liquidity addition method of each.
+/// * `slippage_tolerance_bps` - An optional slippage tolerance in basis points. Defaults to the global slippage tolerance if not provided.
+/// * `authority` - An optional public key of the account authorizing the liquidity addition. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing `IncreaseLiquidityManyInstruction` on success:
+///
+/// * `quotes` - The computed quotes for increasing liquidity, one per position.
+/// * `instructions` - The instructions required to execute the liquidity additions, grouped by transaction.
+/// * `additional_signers` - A vector of `Keypair` objects representing additional signers required for the instructions.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The `authority` account is invalid or missing.
+/// - The position or token mint accounts are not found or have invalid data.
+/// - The total token amounts overflow.
+/// - Any RPC request to the blockchain fails.
+pub async fn increase_liquidity_many_instructions(
+    rpc: &RpcClient,
+    requests: Vec<(Pubkey, IncreaseLiquidityParam)>,
+    slippage_tolerance_bps: Option<u16>,
+    authority: Option<Pubkey>,
+) -> Result<IncreaseLiquidityManyInstruction, Box<dyn Error>> {
+    let slippage_tolerance_bps = slippage_tolerance_bps.unwrap_or(*SLIPPAGE_TOLERANCE_BPS.try_lock()?);
+    let authority = authority.unwrap_or(*FUNDER.try_lock()?);
+    if authority == Pubkey::default() {
+        return Err("Authority must be provided".into());
+    }
+
+    let current_epoch = rpc.get_epoch_info().await?.epoch;
+    let conservative_transfer_fee = *CONSERVATIVE_TRANSFER_FEE.try_lock()?;
+
+    let mut increases = Vec::new();
+    let mut token_mints: Vec<Pubkey> = Vec::new();
+    let mut token_amounts: HashMap<Pubkey, u64> = HashMap::new();
+
+    for (position_mint_address, param) in requests {
+        let position_address = get_position_address(&position_mint_address)?.0;
+        let position = Position::from_bytes(&fetch_account(rpc, &position_address).await?.data)?;
+        let pool = FusionPool::from_bytes(&fetch_account(rpc, &position.fusion_pool).await?.data)?;
+
+        let mut mint_infos = fetch_multiple_accounts(rpc, &[pool.token_mint_a, pool.token_mint_b, position_mint_address])
+            .await?
+            .into_iter();
+        let mint_a_info = mint_infos.next().flatten().ok_or("Token A mint info not found")?;
+        let mint_b_info = mint_infos.next().flatten().ok_or("Token B mint info not found")?;
+        let position_mint_info = mint_infos.next().flatten().ok_or("Position mint info not found")?;
+
+        let transfer_fee_a = get_current_transfer_fee(Some(&mint_a_info), current_epoch, conservative_transfer_fee);
+        let transfer_fee_b = get_current_transfer_fee(Some(&mint_b_info), current_epoch, conservative_transfer_fee);
+
+        let quote = get_increase_liquidity_quote(
+            param,
+            slippage_tolerance_bps,
+            &pool,
+            position.tick_lower_index,
+            position.tick_upper_index,
+            transfer_fee_a,
+            transfer_fee_b,
+        )?;
+
+        for (mint, amount) in [(pool.token_mint_a, quote.token_max_a), (pool.token_mint_b, quote.token_max_b)] {
+            if !token_amounts.contains_key(&mint) {
+                token_mints.push(mint);
+            }
+            let total = token_amounts.entry(mint).or_insert(0);
+            *total = total.checked_add(amount).ok_or("Token amount overflow")?;
+        }
+
+        increases.push((position_mint_address, position, position_mint_info, pool, mint_a_info, mint_b_info, quote));
+    }
+
+    let token_accounts = prepare_token_accounts_instructions(
+        rpc,
+        authority,
+        token_mints
+            .iter()
+            .map(|mint| TokenAccountStrategy::WithBalance(*mint, token_amounts[mint]))
+            .collect(),
+    )
+    .await?;
+
+    let mut increase_instructions: Vec<Instruction> = Vec::new();
+    let mut quotes: Vec<IncreaseLiquidityQuote> = Vec::new();
+    for (position_mint_address, position, position_mint_info, pool, mint_a_info, mint_b_info, quote) in increases {
+        let token_owner_account_a = token_accounts
+            .token_account_addresses
+            .get(&pool.token_mint_a)
+            .ok_or("Token A owner account not found")?;
+        let token_owner_account_b = token_accounts
+            .token_account_addresses
+            .get(&pool.token_mint_b)
+            .ok_or("Token B owner account not found")?;
+
+        increase_instructions.push(
+            increase_liquidity_instruction(
+                rpc,
+                &position,
+                position_mint_address,
+                &position_mint_info,
+                &pool,
+                &mint_a_info,
+                &mint_b_info,
+                &quote,
+                *token_owner_account_a,
+                *token_owner_account_b,
+                authority,
+            )
+            .await?,
+        );
+        quotes.push(quote);
+    }
+
+    let mut instructions: Vec<Vec<Instruction>> = Vec::new();
+    let mut transaction_instructions = token_accounts.create_instructions;
+    for instruction in increase_instructions.into_iter().chain(token_accounts.cleanup_instructions) {
+        transaction_instructions.push(instruction);
+        if transaction_instructions.len() > 1
+            && estimate_transaction_size(&transaction_instructions, &authority)? + SENDER_INSTRUCTIONS_SIZE > MAX_TRANSACTION_SIZE
+        {
+            let instruction = transaction_instructions.pop().ok_or("Instruction not found")?;
+            instructions.push(std::mem::replace(&mut transaction_instructions, vec![instruction]));
+        }
+    }
+    if !transaction_instructions.is_empty() {
+        instructions.push(transaction_instructions);
+    }
+
+    Ok(IncreaseLiquidityManyInstruction {
+        quotes,
+        instructions,
+        additional_signers: token_accounts.additional_signers,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn increase_liquidity_instruction(
+    rpc: &RpcClient,
+    position: &Position,
+    position_mint_address: Pubkey,
+    position_mint_info: &Account,
+    pool: &FusionPool,
+    mint_a_info: &Account,
+    mint_b_info: &Account,
+    quote: &IncreaseLiquidityQuote,
+    token_owner_account_a: Pubkey,
+    token_owner_account_b: Pubkey,
+    authority: Pubkey,
+) -> Result<Instruction, Box<dyn Error>> {
+    let position_address = get_position_address(&position_mint_address)?.0;
+
+    let lower_tick_array_start_index = get_tick_array_start_tick_index(position.tick_lower_index, pool.tick_spacing);
+    let upper_tick_array_start_index = get_tick_array_start_tick_index(position.tick_upper_index, pool.tick_spacing);
+
+    let position_token_account_address = get_associated_token_address_with_program_id(&authority, &position_mint_address, &position_mint_info.owner);
+    let lower_tick_array_address = get_tick_array_address(&position.fusion_pool, lower_tick_array_start_index)?.0;
+    let upper_tick_array_address = get_tick_array_address(&position.fusion_pool, upper_tick_array_start_index)?.0;
+
+    let mut remaining_accounts = RemainingAccounts::default();
+    remaining_accounts.add(
+        AccountsType::TransferHookA,
+        get_transfer_hook_extra_accounts(
+            rpc,
+            pool.token_mint_a,
+            mint_a_info,
+            token_owner_account_a,
+            pool.token_vault_a,
+            authority,
+            quote.token_max_a,
+        )
+        .await?,
+    );
+    remaining_accounts.add(
+        AccountsType::TransferHookB,
+        get_transfer_hook_extra_accounts(
+            rpc,
+            pool.token_mint_b,
+            mint_b_info,
+            token_owner_account_b,
+            pool.token_vault_b,
+            authority,
+            quote.token_max_b,
+        )
+        .await?,
+    );
+
+    Ok(IncreaseLiquidity {
+        fusion_pool: position.fusion_pool,
+        token_program_a: mint_a_info.owner,
+        token_program_b: mint_b_info.owner,
+        memo_program: spl_memo::ID,
+        position_authority: authority,
+        position: position_address,
+        position_token_account: position_token_account_address,
+        token_mint_a: pool.token_mint_a,
+        token_mint_b: pool.token_mint_b,
+        token_owner_account_a,
+        token_owner_account_b,
+        token_vault_a: pool.token_vault_a,
+        token_vault_b: pool.token_vault_b,
+        tick_array_lower: lower_tick_array_address,
+        tick_array_upper: upper_tick_array_address,
+    }
+    .instruction_with_remaining_accounts(
+        IncreaseLiquidityInstructionArgs {
+            liquidity_amount: quote.liquidity_delta,
+            token_max_a: quote.token_max_a,
+            token_max_b: quote.token_max_b,
+            remaining_accounts_info: remaining_accounts.info(),
+        },
+        &remaining_accounts.accounts,
+    ))
+}
+
 /// Represents the instructions and quote for opening a liquidity position.
 ///
 /// This struct contains the instructions required to open a new position, along with detailed
@@ -324,7 +556,6 @@ async fn internal_open_position(
 ) -> Result<OpenPositionInstruction, Box<dyn Error>> {
     let funder = funder.unwrap_or(*FUNDER.try_lock()?);
     let slippage_tolerance_bps = slippage_tolerance_bps.unwrap_or(*SLIPPAGE_TOLERANCE_BPS.try_lock()?);
-    let rent = get_rent(rpc).await?;
     if funder == Pubkey::default() {
         return Err("Funder must be provided".into());
     }
@@ -335,13 +566,11 @@ async fn internal_open_position(
 
     let upper_initializable_tick_index = get_initializable_tick_index(tick_range.tick_upper_index, fusion_pool.tick_spacing, Some(true));
 
-    let mut instructions: Vec<Instruction> = Vec::new();
-    let mut non_refundable_rent: u64 = 0;
-    let mut additional_signers: Vec<Keypair> = Vec::new();
-
     let epoch = rpc.get_epoch_info().await?.epoch;
-    let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), epoch);
-    let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), epoch);
+
+    let conservative_transfer_fee = *CONSERVATIVE_TRANSFER_FEE.try_lock()?;
+    let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), epoch, conservative_transfer_fee);
+    let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), epoch, conservative_transfer_fee);
 
     let quote = get_increase_liquidity_quote(
         param,
@@ -353,17 +582,6 @@ async fn internal_open_position(
         transfer_fee_b,
     )?;
 
-    additional_signers.push(Keypair::new());
-    let position_mint = additional_signers[0].pubkey();
-
-    let lower_tick_start_index = get_tick_array_start_tick_index(lower_initializable_tick_index, fusion_pool.tick_spacing);
-    let upper_tick_start_index = get_tick_array_start_tick_index(upper_initializable_tick_index, fusion_pool.tick_spacing);
-
-    let position_address = get_position_address(&position_mint)?.0;
-    let position_token_account_address = get_associated_token_address_with_program_id(&funder, &position_mint, &spl_token_2022::ID);
-    let lower_tick_array_address = get_tick_array_address(&pool_address, lower_tick_start_index)?.0;
-    let upper_tick_array_address = get_tick_array_address(&pool_address, upper_tick_start_index)?.0;
-
     let token_accounts = prepare_token_accounts_instructions(
         rpc,
         funder,
@@ -374,10 +592,81 @@ async fn internal_open_position(
     )
     .await?;
 
+    let token_owner_account_a = token_accounts
+        .token_account_addresses
+        .get(&fusion_pool.token_mint_a)
+        .ok_or("Token A owner account not found")?;
+    let token_owner_account_b = token_accounts
+        .token_account_addresses
+        .get(&fusion_pool.token_mint_b)
+        .ok_or("Token B owner account not found")?;
+
+    let (position_mint, open_position_instructions, initialization_cost) = open_position_with_token_accounts_instructions(
+        rpc,
+        pool_address,
+        &fusion_pool,
+        lower_initializable_tick_index,
+        upper_initializable_tick_index,
+        &quote,
+        mint_a_info,
+        mint_b_info,
+        *token_owner_account_a,
+        *token_owner_account_b,
+        funder,
+    )
+    .await?;
+
+    let mut instructions: Vec<Instruction> = Vec::new();
     instructions.extend(token_accounts.create_instructions);
+    instructions.extend(open_position_instructions);
+    instructions.extend(token_accounts.cleanup_instructions);
+
+    let mut additional_signers = vec![position_mint];
     additional_signers.extend(token_accounts.additional_signers);
 
-    let tick_array_infos = rpc.get_multiple_accounts(&[lower_tick_array_address, upper_tick_array_address]).await?;
+    Ok(OpenPositionInstruction {
+        position_mint: additional_signers[0].pubkey(),
+        quote,
+        instructions,
+        additional_signers,
+        initialization_cost,
+    })
+}
+
+/// Builds the instructions to open a position and deposit the quoted liquidity, given the token accounts of the funder.
+///
+/// The tick indexes must be initializable. Returns the keypair of the position mint, the instructions and
+/// the rent of the tick arrays that need to be initialized.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn open_position_with_token_accounts_instructions(
+    rpc: &RpcClient,
+    pool_address: Pubkey,
+    fusion_pool: &FusionPool,
+    lower_initializable_tick_index: i32,
+    upper_initializable_tick_index: i32,
+    quote: &IncreaseLiquidityQuote,
+    mint_a_info: &Account,
+    mint_b_info: &Account,
+    token_owner_account_a: Pubkey,
+    token_owner_account_b: Pubkey,
+    funder: Pubkey,
+) -> Result<(Keypair, Vec<Instruction>, u64), Box<dyn Error>> {
+    let rent = get_rent(rpc).await?;
+
+    let mut instructions: Vec<Instruction> = Vec::new();
+    let mut non_refundable_rent: u64 = 0;
+
+    let position_mint = Keypair::new();
+
+    let lower_tick_start_index = get_tick_array_start_tick_index(lower_initializable_tick_index, fusion_pool.tick_spacing);
+    let upper_tick_start_index = get_tick_array_start_tick_index(upper_initializable_tick_index, fusion_pool.tick_spacing);
+
+    let position_address = get_position_address(&position_mint.pubkey())?.0;
+    let position_token_account_address = get_associated_token_address_with_program_id(&funder, &position_mint.pubkey(), &spl_token_2022::ID);
+    let lower_tick_array_address = get_tick_array_address(&pool_address, lower_tick_start_index)?.0;
+    let upper_tick_array_address = get_tick_array_address(&pool_address, upper_tick_start_index)?.0;
+
+    let tick_array_infos = fetch_multiple_accounts(rpc, &[lower_tick_array_address, upper_tick_array_address]).await?;
 
     if tick_array_infos[0].is_none() {
         instructions.push(
@@ -409,21 +698,12 @@ async fn internal_open_position(
         non_refundable_rent += rent.minimum_balance(TickArray::LEN);
     }
 
-    let token_owner_account_a = token_accounts
-        .token_account_addresses
-        .get(&fusion_pool.token_mint_a)
-        .ok_or("Token A owner account not found")?;
-    let token_owner_account_b = token_accounts
-        .token_account_addresses
-        .get(&fusion_pool.token_mint_b)
-        .ok_or("Token B owner account not found")?;
-
     instructions.push(
         OpenPosition {
             funder,
             owner: funder,
             position: position_address,
-            position_mint,
+            position_mint: position_mint.pubkey(),
             position_token_account: position_token_account_address,
             fusion_pool: pool_address,
             token2022_program: spl_token_2022::ID,
@@ -438,6 +718,34 @@ async fn internal_open_position(
         }),
     );
 
+    let mut remaining_accounts = RemainingAccounts::default();
+    remaining_accounts.add(
+        AccountsType::TransferHookA,
+        get_transfer_hook_extra_accounts(
+            rpc,
+            fusion_pool.token_mint_a,
+            mint_a_info,
+            token_owner_account_a,
+            fusion_pool.token_vault_a,
+            funder,
+            quote.token_max_a,
+        )
+        .await?,
+    );
+    remaining_accounts.add(
+        AccountsType::TransferHookB,
+        get_transfer_hook_extra_accounts(
+            rpc,
+            fusion_pool.token_mint_b,
+            mint_b_info,
+            token_owner_account_b,
+            fusion_pool.token_vault_b,
+            funder,
+            quote.token_max_b,
+        )
+        .await?,
+    );
+
     instructions.push(
         IncreaseLiquidity {
             fusion_pool: pool_address,
@@ -449,30 +757,80 @@ async fn internal_open_position(
             position_token_account: position_token_account_address,
             token_mint_a: fusion_pool.token_mint_a,
             token_mint_b: fusion_pool.token_mint_b,
-            token_owner_account_a: *token_owner_account_a,
-            token_owner_account_b: *token_owner_account_b,
+            token_owner_account_a,
+            token_owner_account_b,
             token_vault_a: fusion_pool.token_vault_a,
             token_vault_b: fusion_pool.token_vault_b,
             tick_array_lower: lower_tick_array_address,
             tick_array_upper: upper_tick_array_address,
         }
-        .instruction(IncreaseLiquidityInstructionArgs {
-            liquidity_amount: quote.liquidity_delta,
-            token_max_a: quote.token_max_a,
-            token_max_b: quote.token_max_b,
-            remaining_accounts_info: None,
-        }),
+        .instruction_with_remaining_accounts(
+            IncreaseLiquidityInstructionArgs {
+                liquidity_amount: quote.liquidity_delta,
+                token_max_a: quote.token_max_a,
+                token_max_b: quote.token_max_b,
+                remaining_accounts_info: remaining_accounts.info(),
+            },
+            &remaining_accounts.accounts,
+        ),
     );
 
-    instructions.extend(token_accounts.cleanup_instructions);
+    Ok((position_mint, instructions, non_refundable_rent))
+}
 
-    Ok(OpenPositionInstruction {
-        position_mint,
-        quote,
-        instructions,
-        additional_signers,
-        initialization_cost: non_refundable_rent,
-    })
+/// Estimates the non-refundable rent needed to open a position in a liquidity pool.
+///
+/// The estimate matches the `initialization_cost` returned by `open_position_instructions`
+/// and only includes the rent of the lower and upper tick arrays which have not been initialized yet.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `pool_address` - The public key of the liquidity pool.
+/// * `lower_tick_index` - The lower tick index of the position.
+/// * `upper_tick_index` - The upper tick index of the position.
+///
+/// # Returns
+///
+/// Returns a `Result` containing the rent in lamports.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The pool account is not found or invalid.
+/// - Any RPC request fails.
+pub async fn estimate_open_position_rent(
+    rpc: &RpcClient,
+    pool_address: Pubkey,
+    lower_tick_index: i32,
+    upper_tick_index: i32,
+) -> Result<u64, Box<dyn Error>> {
+    let rent = get_rent(rpc).await?;
+
+    let fusion_pool_info = fetch_account(rpc, &pool_address).await?;
+    let fusion_pool = FusionPool::from_bytes(&fusion_pool_info.data)?;
+
+    let tick_range = order_tick_indexes(lower_tick_index, upper_tick_index);
+    let lower_initializable_tick_index = get_initializable_tick_index(tick_range.tick_lower_index, fusion_pool.tick_spacing, Some(false));
+    let upper_initializable_tick_index = get_initializable_tick_index(tick_range.tick_upper_index, fusion_pool.tick_spacing, Some(true));
+
+    let lower_tick_start_index = get_tick_array_start_tick_index(lower_initializable_tick_index, fusion_pool.tick_spacing);
+    let upper_tick_start_index = get_tick_array_start_tick_index(upper_initializable_tick_index, fusion_pool.tick_spacing);
+
+    let lower_tick_array_address = get_tick_array_address(&pool_address, lower_tick_start_index)?.0;
+    let upper_tick_array_address = get_tick_array_address(&pool_address, upper_tick_start_index)?.0;
+
+    let tick_array_infos = fetch_multiple_accounts(rpc, &[lower_tick_array_address, upper_tick_array_address]).await?;
+
+    let mut non_refundable_rent: u64 = 0;
+    if tick_array_infos[0].is_none() {
+        non_refundable_rent += rent.minimum_balance(TickArray::LEN);
+    }
+    if tick_array_infos[1].is_none() && lower_tick_start_index != upper_tick_start_index {
+        non_refundable_rent += rent.minimum_balance(TickArray::LEN);
+    }
+
+    Ok(non_refundable_rent)
 }
 
 #[cfg(not(doctest))]
@@ -543,10 +901,10 @@ pub async fn open_full_range_position_instructions(
     slippage_tolerance_bps: Option<u16>,
     funder: Option<Pubkey>,
 ) -> Result<OpenPositionInstruction, Box<dyn Error>> {
-    let fusion_pool_info = rpc.get_account(&pool_address).await?;
+    let fusion_pool_info = fetch_account(rpc, &pool_address).await?;
     let fusion_pool = FusionPool::from_bytes(&fusion_pool_info.data)?;
     let tick_range = get_full_range_tick_indexes(fusion_pool.tick_spacing);
-    let mint_infos = rpc.get_multiple_accounts(&[fusion_pool.token_mint_a, fusion_pool.token_mint_b]).await?;
+    let mint_infos = fetch_multiple_accounts(rpc, &[fusion_pool.token_mint_a, fusion_pool.token_mint_b]).await?;
     let mint_a_info = mint_infos[0].as_ref().ok_or("Token A mint info not found")?;
     let mint_b_info = mint_infos[1].as_ref().ok_or("Token B mint info not found")?;
     internal_open_position(
@@ -640,9 +998,9 @@ pub async fn open_position_instructions(
     slippage_tolerance_bps: Option<u16>,
     funder: Option<Pubkey>,
 ) -> Result<OpenPositionInstruction, Box<dyn Error>> {
-    let fusion_pool_info = rpc.get_account(&pool_address).await?;
+    let fusion_pool_info = fetch_account(rpc, &pool_address).await?;
     let fusion_pool = FusionPool::from_bytes(&fusion_pool_info.data)?;
-    let mint_infos = rpc.get_multiple_accounts(&[fusion_pool.token_mint_a, fusion_pool.token_mint_b]).await?;
+    let mint_infos = fetch_multiple_accounts(rpc, &[fusion_pool.token_mint_a, fusion_pool.token_mint_b]).await?;
     let mint_a_info = mint_infos[0].as_ref().ok_or("Token A mint info not found")?;
     let mint_a = Mint::unpack(&mint_a_info.data)?;
     let mint_b_info = mint_infos[1].as_ref().ok_or("Token B mint info not found")?;
@@ -685,18 +1043,20 @@ mod tests {
     use rstest::rstest;
     use serial_test::serial;
     use solana_program_test::tokio;
-    use spl_token::state::Account as TokenAccount;
+    use spl_token::{native_mint, state::Account as TokenAccount};
     use spl_token_2022::{extension::StateWithExtensionsOwned, state::Account as TokenAccount2022, ID as TOKEN_2022_PROGRAM_ID};
 
     use crate::{
-        increase_liquidity_instructions,
+        estimate_open_position_rent, increase_liquidity_instructions, increase_liquidity_many_instructions, open_position_instructions,
         tests::{
             setup_ata_te, setup_ata_with_amount, setup_fusion_pool, setup_mint_te, setup_mint_te_fee, setup_mint_with_decimals, RpcContext,
             SetupAtaConfig,
         },
-        IncreaseLiquidityParam,
+        IncreaseLiquidityParam, PriceOrTickIndex,
     };
 
+    use super::SENDER_INSTRUCTIONS_SIZE;
+    use crate::message::{estimate_transaction_size, MAX_TRANSACTION_SIZE};
     use crate::tests::setup_position;
     use solana_client::nonblocking::rpc_client::RpcClient;
     use solana_keypair::Keypair;
@@ -948,4 +1308,92 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_increase_liquidity_many_shares_token_accounts() -> Result<(), Box<dyn Error>> {
+        let ctx = RpcContext::new().await;
+
+        let mint = setup_mint_with_decimals(&ctx, 9).await?;
+        setup_ata_with_amount(&ctx, mint, 1_000_000_000).await?;
+        let (mint_a, mint_b) = if native_mint::ID < mint {
+            (native_mint::ID, mint)
+        } else {
+            (mint, native_mint::ID)
+        };
+        let pool_pubkey = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 300).await?;
+
+        let position_mints = [
+            setup_position(&ctx, pool_pubkey, Some((-128, 128)), None).await?,
+            setup_position(&ctx, pool_pubkey, Some((-640, 640)), None).await?,
+        ];
+        let requests = position_mints
+            .iter()
+            .map(|x| (*x, IncreaseLiquidityParam::Liquidity(10_000_000)))
+            .collect();
+
+        let result = increase_liquidity_many_instructions(&ctx.rpc, requests, Some(100), Some(ctx.signer.pubkey())).await?;
+        assert_eq!(result.quotes.len(), 2);
+        assert_eq!(result.instructions.len(), 1);
+
+        // The wrapped SOL account is created once for both positions.
+        assert_eq!(result.additional_signers.len(), 1);
+        let create_account_count = result.instructions[0]
+            .iter()
+            .filter(|ix| ix.program_id == solana_program::system_program::ID)
+            .count();
+        assert_eq!(create_account_count, 1);
+        for instructions in &result.instructions {
+            assert!(estimate_transaction_size(instructions, &ctx.signer.pubkey())? + SENDER_INSTRUCTIONS_SIZE <= MAX_TRANSACTION_SIZE);
+        }
+
+        for instructions in result.instructions {
+            ctx.send_transaction_with_signers(instructions, result.additional_signers.iter().collect())
+                .await?;
+        }
+
+        for (position_mint, quote) in position_mints.iter().zip(result.quotes.iter()) {
+            let position_address = get_position_address(position_mint)?.0;
+            let position = Position::from_bytes(&ctx.rpc.get_account(&position_address).await?.data)?;
+            assert_eq!(position.liquidity, quote.liquidity_delta);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_estimate_open_position_rent() -> Result<(), Box<dyn Error>> {
+        let ctx = RpcContext::new().await;
+
+        let minted = setup_all_mints(&ctx).await?;
+        let _user_atas = setup_all_atas(&ctx, &minted).await?;
+
+        let mint_a_key = minted.get("A").unwrap();
+        let mint_b_key = minted.get("B").unwrap();
+        let pool_pubkey = setup_fusion_pool(&ctx, *mint_a_key, *mint_b_key, 64, 300).await?;
+
+        // The position spans two uninitialized tick arrays.
+        let estimated_rent = estimate_open_position_rent(&ctx.rpc, pool_pubkey, -100, 100).await?;
+        let open_ix = open_position_instructions(
+            &ctx.rpc,
+            pool_pubkey,
+            PriceOrTickIndex::Tick(-100),
+            PriceOrTickIndex::Tick(100),
+            IncreaseLiquidityParam::Liquidity(100_000),
+            Some(100),
+            Some(ctx.signer.pubkey()),
+        )
+        .await?;
+        assert!(estimated_rent > 0);
+        assert_eq!(estimated_rent, open_ix.initialization_cost);
+
+        let signers: Vec<&Keypair> = open_ix.additional_signers.iter().collect();
+        ctx.send_transaction_with_signers(open_ix.instructions, signers).await?;
+
+        let estimated_rent = estimate_open_position_rent(&ctx.rpc, pool_pubkey, -100, 100).await?;
+        assert_eq!(estimated_rent, 0);
+
+        Ok(())
+    }
 }
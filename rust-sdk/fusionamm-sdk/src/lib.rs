@@ -15,9 +15,13 @@ mod decrease_liquidity;
 mod harvest;
 mod increase_liquidity;
 mod limit_order;
+mod message;
 mod pool;
 mod position;
+mod position_bundle;
+mod protocol;
 mod swap;
+mod tick_array;
 mod token;
 
 #[cfg(test)]
@@ -33,7 +37,11 @@ pub use decrease_liquidity::*;
 pub use harvest::*;
 pub use increase_liquidity::*;
 pub use limit_order::*;
+pub use message::*;
 pub use pool::*;
 pub use position::*;
+pub use position_bundle::*;
+pub use protocol::*;
 pub use swap::*;
+pub use tick_array::*;
 pub use token::*;
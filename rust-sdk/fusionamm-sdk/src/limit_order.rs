@@ -1,16 +1,21 @@
-use crate::account::get_rent;
-use crate::token::{get_current_transfer_fee, prepare_token_accounts_instructions, TokenAccountStrategy};
-use crate::{PriceOrTickIndex, FUNDER};
+use crate::account::{fetch_account, fetch_multiple_accounts, get_rent, get_token_accounts_for_owner};
+use crate::token::{
+    get_current_transfer_fee, get_transfer_hook_extra_accounts, prepare_token_accounts_instructions, RemainingAccounts, TokenAccountStrategy,
+};
+#[cfg(not(doctest))]
+use crate::{fetch_fusion_pool_tick_arrays, fetch_fusion_pools_by_token_pair};
+use crate::{order_mints, PriceOrTickIndex, CONSERVATIVE_TRANSFER_FEE, FUNDER};
 use fusionamm_client::{
-    get_limit_order_address, get_tick_array_address, CloseLimitOrder, DecreaseLimitOrder, DecreaseLimitOrderInstructionArgs, FusionPool,
-    IncreaseLimitOrder, IncreaseLimitOrderInstructionArgs, InitializeTickArray, InitializeTickArrayInstructionArgs, LimitOrder, OpenLimitOrder,
-    OpenLimitOrderInstructionArgs, TickArray, FP_NFT_UPDATE_AUTH,
+    get_limit_order_address, get_tick_array_address, AccountsType, CloseLimitOrder, DecodedAccount, DecreaseLimitOrder,
+    DecreaseLimitOrderInstructionArgs, FusionPool, IncreaseLimitOrder, IncreaseLimitOrderInstructionArgs, InitializeTickArray,
+    InitializeTickArrayInstructionArgs, LimitOrder, OpenLimitOrder, OpenLimitOrderInstructionArgs, TickArray, FP_NFT_UPDATE_AUTH,
 };
 use fusionamm_core::{
-    decrease_limit_order_quote, get_initializable_tick_index, get_tick_array_start_tick_index, price_to_tick_index, try_reverse_apply_transfer_fee,
-    LimitOrderDecreaseQuote,
+    decrease_limit_order_quote, get_initializable_tick_index, get_tick_array_start_tick_index, get_tick_index_in_array, limit_order_remaining_input,
+    price_to_tick_index, tick_index_to_price, try_reverse_apply_transfer_fee, LimitOrderDecreaseQuote,
 };
-use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_account::Account;
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_request::TokenAccountsFilter};
 use solana_keypair::Keypair;
 use solana_program::instruction::Instruction;
 use solana_program::program_pack::Pack;
@@ -18,6 +23,7 @@ use solana_program::pubkey::Pubkey;
 use solana_signer::Signer;
 use spl_associated_token_account::get_associated_token_address_with_program_id;
 use spl_token_2022::state::Mint;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 
 #[derive(Debug)]
@@ -145,10 +151,10 @@ pub async fn open_limit_order_instructions(
         return Err("Funder must be provided".into());
     }
 
-    let fusion_pool_info = rpc.get_account(&pool_address).await?;
+    let fusion_pool_info = fetch_account(rpc, &pool_address).await?;
     let fusion_pool = FusionPool::from_bytes(&fusion_pool_info.data)?;
 
-    let mint_infos = rpc.get_multiple_accounts(&[fusion_pool.token_mint_a, fusion_pool.token_mint_b]).await?;
+    let mint_infos = fetch_multiple_accounts(rpc, &[fusion_pool.token_mint_a, fusion_pool.token_mint_b]).await?;
 
     // Use 'unpack_from_slice' instead of 'unpack' because the account length might be larger than Mint::LEN due to extensions.
     let mint_a_info = mint_infos[0].as_ref().ok_or("Token A mint info not found")?;
@@ -180,7 +186,9 @@ pub async fn open_limit_order_instructions(
     let initializable_tick_index = get_initializable_tick_index(tick_index, fusion_pool.tick_spacing, Some(false));
 
     let epoch = rpc.get_epoch_info().await?.epoch;
-    let transfer_fee = get_current_transfer_fee(Some(mint_info), epoch);
+
+    let conservative_transfer_fee = *CONSERVATIVE_TRANSFER_FEE.try_lock()?;
+    let transfer_fee = get_current_transfer_fee(Some(mint_info), epoch, conservative_transfer_fee);
     let amount_with_fee = if transfer_fee.is_some() {
         try_reverse_apply_transfer_fee(amount, transfer_fee.unwrap_or_default())?
     } else {
@@ -202,9 +210,9 @@ pub async fn open_limit_order_instructions(
     instructions.extend(token_accounts.create_instructions);
     additional_signers.extend(token_accounts.additional_signers);
 
-    let tick_array_info = rpc.get_account(&tick_array_address).await;
+    let tick_array_infos = fetch_multiple_accounts(rpc, &[tick_array_address]).await?;
 
-    if tick_array_info.is_err() {
+    if tick_array_infos[0].is_none() {
         instructions.push(
             InitializeTickArray {
                 fusion_pool: pool_address,
@@ -224,6 +232,9 @@ pub async fn open_limit_order_instructions(
         .get(&mint_address)
         .ok_or("Token owner account not found")?;
 
+    let remaining_accounts =
+        increase_limit_order_remaining_accounts(rpc, &fusion_pool, a_to_b, mint_info, *token_owner_account, funder, amount_with_fee).await?;
+
     instructions.push(
         OpenLimitOrder {
             funder,
@@ -257,10 +268,13 @@ pub async fn open_limit_order_instructions(
             token_program: mint_info.owner,
             memo_program: spl_memo::ID,
         }
-        .instruction(IncreaseLimitOrderInstructionArgs {
-            amount,
-            remaining_accounts_info: None,
-        }),
+        .instruction_with_remaining_accounts(
+            IncreaseLimitOrderInstructionArgs {
+                amount,
+                remaining_accounts_info: remaining_accounts.info(),
+            },
+            &remaining_accounts.accounts,
+        ),
     );
 
     instructions.extend(token_accounts.cleanup_instructions);
@@ -275,6 +289,224 @@ pub async fn open_limit_order_instructions(
     })
 }
 
+/// Estimates the non-refundable rent needed to open a limit order at a specific tick index.
+///
+/// The estimate matches the `initialization_cost` returned by `open_limit_order_instructions`
+/// and only includes the rent of the tick array if it has not been initialized yet.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `pool_address` - The public key of the liquidity pool.
+/// * `tick_index` - The tick index of the limit order.
+///
+/// # Returns
+///
+/// Returns a `Result` containing the rent in lamports.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The pool account is not found or invalid.
+/// - Any RPC request fails.
+pub async fn estimate_open_limit_order_rent(rpc: &RpcClient, pool_address: Pubkey, tick_index: i32) -> Result<u64, Box<dyn Error>> {
+    let rent = get_rent(rpc).await?;
+
+    let fusion_pool_info = fetch_account(rpc, &pool_address).await?;
+    let fusion_pool = FusionPool::from_bytes(&fusion_pool_info.data)?;
+
+    let initializable_tick_index = get_initializable_tick_index(tick_index, fusion_pool.tick_spacing, Some(false));
+    let tick_array_start_index = get_tick_array_start_tick_index(initializable_tick_index, fusion_pool.tick_spacing);
+    let tick_array_address = get_tick_array_address(&pool_address, tick_array_start_index)?.0;
+
+    let tick_array_infos = fetch_multiple_accounts(rpc, &[tick_array_address]).await?;
+
+    if tick_array_infos[0].is_none() {
+        Ok(rent.minimum_balance(TickArray::LEN))
+    } else {
+        Ok(0)
+    }
+}
+
+#[cfg(not(doctest))]
+/// Finds the pool of a token pair whose tick spacing allows placing a limit order closest to the desired price.
+///
+/// For each pool of the pair, the desired price is rounded to an initializable tick index of the pool.
+/// The tick index is rounded in favour of the order: up for `a_to_b` orders, which sell token A,
+/// and down for `b_to_a` orders. If several pools reach the same price, the pool with the finest tick spacing is chosen.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `token_1` - The public key of the first token mint in the pool.
+/// * `token_2` - The public key of the second token mint in the pool.
+/// * `desired_price` - The desired price of token A in token B, where the mints are ordered as in the pool.
+/// * `a_to_b` - The direction of the limit order.
+///
+/// # Returns
+///
+/// Returns a `Result` containing the pool address and the initializable tick index of the limit order in this pool.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Both token mints are the same or a mint cannot be fetched.
+/// - There are no pools for the token pair.
+/// - Any RPC request fails.
+pub async fn best_limit_order_pool(
+    rpc: &RpcClient,
+    token_1: Pubkey,
+    token_2: Pubkey,
+    desired_price: f64,
+    a_to_b: bool,
+) -> Result<(Pubkey, i32), Box<dyn Error>> {
+    let [token_a, token_b] = order_mints(token_1, token_2)?;
+    let mut fusion_pools = fetch_fusion_pools_by_token_pair(rpc, token_a, token_b).await?;
+    fusion_pools.sort_by_key(|pool| pool.data.tick_spacing);
+
+    let mint_infos = fetch_multiple_accounts(rpc, &[token_a, token_b]).await?;
+    let mint_a_info = mint_infos[0].as_ref().ok_or("Token A mint info not found")?;
+    let mint_b_info = mint_infos[1].as_ref().ok_or("Token B mint info not found")?;
+    let decimals_a = Mint::unpack_from_slice(&mint_a_info.data)?.decimals;
+    let decimals_b = Mint::unpack_from_slice(&mint_b_info.data)?.decimals;
+
+    let tick_index = price_to_tick_index(desired_price, decimals_a, decimals_b);
+
+    let mut best_pool: Option<(Pubkey, i32, f64)> = None;
+    for pool in fusion_pools {
+        let initializable_tick_index = get_initializable_tick_index(tick_index, pool.data.tick_spacing, Some(a_to_b));
+        let price_difference = (tick_index_to_price(initializable_tick_index, decimals_a, decimals_b) - desired_price).abs();
+        if best_pool.is_none_or(|(_, _, best_price_difference)| price_difference < best_price_difference) {
+            best_pool = Some((pool.address, initializable_tick_index, price_difference));
+        }
+    }
+
+    let (pool_address, tick_index, _) = best_pool.ok_or(format!("No fusion pools found for the token pair {} and {}", token_a, token_b))?;
+    Ok((pool_address, tick_index))
+}
+
+/// Computes the total resting exposure of all limit orders owned by a wallet.
+///
+/// The limit orders are found through the limit order NFTs held by the wallet in SPL Token and
+/// Token 2022 accounts. The input amounts which are not filled yet are read from the order ticks
+/// and summed up by direction: `a_to_b` orders hold token A and `b_to_a` orders hold token B.
+/// Orders of all pools are included, so the totals are only meaningful per token if the wallet
+/// trades a single pool.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `owner` - The public key of the wallet holding the limit order NFTs.
+///
+/// # Returns
+///
+/// Returns a `Result` containing the total unfilled input amounts of the `a_to_b` and `b_to_a` orders.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The token accounts of the owner cannot be fetched.
+/// - The fusion pool or the tick array of an order is not found.
+/// - An order and its tick are out of sync.
+/// - The total amount overflows.
+/// - Any RPC request fails.
+pub async fn aggregate_limit_order_exposure(rpc: &RpcClient, owner: Pubkey) -> Result<(u64, u64), Box<dyn Error>> {
+    let token_accounts = get_token_accounts_for_owner(rpc, owner, TokenAccountsFilter::ProgramId(spl_token::ID)).await?;
+    let token_extension_accounts = get_token_accounts_for_owner(rpc, owner, TokenAccountsFilter::ProgramId(spl_token_2022::ID)).await?;
+
+    let limit_order_addresses: Vec<Pubkey> = [token_accounts, token_extension_accounts]
+        .into_iter()
+        .flatten()
+        .filter(|x| x.amount == 1)
+        .map(|x| get_limit_order_address(&x.mint).map(|x| x.0))
+        .collect::<Result<Vec<Pubkey>, _>>()?;
+
+    let limit_orders: Vec<LimitOrder> = fetch_multiple_accounts(rpc, &limit_order_addresses)
+        .await?
+        .iter()
+        .flatten()
+        .filter_map(|limit_order_info| LimitOrder::from_bytes(&limit_order_info.data).ok())
+        .collect();
+
+    let pool_addresses: Vec<Pubkey> = limit_orders
+        .iter()
+        .map(|x| x.fusion_pool)
+        .collect::<HashSet<Pubkey>>()
+        .into_iter()
+        .collect();
+    let mut tick_spacings: HashMap<Pubkey, u16> = HashMap::new();
+    for (pool_address, pool_info) in pool_addresses.iter().zip(fetch_multiple_accounts(rpc, &pool_addresses).await?) {
+        let pool_info = pool_info.ok_or(format!("Fusion pool {} not found", pool_address))?;
+        tick_spacings.insert(*pool_address, FusionPool::from_bytes(&pool_info.data)?.tick_spacing);
+    }
+
+    let tick_array_start_indexes: Vec<i32> = limit_orders
+        .iter()
+        .map(|x| get_tick_array_start_tick_index(x.tick_index, tick_spacings[&x.fusion_pool]))
+        .collect();
+    let tick_array_addresses: Vec<Pubkey> = limit_orders
+        .iter()
+        .zip(&tick_array_start_indexes)
+        .map(|(x, start_index)| get_tick_array_address(&x.fusion_pool, *start_index).map(|x| x.0))
+        .collect::<Result<Vec<Pubkey>, _>>()?;
+    let tick_array_infos = fetch_multiple_accounts(rpc, &tick_array_addresses).await?;
+
+    let mut exposure_a: u64 = 0;
+    let mut exposure_b: u64 = 0;
+    for ((limit_order, start_index), tick_array_info) in limit_orders.into_iter().zip(tick_array_start_indexes).zip(tick_array_infos) {
+        let tick_array_info = tick_array_info.ok_or(format!("Tick array of the limit order at tick {} not found", limit_order.tick_index))?;
+        let tick_array = TickArray::from_bytes(&tick_array_info.data)?;
+        let tick_spacing = tick_spacings[&limit_order.fusion_pool];
+        let tick = &tick_array.ticks[get_tick_index_in_array(limit_order.tick_index, start_index, tick_spacing)? as usize];
+
+        let remaining_input = limit_order_remaining_input(limit_order.clone().into(), tick.clone().into())?;
+        let exposure = if limit_order.a_to_b { &mut exposure_a } else { &mut exposure_b };
+        *exposure = exposure.checked_add(remaining_input).ok_or("Limit order exposure overflow")?;
+    }
+
+    Ok((exposure_a, exposure_b))
+}
+
+#[cfg(not(doctest))]
+/// Fetches the tick arrays around the current price of a fusion pool which hold resting limit orders.
+///
+/// The tick arrays are fetched with `fetch_fusion_pool_tick_arrays`, and only those with at least one tick
+/// with unfilled limit order input are returned. Order book builders can use it to skip empty tick arrays.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `pool_address` - The public key of the fusion pool.
+/// * `radius` - The number of tick arrays to fetch on each side of the current tick array.
+///
+/// # Returns
+///
+/// A `Result` containing the tick arrays with limit orders, ordered by their start tick index.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The fusion pool account cannot be fetched or decoded.
+/// - Any of the tick array accounts cannot be decoded.
+pub async fn fetch_tick_arrays_with_orders(
+    rpc: &RpcClient,
+    pool_address: Pubkey,
+    radius: u16,
+) -> Result<Vec<DecodedAccount<TickArray>>, Box<dyn Error>> {
+    let tick_arrays = fetch_fusion_pool_tick_arrays(rpc, pool_address, radius).await?;
+
+    Ok(tick_arrays
+        .into_iter()
+        .filter(|tick_array| {
+            tick_array
+                .data
+                .ticks
+                .iter()
+                .any(|tick| tick.open_orders_input + tick.part_filled_orders_remaining_input > 0)
+        })
+        .collect())
+}
+
 /// Increases a limit order.
 /// The limit order can't be increased if it's partially filled.
 ///
@@ -311,13 +543,13 @@ pub async fn increase_limit_order_instructions(
     let mut instructions: Vec<Instruction> = Vec::new();
 
     let limit_order_address = get_limit_order_address(&limit_order_mint)?.0;
-    let limit_order_info = rpc.get_account(&limit_order_address).await?;
+    let limit_order_info = fetch_account(rpc, &limit_order_address).await?;
     let limit_order = LimitOrder::from_bytes(&limit_order_info.data)?;
 
-    let fusion_pool_info = rpc.get_account(&limit_order.fusion_pool).await?;
+    let fusion_pool_info = fetch_account(rpc, &limit_order.fusion_pool).await?;
     let fusion_pool = FusionPool::from_bytes(&fusion_pool_info.data)?;
 
-    let mint_infos = rpc.get_multiple_accounts(&[fusion_pool.token_mint_a, fusion_pool.token_mint_b]).await?;
+    let mint_infos = fetch_multiple_accounts(rpc, &[fusion_pool.token_mint_a, fusion_pool.token_mint_b]).await?;
     let mint_a_info = mint_infos[0].as_ref().ok_or("Token A mint info not found")?;
     let mint_b_info = mint_infos[1].as_ref().ok_or("Token B mint info not found")?;
 
@@ -333,7 +565,9 @@ pub async fn increase_limit_order_instructions(
     let tick_array_address = get_tick_array_address(&limit_order.fusion_pool, tick_array_start_index)?.0;
 
     let epoch = rpc.get_epoch_info().await?.epoch;
-    let transfer_fee = get_current_transfer_fee(Some(mint_info), epoch);
+
+    let conservative_transfer_fee = *CONSERVATIVE_TRANSFER_FEE.try_lock()?;
+    let transfer_fee = get_current_transfer_fee(Some(mint_info), epoch, conservative_transfer_fee);
     let amount_with_fee = if transfer_fee.is_some() {
         try_reverse_apply_transfer_fee(amount, transfer_fee.unwrap_or_default())?
     } else {
@@ -350,6 +584,10 @@ pub async fn increase_limit_order_instructions(
         .get(&mint_address)
         .ok_or("Token owner account not found")?;
 
+    let remaining_accounts =
+        increase_limit_order_remaining_accounts(rpc, &fusion_pool, limit_order.a_to_b, mint_info, *token_owner_account, funder, amount_with_fee)
+            .await?;
+
     instructions.push(
         IncreaseLimitOrder {
             limit_order_authority: funder,
@@ -367,10 +605,13 @@ pub async fn increase_limit_order_instructions(
             token_program: mint_info.owner,
             memo_program: spl_memo::ID,
         }
-        .instruction(IncreaseLimitOrderInstructionArgs {
-            amount,
-            remaining_accounts_info: None,
-        }),
+        .instruction_with_remaining_accounts(
+            IncreaseLimitOrderInstructionArgs {
+                amount,
+                remaining_accounts_info: remaining_accounts.info(),
+            },
+            &remaining_accounts.accounts,
+        ),
     );
 
     instructions.extend(token_accounts.cleanup_instructions);
@@ -383,6 +624,30 @@ pub async fn increase_limit_order_instructions(
     })
 }
 
+/// Resolves the transfer hook accounts of the token transferred from the owner to the pool vault when a limit order is increased.
+async fn increase_limit_order_remaining_accounts(
+    rpc: &RpcClient,
+    fusion_pool: &FusionPool,
+    a_to_b: bool,
+    mint_info: &Account,
+    token_owner_account: Pubkey,
+    authority: Pubkey,
+    amount: u64,
+) -> Result<RemainingAccounts, Box<dyn Error>> {
+    let (accounts_type, mint_address, token_vault) = if a_to_b {
+        (AccountsType::TransferHookA, fusion_pool.token_mint_a, fusion_pool.token_vault_a)
+    } else {
+        (AccountsType::TransferHookB, fusion_pool.token_mint_b, fusion_pool.token_vault_b)
+    };
+
+    let mut remaining_accounts = RemainingAccounts::default();
+    remaining_accounts.add(
+        accounts_type,
+        get_transfer_hook_extra_accounts(rpc, mint_address, mint_info, token_owner_account, token_vault, authority, amount).await?,
+    );
+    Ok(remaining_accounts)
+}
+
 #[cfg(not(doctest))]
 /// Generates instructions to close a limit order.
 ///
@@ -524,13 +789,13 @@ async fn internal_decrease_and_close_limit_order_instructions(
     let mut instructions: Vec<Instruction> = Vec::new();
 
     let limit_order_address = get_limit_order_address(&limit_order_mint)?.0;
-    let limit_order_info = rpc.get_account(&limit_order_address).await?;
+    let limit_order_info = fetch_account(rpc, &limit_order_address).await?;
     let limit_order = LimitOrder::from_bytes(&limit_order_info.data)?;
 
-    let fusion_pool_info = rpc.get_account(&limit_order.fusion_pool).await?;
+    let fusion_pool_info = fetch_account(rpc, &limit_order.fusion_pool).await?;
     let fusion_pool = FusionPool::from_bytes(&fusion_pool_info.data)?;
 
-    let mint_infos = rpc.get_multiple_accounts(&[fusion_pool.token_mint_a, fusion_pool.token_mint_b]).await?;
+    let mint_infos = fetch_multiple_accounts(rpc, &[fusion_pool.token_mint_a, fusion_pool.token_mint_b]).await?;
     let mint_a_info = mint_infos[0].as_ref().ok_or("Token A mint info not found")?;
     let mint_b_info = mint_infos[1].as_ref().ok_or("Token B mint info not found")?;
 
@@ -539,7 +804,7 @@ async fn internal_decrease_and_close_limit_order_instructions(
     let limit_order_token_account_address = get_associated_token_address_with_program_id(&funder, &limit_order_mint, &spl_token_2022::ID);
     let tick_array_address = get_tick_array_address(&limit_order.fusion_pool, tick_array_start_index)?.0;
 
-    let tick_array_info = rpc.get_account(&tick_array_address).await?;
+    let tick_array_info = fetch_account(rpc, &tick_array_address).await?;
     let tick_array = TickArray::from_bytes(&tick_array_info.data)?;
     let tick = &tick_array.ticks[((limit_order.tick_index - tick_array_start_index) / fusion_pool.tick_spacing as i32) as usize];
 
@@ -549,8 +814,10 @@ async fn internal_decrease_and_close_limit_order_instructions(
     };
 
     let current_epoch = rpc.get_epoch_info().await?.epoch;
-    let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), current_epoch);
-    let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), current_epoch);
+
+    let conservative_transfer_fee = *CONSERVATIVE_TRANSFER_FEE.try_lock()?;
+    let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), current_epoch, conservative_transfer_fee);
+    let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), current_epoch, conservative_transfer_fee);
 
     let quote = decrease_limit_order_quote(
         fusion_pool.clone().into(),
@@ -573,6 +840,37 @@ async fn internal_decrease_and_close_limit_order_instructions(
 
     instructions.extend(token_accounts.create_instructions);
 
+    let token_owner_account_a = *token_accounts.token_account_addresses.get(&fusion_pool.token_mint_a).unwrap();
+    let token_owner_account_b = *token_accounts.token_account_addresses.get(&fusion_pool.token_mint_b).unwrap();
+
+    let mut remaining_accounts = RemainingAccounts::default();
+    remaining_accounts.add(
+        AccountsType::TransferHookA,
+        get_transfer_hook_extra_accounts(
+            rpc,
+            fusion_pool.token_mint_a,
+            mint_a_info,
+            fusion_pool.token_vault_a,
+            token_owner_account_a,
+            limit_order.fusion_pool,
+            quote.amount_out_a,
+        )
+        .await?,
+    );
+    remaining_accounts.add(
+        AccountsType::TransferHookB,
+        get_transfer_hook_extra_accounts(
+            rpc,
+            fusion_pool.token_mint_b,
+            mint_b_info,
+            fusion_pool.token_vault_b,
+            token_owner_account_b,
+            limit_order.fusion_pool,
+            quote.amount_out_b,
+        )
+        .await?,
+    );
+
     instructions.push(
         DecreaseLimitOrder {
             limit_order_authority: funder,
@@ -581,8 +879,8 @@ async fn internal_decrease_and_close_limit_order_instructions(
             limit_order_token_account: limit_order_token_account_address,
             token_mint_a: fusion_pool.token_mint_a,
             token_mint_b: fusion_pool.token_mint_b,
-            token_owner_account_a: *token_accounts.token_account_addresses.get(&fusion_pool.token_mint_a).unwrap(),
-            token_owner_account_b: *token_accounts.token_account_addresses.get(&fusion_pool.token_mint_b).unwrap(),
+            token_owner_account_a,
+            token_owner_account_b,
             token_vault_a: fusion_pool.token_vault_a,
             token_vault_b: fusion_pool.token_vault_b,
             tick_array: tick_array_address,
@@ -590,10 +888,13 @@ async fn internal_decrease_and_close_limit_order_instructions(
             token_program_b: mint_b_info.owner,
             memo_program: spl_memo::ID,
         }
-        .instruction(DecreaseLimitOrderInstructionArgs {
-            amount: decrease_amount,
-            remaining_accounts_info: None,
-        }),
+        .instruction_with_remaining_accounts(
+            DecreaseLimitOrderInstructionArgs {
+                amount: decrease_amount,
+                remaining_accounts_info: remaining_accounts.info(),
+            },
+            &remaining_accounts.accounts,
+        ),
     );
 
     if amount.is_none() {
@@ -622,24 +923,37 @@ async fn internal_decrease_and_close_limit_order_instructions(
 #[cfg(test)]
 mod tests {
     use crate::{
-        close_limit_order_instructions, decrease_limit_order_instructions, increase_limit_order_instructions, open_limit_order_instructions,
+        aggregate_limit_order_exposure, best_limit_order_pool, close_limit_order_instructions, decrease_limit_order_instructions,
+        estimate_open_limit_order_rent, fetch_fusion_pool_tick_arrays, fetch_tick_arrays_with_orders, increase_limit_order_instructions,
+        open_limit_order_instructions, order_mints, swap_instructions,
         tests::{
-            setup_ata_te, setup_ata_with_amount, setup_fusion_pool, setup_mint_te, setup_mint_te_fee, setup_mint_with_decimals, RpcContext,
-            SetupAtaConfig,
+            setup_ata_te, setup_ata_with_amount, setup_fusion_pool, setup_mint_te, setup_mint_te_fee, setup_mint_with_decimals, setup_position,
+            setup_transfer_hook_validation_account, RpcContext, SetupAtaConfig, TEST_TRANSFER_HOOK_PROGRAM_ID,
         },
-        DecreaseLimitOrderInstruction, IncreaseLimitOrderInstruction, OpenLimitOrderInstruction, PriceOrTickIndex,
+        DecreaseLimitOrderInstruction, IncreaseLimitOrderInstruction, OpenLimitOrderInstruction, PriceOrTickIndex, SwapType,
+    };
+    use fusionamm_client::{
+        decode_instruction, get_fusion_pools_config_address, get_limit_order_address, get_token_badge_address, AccountsType,
+        DecodedFusionInstruction, InitializeTokenBadge, LimitOrder, RemainingAccountsInfo, RemainingAccountsSlice,
     };
-    use fusionamm_client::{get_limit_order_address, LimitOrder};
+    use fusionamm_core::price_to_tick_index;
     use rstest::rstest;
     use serial_test::serial;
     use solana_client::nonblocking::rpc_client::RpcClient;
+    use solana_instruction::AccountMeta;
     use solana_keypair::Keypair;
     use solana_program::program_pack::Pack;
     use solana_program_test::tokio;
     use solana_pubkey::Pubkey;
     use solana_signer::Signer;
+    use spl_associated_token_account::get_associated_token_address_with_program_id;
     use spl_token::state::Account as TokenAccount;
-    use spl_token_2022::{extension::StateWithExtensionsOwned, state::Account as TokenAccount2022, ID as TOKEN_2022_PROGRAM_ID};
+    use spl_token_2022::{
+        extension::{ExtensionType, StateWithExtensionsOwned},
+        state::Account as TokenAccount2022,
+        ID as TOKEN_2022_PROGRAM_ID,
+    };
+    use spl_transfer_hook_interface::get_extra_account_metas_address;
     use std::collections::HashMap;
     use std::error::Error;
 
@@ -933,4 +1247,227 @@ mod tests {
                 .unwrap();
         });
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_aggregate_limit_order_exposure() -> Result<(), Box<dyn Error>> {
+        let ctx = RpcContext::new().await;
+
+        let minted = setup_all_mints(&ctx).await?;
+        let _user_atas = setup_all_atas(&ctx, &minted).await?;
+
+        let mint_a_key = minted.get("A").unwrap();
+        let mint_b_key = minted.get("B").unwrap();
+        let (mint_a, mint_b) = if mint_a_key < mint_b_key {
+            (*mint_a_key, *mint_b_key)
+        } else {
+            (*mint_b_key, *mint_a_key)
+        };
+        let pool_pubkey = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 300).await?;
+
+        assert_eq!(aggregate_limit_order_exposure(&ctx.rpc, ctx.signer.pubkey()).await?, (0, 0));
+
+        for (amount, tick_index, a_to_b) in [(1_000_000, 128, true), (2_000_000, 256, true), (500_000, -128, false)] {
+            let open_ix =
+                open_limit_order_instructions(&ctx.rpc, pool_pubkey, amount, PriceOrTickIndex::Tick(tick_index), a_to_b, Some(ctx.signer.pubkey()))
+                    .await?;
+            let signers: Vec<&Keypair> = open_ix.additional_signers.iter().collect();
+            ctx.send_transaction_with_signers(open_ix.instructions, signers).await?;
+        }
+
+        assert_eq!(aggregate_limit_order_exposure(&ctx.rpc, ctx.signer.pubkey()).await?, (3_000_000, 500_000));
+        assert_eq!(aggregate_limit_order_exposure(&ctx.rpc, Keypair::new().pubkey()).await?, (0, 0));
+
+        // The swap partially fills the order at tick 128, only its unfilled input is counted.
+        let swap_ix = swap_instructions(&ctx.rpc, pool_pubkey, 400_000, mint_a, SwapType::ExactOut, Some(100), Some(ctx.signer.pubkey())).await?;
+        let signers: Vec<&Keypair> = swap_ix.additional_signers.iter().collect();
+        ctx.send_transaction_with_signers(swap_ix.instructions, signers).await?;
+
+        assert_eq!(aggregate_limit_order_exposure(&ctx.rpc, ctx.signer.pubkey()).await?, (2_600_000, 500_000));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_fetch_tick_arrays_with_orders() -> Result<(), Box<dyn Error>> {
+        let ctx = RpcContext::new().await;
+
+        let minted = setup_all_mints(&ctx).await?;
+        let _user_atas = setup_all_atas(&ctx, &minted).await?;
+
+        let mint_a_key = minted.get("A").unwrap();
+        let mint_b_key = minted.get("B").unwrap();
+        let (mint_a, mint_b) = if mint_a_key < mint_b_key {
+            (*mint_a_key, *mint_b_key)
+        } else {
+            (*mint_b_key, *mint_a_key)
+        };
+        let pool_pubkey = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 300).await?;
+
+        // Initializes the tick arrays starting at -5632 and 0 without placing any orders.
+        setup_position(&ctx, pool_pubkey, Some((-5632, 128)), None).await?;
+        assert!(fetch_tick_arrays_with_orders(&ctx.rpc, pool_pubkey, 1).await?.is_empty());
+
+        let open_ix =
+            open_limit_order_instructions(&ctx.rpc, pool_pubkey, 1_000_000, PriceOrTickIndex::Tick(6400), true, Some(ctx.signer.pubkey())).await?;
+        let signers: Vec<&Keypair> = open_ix.additional_signers.iter().collect();
+        ctx.send_transaction_with_signers(open_ix.instructions, signers).await?;
+
+        assert_eq!(fetch_fusion_pool_tick_arrays(&ctx.rpc, pool_pubkey, 1).await?.len(), 3);
+        let tick_arrays = fetch_tick_arrays_with_orders(&ctx.rpc, pool_pubkey, 1).await?;
+        assert_eq!(tick_arrays.len(), 1);
+        assert_eq!(tick_arrays[0].data.start_tick_index, 5632);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_best_limit_order_pool() -> Result<(), Box<dyn Error>> {
+        let ctx = RpcContext::new().await;
+
+        let mint_1 = setup_mint_with_decimals(&ctx, 9).await?;
+        let mint_2 = setup_mint_with_decimals(&ctx, 9).await?;
+        let [mint_a_key, mint_b_key] = order_mints(mint_1, mint_2)?;
+
+        let coarse_pool = setup_fusion_pool(&ctx, mint_a_key, mint_b_key, 64, 300).await?;
+        let desired_price = 1.105;
+        assert_eq!(best_limit_order_pool(&ctx.rpc, mint_a_key, mint_b_key, desired_price, true).await?, (coarse_pool, 1024));
+        assert_eq!(best_limit_order_pool(&ctx.rpc, mint_a_key, mint_b_key, desired_price, false).await?, (coarse_pool, 960));
+
+        // The finer tick spacing reaches the desired price more precisely.
+        let fine_pool = setup_fusion_pool(&ctx, mint_a_key, mint_b_key, 1, 100).await?;
+        let (pool_address, tick_index) = best_limit_order_pool(&ctx.rpc, mint_b_key, mint_a_key, desired_price, true).await?;
+        assert_eq!(pool_address, fine_pool);
+        assert_eq!(tick_index, price_to_tick_index(desired_price, 9, 9));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_open_limit_order_with_transfer_hook() -> Result<(), Box<dyn Error>> {
+        let ctx = RpcContext::new().await;
+
+        let mint_hook = setup_mint_te(&ctx, &[ExtensionType::TransferHook]).await?;
+        let mint_other = setup_mint_with_decimals(&ctx, 6).await?;
+        let extra_account = setup_transfer_hook_validation_account(&ctx, mint_hook).await?;
+        setup_ata_te(&ctx, mint_hook, Some(SetupAtaConfig { amount: Some(1_000_000) })).await?;
+
+        // Mints with a transfer hook require a token badge.
+        ctx.send_transaction(vec![InitializeTokenBadge {
+            fusion_pools_config: get_fusion_pools_config_address()?.0,
+            token_badge_authority: ctx.signer.pubkey(),
+            token_mint: mint_hook,
+            token_badge: get_token_badge_address(&mint_hook)?.0,
+            funder: ctx.signer.pubkey(),
+            system_program: solana_program::system_program::id(),
+        }
+        .instruction()])
+            .await?;
+
+        let [mint_a, mint_b] = order_mints(mint_hook, mint_other)?;
+        let pool_pubkey = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 300).await?;
+        let a_to_b = mint_a == mint_hook;
+
+        let open_ix =
+            open_limit_order_instructions(&ctx.rpc, pool_pubkey, 1_000, PriceOrTickIndex::Tick(128), a_to_b, Some(ctx.signer.pubkey())).await?;
+        let increase_ix = open_ix.instructions.iter().find(|ix| {
+            let accounts: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+            matches!(decode_instruction(&ix.data, &accounts), Some(DecodedFusionInstruction::IncreaseLimitOrder { .. }))
+        });
+        let increase_ix = increase_ix.ok_or("IncreaseLimitOrder instruction not found")?;
+        let accounts: Vec<Pubkey> = increase_ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        let Some(DecodedFusionInstruction::IncreaseLimitOrder { args, .. }) = decode_instruction(&increase_ix.data, &accounts) else {
+            unreachable!();
+        };
+
+        let accounts_type = if a_to_b {
+            AccountsType::TransferHookA
+        } else {
+            AccountsType::TransferHookB
+        };
+        assert_eq!(
+            args.remaining_accounts_info,
+            Some(RemainingAccountsInfo {
+                slices: vec![RemainingAccountsSlice { accounts_type, length: 3 }],
+            })
+        );
+
+        // The extra accounts are appended after the accounts of the instruction.
+        let validation_address = get_extra_account_metas_address(&mint_hook, &TEST_TRANSFER_HOOK_PROGRAM_ID);
+        assert_eq!(
+            increase_ix.accounts[increase_ix.accounts.len() - 3..],
+            [
+                AccountMeta::new(extra_account, false),
+                AccountMeta::new_readonly(TEST_TRANSFER_HOOK_PROGRAM_ID, false),
+                AccountMeta::new_readonly(validation_address, false),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_decrease_limit_order_creates_missing_token_account() -> Result<(), Box<dyn Error>> {
+        let ctx = RpcContext::new().await;
+
+        let mint_1 = setup_mint_with_decimals(&ctx, 9).await?;
+        let mint_2 = setup_mint_with_decimals(&ctx, 9).await?;
+        let (mint_a, mint_b) = if mint_1 < mint_2 { (mint_1, mint_2) } else { (mint_2, mint_1) };
+        let pool_pubkey = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 300).await?;
+
+        // Only the input token account exists.
+        setup_ata_with_amount(&ctx, mint_a, 1_000_000_000).await?;
+        let ata_b = get_associated_token_address_with_program_id(&ctx.signer.pubkey(), &mint_b, &spl_token::ID);
+        assert!(ctx.rpc.get_account(&ata_b).await.is_err());
+
+        let open_ix =
+            open_limit_order_instructions(&ctx.rpc, pool_pubkey, 1_000_000, PriceOrTickIndex::Tick(128), true, Some(ctx.signer.pubkey())).await?;
+        let signers: Vec<&Keypair> = open_ix.additional_signers.iter().collect();
+        ctx.send_transaction_with_signers(open_ix.instructions, signers).await?;
+
+        let decrease_ix = decrease_limit_order_instructions(&ctx.rpc, open_ix.limit_order_mint, 500_000, Some(ctx.signer.pubkey())).await?;
+        let signers: Vec<&Keypair> = decrease_ix.additional_signers.iter().collect();
+        ctx.send_transaction_with_signers(decrease_ix.instructions, signers).await?;
+
+        // The missing token account is created as the associated token account of the owner.
+        let token_account = TokenAccount::unpack(&ctx.rpc.get_account(&ata_b).await?.data)?;
+        assert_eq!(token_account.mint, mint_b);
+        assert_eq!(token_account.owner, ctx.signer.pubkey());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_estimate_open_limit_order_rent() -> Result<(), Box<dyn Error>> {
+        let ctx = RpcContext::new().await;
+
+        let minted = setup_all_mints(&ctx).await?;
+        let _user_atas = setup_all_atas(&ctx, &minted).await?;
+
+        let mint_a_key = minted.get("A").unwrap();
+        let mint_b_key = minted.get("B").unwrap();
+        let pool_pubkey = setup_fusion_pool(&ctx, *mint_a_key, *mint_b_key, 64, 300).await?;
+
+        let estimated_rent = estimate_open_limit_order_rent(&ctx.rpc, pool_pubkey, 128).await?;
+        let open_ix =
+            open_limit_order_instructions(&ctx.rpc, pool_pubkey, 1_000_000, PriceOrTickIndex::Tick(128), true, Some(ctx.signer.pubkey())).await?;
+        assert!(estimated_rent > 0);
+        assert_eq!(estimated_rent, open_ix.initialization_cost);
+
+        let signers: Vec<&Keypair> = open_ix.additional_signers.iter().collect();
+        ctx.send_transaction_with_signers(open_ix.instructions, signers).await?;
+
+        let estimated_rent = estimate_open_limit_order_rent(&ctx.rpc, pool_pubkey, 128).await?;
+        let open_ix =
+            open_limit_order_instructions(&ctx.rpc, pool_pubkey, 1_000_000, PriceOrTickIndex::Tick(128), true, Some(ctx.signer.pubkey())).await?;
+        assert_eq!(estimated_rent, 0);
+        assert_eq!(estimated_rent, open_ix.initialization_cost);
+
+        Ok(())
+    }
 }
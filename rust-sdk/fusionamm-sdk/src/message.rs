@@ -0,0 +1,117 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use solana_instruction::Instruction;
+use solana_message::{v0, AddressLookupTableAccount, VersionedMessage};
+use solana_program::hash::Hash;
+use solana_pubkey::Pubkey;
+use std::error::Error;
+
+/// Compiles the instructions returned by the SDK builders into a ready-to-sign v0 message.
+///
+/// Accounts found in the provided address lookup tables are referenced through the tables
+/// instead of being included in the message's static account keys.
+///
+/// # Arguments
+///
+/// * `instructions` - The instructions to include in the message.
+/// * `payer` - The public key of the fee payer.
+/// * `lookup_tables` - The address lookup tables available to the message.
+/// * `recent_blockhash` - A recent blockhash.
+///
+/// # Returns
+///
+/// A `Result` containing the compiled `VersionedMessage` on success.
+///
+/// # Errors
+///
+/// This function will return an error if the message can't be compiled, for example if it
+/// references too many accounts.
+pub fn build_versioned_message(
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    lookup_tables: &[AddressLookupTableAccount],
+    recent_blockhash: Hash,
+) -> Result<VersionedMessage, Box<dyn Error>> {
+    Ok(VersionedMessage::V0(v0::Message::try_compile(payer, instructions, lookup_tables, recent_blockhash)?))
+}
+
+/// The maximum size of a serialized transaction in bytes.
+pub(crate) const MAX_TRANSACTION_SIZE: usize = 1232;
+
+/// Estimates the serialized size in bytes of a transaction containing the instructions, signed by
+/// the signers required by the compiled message.
+pub(crate) fn estimate_transaction_size(instructions: &[Instruction], payer: &Pubkey) -> Result<usize, Box<dyn Error>> {
+    let message = build_versioned_message(instructions, payer, &[], Hash::default())?;
+    let num_signers = message.header().num_required_signatures as usize;
+    // The signatures are prefixed by their count, encoded as a single byte below 128 signatures.
+    Ok(1 + num_signers * 64 + bincode::serialized_size(&message)? as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use fusionamm_client::FusionPool;
+    use serial_test::serial;
+    use solana_message::{AddressLookupTableAccount, VersionedMessage};
+    use solana_program::hash::Hash;
+    use solana_program_test::tokio;
+    use solana_pubkey::Pubkey;
+    use solana_signer::Signer;
+
+    use crate::{
+        build_versioned_message, increase_liquidity_instructions, swap_instructions,
+        tests::{setup_ata_with_amount, setup_fusion_pool, setup_mint_with_decimals, setup_position, RpcContext},
+        IncreaseLiquidityParam, SwapType,
+    };
+
+    #[tokio::test]
+    #[serial]
+    async fn test_build_versioned_message_with_lookup_table() {
+        let ctx = RpcContext::new().await;
+
+        let mint_a = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+        let mint_b = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+        let (mint_a, mint_b) = if mint_a < mint_b { (mint_a, mint_b) } else { (mint_b, mint_a) };
+        setup_ata_with_amount(&ctx, mint_a, 1_000_000_000).await.unwrap();
+        setup_ata_with_amount(&ctx, mint_b, 1_000_000_000).await.unwrap();
+
+        let pool_address = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 300).await.unwrap();
+        let position_mint = setup_position(&ctx, pool_address, Some((-192, 192)), None).await.unwrap();
+        let liquidity_ix = increase_liquidity_instructions(
+            &ctx.rpc,
+            position_mint,
+            IncreaseLiquidityParam::Liquidity(10_000_000),
+            Some(100),
+            Some(ctx.signer.pubkey()),
+        )
+        .await
+        .unwrap();
+        ctx.send_transaction_with_signers(liquidity_ix.instructions, liquidity_ix.additional_signers.iter().collect())
+            .await
+            .unwrap();
+
+        let swap_ix = swap_instructions(&ctx.rpc, pool_address, 1000, mint_a, SwapType::ExactIn, Some(100), Some(ctx.signer.pubkey()))
+            .await
+            .unwrap();
+
+        let pool = FusionPool::from_bytes(&ctx.rpc.get_account(&pool_address).await.unwrap().data).unwrap();
+        let lookup_table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: vec![pool.token_vault_a, pool.token_vault_b, mint_a, mint_b],
+        };
+
+        let message = build_versioned_message(&swap_ix.instructions, &ctx.signer.pubkey(), &[lookup_table.clone()], Hash::new_unique()).unwrap();
+        let VersionedMessage::V0(message) = message else {
+            panic!("Expected a v0 message");
+        };
+        assert_eq!(message.address_table_lookups.len(), 1);
+        assert_eq!(message.address_table_lookups[0].account_key, lookup_table.key);
+        assert!(!message.account_keys.contains(&pool.token_vault_a));
+        assert!(!message.account_keys.contains(&pool.token_vault_b));
+        assert!(message.account_keys.contains(&pool_address));
+    }
+}
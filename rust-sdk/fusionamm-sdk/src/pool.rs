@@ -8,12 +8,17 @@
 // See the LICENSE file in the project root for license information.
 //
 
-use fusionamm_client::{fetch_all_fusion_pool_with_filter, get_fusion_pool_address, DecodedAccount, FusionPool, FusionPoolFilter};
+use fusionamm_client::{
+    fetch_all_fusion_pool_with_filter, get_fusion_pool_address, get_fusion_pools_config_address, get_tick_array_address, DecodedAccount, FusionPool,
+    FusionPoolFilter, FusionPoolsConfig, SetFeeRate, SetFeeRateInstructionArgs, TickArray,
+};
+use fusionamm_core::{get_tick_array_start_tick_index, sqrt_price_to_price, MAX_FEE_RATE, MAX_TICK_INDEX, MIN_TICK_INDEX, TICK_ARRAY_SIZE};
 use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_instruction::Instruction;
 use solana_program::pubkey::Pubkey;
-use std::error::Error;
+use std::{error::Error, iter::zip};
 
-use crate::order_mints;
+use crate::{fetch_account, fetch_multiple_accounts, order_mints};
 
 #[cfg(not(doctest))]
 /// Fetches the details of a specific Concentrated Liquidity Pool.
@@ -37,6 +42,7 @@ use crate::order_mints;
 /// # Errors
 ///
 /// This function will return an error if:
+/// - Both token mints are the same.
 /// - Any required account or mint information cannot be fetched.
 /// - The pool or its configuration details are invalid.
 pub async fn fetch_fusion_pool_by_token_pair_and_tick_spacing(
@@ -45,10 +51,10 @@ pub async fn fetch_fusion_pool_by_token_pair_and_tick_spacing(
     token_2: Pubkey,
     tick_spacing: u16,
 ) -> Result<DecodedAccount<FusionPool>, Box<dyn Error>> {
-    let [token_a, token_b] = order_mints(token_1, token_2);
+    let [token_a, token_b] = order_mints(token_1, token_2)?;
     let fusion_pool_address = get_fusion_pool_address(&token_a, &token_b, tick_spacing)?.0;
 
-    let fusion_pool_account = rpc.get_account(&fusion_pool_address).await?;
+    let fusion_pool_account = fetch_account(rpc, &fusion_pool_address).await?;
     let fusion_pool = FusionPool::from_bytes(&fusion_pool_account.data)?;
 
     Ok(DecodedAccount {
@@ -80,6 +86,7 @@ pub async fn fetch_fusion_pool_by_token_pair_and_tick_spacing(
 /// # Errors
 ///
 /// This function will return an error if:
+/// - Both token mints are the same.
 /// - Any required account or mint information cannot be fetched.
 /// - The pool or its configuration details are invalid.
 ///
@@ -88,9 +95,9 @@ pub async fn fetch_fusion_pools_by_token_pair(
     token_1: Pubkey,
     token_2: Pubkey,
 ) -> Result<Vec<DecodedAccount<FusionPool>>, Box<dyn Error>> {
-    let [token_a, token_b] = order_mints(token_1, token_2);
+    let [token_a, token_b] = order_mints(token_1, token_2)?;
 
-    let account_infos = rpc.get_multiple_accounts(&[token_a, token_b]).await?;
+    let account_infos = fetch_multiple_accounts(rpc, &[token_a, token_b]).await?;
 
     account_infos[0].as_ref().ok_or(format!("Mint {} not found", token_a))?;
     account_infos[1].as_ref().ok_or(format!("Mint {} not found", token_b))?;
@@ -101,12 +108,166 @@ pub async fn fetch_fusion_pools_by_token_pair(
     Ok(fusion_pools)
 }
 
+#[cfg(not(doctest))]
+/// Lists the pool configurations which have an existing fusion pool for a token pair.
+///
+/// This function is built on `fetch_fusion_pools_by_token_pair` and helps to pick a tick spacing
+/// and fee rate before opening a position.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `token_1` - The public key of the first token mint in the pool.
+/// * `token_2` - The public key of the second token mint in the pool.
+///
+/// # Returns
+///
+/// A `Result` containing `(tick_spacing, fee_rate, pool_address)` tuples sorted by tick spacing.
+///
+/// # Errors
+///
+/// This function will return an error if any of the mints or the pools cannot be fetched.
+pub async fn list_pool_configs_for_pair(rpc: &RpcClient, token_1: Pubkey, token_2: Pubkey) -> Result<Vec<(u16, u16, Pubkey)>, Box<dyn Error>> {
+    let fusion_pools = fetch_fusion_pools_by_token_pair(rpc, token_1, token_2).await?;
+
+    let mut pool_configs: Vec<(u16, u16, Pubkey)> = fusion_pools
+        .into_iter()
+        .map(|pool| (pool.data.tick_spacing, pool.data.fee_rate, pool.address))
+        .collect();
+    pool_configs.sort_by_key(|x| x.0);
+
+    Ok(pool_configs)
+}
+
+#[cfg(not(doctest))]
+/// Fetches the initialized tick arrays around the current price of a fusion pool.
+///
+/// This function reads the pool's current tick index and tick spacing, computes the start indexes of
+/// `radius` tick arrays on each side of the current one and fetches them with a single RPC request.
+/// Tick arrays which have not been initialized are skipped.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `fusion_pool_address` - The public key of the fusion pool.
+/// * `radius` - The number of tick arrays to fetch on each side of the current tick array.
+///
+/// # Returns
+///
+/// A `Result` containing the initialized tick arrays ordered by their start tick index.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The fusion pool account cannot be fetched or decoded.
+/// - Any of the tick array accounts cannot be decoded.
+pub async fn fetch_fusion_pool_tick_arrays(
+    rpc: &RpcClient,
+    fusion_pool_address: Pubkey,
+    radius: u16,
+) -> Result<Vec<DecodedAccount<TickArray>>, Box<dyn Error>> {
+    let fusion_pool_account = fetch_account(rpc, &fusion_pool_address).await?;
+    let fusion_pool = FusionPool::from_bytes(&fusion_pool_account.data)?;
+
+    let offset = fusion_pool.tick_spacing as i32 * TICK_ARRAY_SIZE as i32;
+    let current_start_index = get_tick_array_start_tick_index(fusion_pool.tick_current_index, fusion_pool.tick_spacing);
+    let min_start_index = get_tick_array_start_tick_index(MIN_TICK_INDEX, fusion_pool.tick_spacing);
+
+    let tick_array_addresses = (-(radius as i32)..=radius as i32)
+        .map(|i| current_start_index + i * offset)
+        .filter(|x| (min_start_index..=MAX_TICK_INDEX).contains(x))
+        .map(|x| get_tick_array_address(&fusion_pool_address, x).map(|y| y.0))
+        .collect::<Result<Vec<Pubkey>, _>>()?;
+
+    let tick_array_infos = fetch_multiple_accounts(rpc, &tick_array_addresses).await?;
+
+    let mut tick_arrays: Vec<DecodedAccount<TickArray>> = Vec::new();
+    for (address, account) in zip(tick_array_addresses, tick_array_infos) {
+        if let Some(account) = account {
+            let data = TickArray::from_bytes(&account.data)?;
+            tick_arrays.push(DecodedAccount { address, account, data });
+        }
+    }
+
+    Ok(tick_arrays)
+}
+
+/// Returns the current spot price of a fusion pool.
+///
+/// The FusionAMM program doesn't record price observations, so a time-weighted average price
+/// can't be derived from the pool account. The spot price reflects the last swap and can be moved
+/// within a single transaction, so it shouldn't be relied upon as a manipulation-resistant oracle.
+///
+/// # Arguments
+///
+/// * `pool` - The fusion pool state.
+/// * `decimals_a` - The number of decimals of token A.
+/// * `decimals_b` - The number of decimals of token B.
+///
+/// # Returns
+///
+/// The price of token A denominated in token B, adjusted for the token decimals.
+pub fn spot_price(pool: &FusionPool, decimals_a: u8, decimals_b: u8) -> f64 {
+    sqrt_price_to_price(pool.sqrt_price, decimals_a, decimals_b)
+}
+
+/// Generates the instruction to update the fee rate of a fusion pool.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `pool_address` - The public key of the fusion pool.
+/// * `new_fee_rate` - The new fee rate, denominated in hundredths of a basis point.
+/// * `authority` - The public key of the fee authority. It must match the pools config.
+///
+/// # Returns
+///
+/// A `Result` containing the instructions to update the fee rate.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The pool or the pools config accounts cannot be fetched.
+/// - The provided authority is not the config's fee authority.
+/// - The new fee rate exceeds `MAX_FEE_RATE`.
+pub async fn set_fee_rate_instructions(
+    rpc: &RpcClient,
+    pool_address: Pubkey,
+    new_fee_rate: u16,
+    authority: Pubkey,
+) -> Result<Vec<Instruction>, Box<dyn Error>> {
+    if new_fee_rate > MAX_FEE_RATE {
+        return Err(format!("Fee rate {} exceeds the maximum of {}", new_fee_rate, MAX_FEE_RATE).into());
+    }
+
+    let config_address = get_fusion_pools_config_address()?.0;
+    let account_infos = fetch_multiple_accounts(rpc, &[pool_address, config_address]).await?;
+
+    let pool_info = account_infos[0].as_ref().ok_or(format!("Fusion pool {} not found", pool_address))?;
+    FusionPool::from_bytes(&pool_info.data)?;
+
+    let config_info = account_infos[1].as_ref().ok_or("Fusion pools config not found")?;
+    let config = FusionPoolsConfig::from_bytes(&config_info.data)?;
+
+    if authority != config.fee_authority {
+        return Err(format!("{} is not the fee authority", authority).into());
+    }
+
+    Ok(vec![SetFeeRate {
+        fusion_pools_config: config_address,
+        fusion_pool: pool_address,
+        fee_authority: authority,
+    }
+    .instruction(SetFeeRateInstructionArgs { fee_rate: new_fee_rate })])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tests::{setup_ata_with_amount, setup_fusion_pool, setup_mint_with_decimals, RpcContext};
+    use crate::tests::{init_tick_arrays_for_range, setup_ata_with_amount, setup_fusion_pool, setup_mint_with_decimals, RpcContext};
     use serial_test::serial;
     use solana_program_test::tokio;
+    use solana_signer::Signer;
 
     struct TestContext {
         ctx: RpcContext,
@@ -153,4 +314,95 @@ mod tests {
         assert_eq!(pool.data.fee_rate, 300);
         assert_eq!(pool.data.protocol_fee_rate, 0);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_fetch_pool_with_identical_mints() {
+        let test_ctx = TestContext::new().await.unwrap();
+
+        let result = fetch_fusion_pool_by_token_pair_and_tick_spacing(&test_ctx.ctx.rpc, test_ctx.mint_a, test_ctx.mint_a, 64).await;
+        assert!(result.unwrap_err().to_string().contains("Token mints must be different"));
+
+        let result = fetch_fusion_pools_by_token_pair(&test_ctx.ctx.rpc, test_ctx.mint_a, test_ctx.mint_a).await;
+        assert!(result.unwrap_err().to_string().contains("Token mints must be different"));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_spot_price() {
+        let test_ctx = TestContext::new().await.unwrap();
+        let pool = FusionPool::from_bytes(&test_ctx.ctx.rpc.get_account(&test_ctx.concentrated_pool).await.unwrap().data).unwrap();
+
+        assert_eq!(spot_price(&pool, 9, 9), sqrt_price_to_price(pool.sqrt_price, 9, 9));
+        assert!((spot_price(&pool, 9, 9) - 1.0).abs() < 1e-9);
+        assert!((spot_price(&pool, 9, 6) - 1000.0).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_list_pool_configs_for_pair() {
+        let test_ctx = TestContext::new().await.unwrap();
+        let splash_pool = setup_fusion_pool(&test_ctx.ctx, test_ctx.mint_a, test_ctx.mint_b, 32, 100).await.unwrap();
+
+        let pool_configs = list_pool_configs_for_pair(&test_ctx.ctx.rpc, test_ctx.mint_b, test_ctx.mint_a)
+            .await
+            .unwrap();
+
+        assert_eq!(pool_configs, vec![(32, 100, splash_pool), (64, 300, test_ctx.concentrated_pool)]);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_fetch_fusion_pool_tick_arrays() {
+        let test_ctx = TestContext::new().await.unwrap();
+        let offset = 64 * TICK_ARRAY_SIZE as i32;
+
+        init_tick_arrays_for_range(&test_ctx.ctx, test_ctx.concentrated_pool, -2 * offset, offset, 64)
+            .await
+            .unwrap();
+
+        let tick_arrays = fetch_fusion_pool_tick_arrays(&test_ctx.ctx.rpc, test_ctx.concentrated_pool, 0)
+            .await
+            .unwrap();
+        assert_eq!(tick_arrays.iter().map(|x| x.data.start_tick_index).collect::<Vec<i32>>(), vec![0]);
+
+        let tick_arrays = fetch_fusion_pool_tick_arrays(&test_ctx.ctx.rpc, test_ctx.concentrated_pool, 1)
+            .await
+            .unwrap();
+        assert_eq!(tick_arrays.iter().map(|x| x.data.start_tick_index).collect::<Vec<i32>>(), vec![-offset, 0, offset]);
+
+        let tick_arrays = fetch_fusion_pool_tick_arrays(&test_ctx.ctx.rpc, test_ctx.concentrated_pool, 3)
+            .await
+            .unwrap();
+        assert_eq!(tick_arrays.iter().map(|x| x.data.start_tick_index).collect::<Vec<i32>>(), vec![-2 * offset, -offset, 0, offset]);
+        assert_eq!(tick_arrays[0].address, get_tick_array_address(&test_ctx.concentrated_pool, -2 * offset).unwrap().0);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_set_fee_rate() {
+        let test_ctx = TestContext::new().await.unwrap();
+        let ctx = &test_ctx.ctx;
+
+        let instructions = set_fee_rate_instructions(&ctx.rpc, test_ctx.concentrated_pool, 1000, ctx.signer.pubkey())
+            .await
+            .unwrap();
+        ctx.send_transaction(instructions).await.unwrap();
+
+        let pool = fetch_fusion_pool_by_token_pair_and_tick_spacing(&ctx.rpc, test_ctx.mint_a, test_ctx.mint_b, 64)
+            .await
+            .unwrap();
+        assert_eq!(pool.data.fee_rate, 1000);
+
+        let instructions = set_fee_rate_instructions(&ctx.rpc, test_ctx.concentrated_pool, MAX_FEE_RATE, ctx.signer.pubkey())
+            .await
+            .unwrap();
+        ctx.send_transaction(instructions).await.unwrap();
+
+        let result = set_fee_rate_instructions(&ctx.rpc, test_ctx.concentrated_pool, MAX_FEE_RATE + 1, ctx.signer.pubkey()).await;
+        assert!(result.unwrap_err().to_string().contains("exceeds the maximum"));
+
+        let result = set_fee_rate_instructions(&ctx.rpc, test_ctx.concentrated_pool, 1000, Pubkey::new_unique()).await;
+        assert!(result.unwrap_err().to_string().contains("is not the fee authority"));
+    }
 }
@@ -9,16 +9,35 @@
 //
 
 use fusionamm_client::{
-    fetch_all_position_with_filter, get_bundled_position_address, get_position_address, get_position_bundle_address, DecodedAccount, Position,
-    PositionBundle, PositionFilter,
+    fetch_all_position_with_filter, get_bundled_position_address, get_position_address, get_position_bundle_address, DecodedAccount, FusionPool,
+    Position, PositionBundle, PositionFilter,
+};
+use fusionamm_core::{
+    get_initializable_tick_index, increase_liquidity_quote, increase_liquidity_quote_a, increase_liquidity_quote_b, order_tick_indexes,
+    position_ratio_x64, position_status, swap_quote_by_input_token, ExactInSwapQuote, IncreaseLiquidityQuote, PositionStatus, POSITION_BUNDLE_SIZE,
 };
-use fusionamm_core::POSITION_BUNDLE_SIZE;
 use solana_account::Account;
 use solana_client::{nonblocking::rpc_client::RpcClient, rpc_request::TokenAccountsFilter};
+use solana_instruction::Instruction;
+use solana_keypair::Keypair;
 use solana_pubkey::Pubkey;
-use std::{collections::HashMap, error::Error};
+use solana_signer::Signer;
+use spl_associated_token_account::{get_associated_token_address_with_program_id, instruction::create_associated_token_account};
+use spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+use spl_token_2022::state::Mint;
+use spl_token_metadata_interface::state::TokenMetadata;
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+};
 
-use crate::{get_token_accounts_for_owner, ParsedTokenAccount};
+use crate::increase_liquidity::open_position_with_token_accounts_instructions;
+use crate::swap::{fetch_tick_arrays_or_default, map_quote_error, swap_instruction, to_tick_arrays};
+use crate::token::{get_current_transfer_fee, prepare_token_accounts_instructions, TokenAccountStrategy};
+use crate::{
+    fetch_account, fetch_multiple_accounts, get_token_accounts_for_owner, ParsedTokenAccount, SwapQuote, CONSERVATIVE_TRANSFER_FEE, FUNDER,
+    SLIPPAGE_TOLERANCE_BPS, TICK_ARRAY_RADIUS,
+};
 
 /// Represents a single Position account.
 ///
@@ -161,14 +180,14 @@ pub async fn fetch_positions_for_owner(rpc: &RpcClient, owner: Pubkey) -> Result
         .map(|x| get_position_bundle_address(&x.mint).map(|x| x.0))
         .collect::<Result<Vec<Pubkey>, _>>()?;
 
-    let position_infos = rpc.get_multiple_accounts(&position_addresses).await?;
+    let position_infos = fetch_multiple_accounts(rpc, &position_addresses).await?;
 
     let positions: Vec<Option<Position>> = position_infos
         .iter()
         .map(|x| x.as_ref().and_then(|x| Position::from_bytes(&x.data).ok()))
         .collect();
 
-    let position_bundle_infos = rpc.get_multiple_accounts(&position_bundle_addresses).await?;
+    let position_bundle_infos = fetch_multiple_accounts(rpc, &position_bundle_addresses).await?;
 
     let position_bundles: Vec<Option<PositionBundle>> = position_bundle_infos
         .iter()
@@ -284,11 +303,401 @@ pub async fn fetch_positions_in_fusion_pool(rpc: &RpcClient, fusion_pool: Pubkey
     fetch_all_position_with_filter(rpc, filters).await
 }
 
+/// Computes the liquidity curve of a set of positions.
+///
+/// The result is a step function sorted by tick index: each entry holds a tick index and the total liquidity
+/// of the positions active from that tick up to the tick of the next entry. The last entry always has zero liquidity.
+/// Use it with the positions returned by `fetch_positions_in_fusion_pool` to plot the liquidity of a pool.
+///
+/// # Arguments
+///
+/// * `positions` - The positions to aggregate.
+///
+/// # Returns
+///
+/// A vector of `(tick_index, liquidity)` pairs. Empty if none of the positions has liquidity.
+pub fn get_liquidity_histogram(positions: &[DecodedAccount<Position>]) -> Vec<(i32, u128)> {
+    let mut liquidity_deltas: BTreeMap<i32, i128> = BTreeMap::new();
+    for position in positions.iter().filter(|x| x.data.liquidity > 0) {
+        *liquidity_deltas.entry(position.data.tick_lower_index).or_default() += position.data.liquidity as i128;
+        *liquidity_deltas.entry(position.data.tick_upper_index).or_default() -= position.data.liquidity as i128;
+    }
+
+    let mut liquidity: i128 = 0;
+    liquidity_deltas
+        .into_iter()
+        .filter(|(_, delta)| *delta != 0)
+        .map(|(tick_index, delta)| {
+            liquidity += delta;
+            (tick_index, liquidity as u128)
+        })
+        .collect()
+}
+
+/// Fetches the token metadata of a position NFT.
+///
+/// Position and limit order NFTs minted with the Token-2022 program store their name, symbol
+/// and uri in the token metadata extension of the mint account. This function works for both kinds of mints.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `position_mint` - The public key of the position or limit order NFT mint.
+///
+/// # Returns
+///
+/// A `Result` containing the `TokenMetadata` of the mint, or `None` if the mint
+/// has no token metadata extension (e.g. a legacy SPL Token mint).
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The mint account cannot be fetched.
+/// - The mint account data or its metadata extension cannot be decoded.
+pub async fn fetch_position_metadata(rpc: &RpcClient, position_mint: Pubkey) -> Result<Option<TokenMetadata>, Box<dyn Error>> {
+    let mint_account = fetch_account(rpc, &position_mint).await?;
+    if mint_account.owner != spl_token_2022::ID {
+        return Ok(None);
+    }
+
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_account.data)?;
+    if !mint.get_extension_types()?.contains(&ExtensionType::TokenMetadata) {
+        return Ok(None);
+    }
+
+    Ok(Some(mint.get_variable_len_extension::<TokenMetadata>()?))
+}
+
+/// Generates instructions to transfer a position NFT to a new owner.
+///
+/// The destination associated token account is created if it does not exist yet, paid for by the current owner.
+/// The token account of the current owner is left empty and is not closed.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `position_mint` - The public key of the position NFT mint.
+/// * `from` - The public key of the current owner of the position NFT. It must sign the transaction.
+/// * `to` - The public key of the new owner of the position NFT.
+///
+/// # Returns
+///
+/// A `Result` containing the instructions to create the destination token account if needed,
+/// followed by the transfer of the position NFT.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The position mint account cannot be fetched or decoded.
+/// - The current owner does not hold the position NFT.
+pub async fn transfer_position_instructions(
+    rpc: &RpcClient,
+    position_mint: Pubkey,
+    from: Pubkey,
+    to: Pubkey,
+) -> Result<Vec<Instruction>, Box<dyn Error>> {
+    let mint_account = fetch_account(rpc, &position_mint).await?;
+    let token_program = mint_account.owner;
+    // The metadata extension makes the mint account variable-length, so it must be unpacked with its extensions.
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_account.data)?;
+
+    let source = get_associated_token_address_with_program_id(&from, &position_mint, &token_program);
+    let destination = get_associated_token_address_with_program_id(&to, &position_mint, &token_program);
+    let token_accounts = fetch_multiple_accounts(rpc, &[source, destination]).await?;
+
+    let source_account = token_accounts[0].as_ref().ok_or(format!("Position token account {} not found", source))?;
+    let source_amount = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&source_account.data)?
+        .base
+        .amount;
+    if source_amount != 1 {
+        return Err(format!("{} does not hold the position NFT {}", from, position_mint).into());
+    }
+
+    let mut instructions = vec![];
+    if token_accounts[1].is_none() {
+        instructions.push(create_associated_token_account(&from, &to, &position_mint, &token_program));
+    }
+    instructions.push(spl_token_2022::instruction::transfer_checked(
+        &token_program,
+        &source,
+        &position_mint,
+        &destination,
+        &from,
+        &[],
+        1,
+        mint.base.decimals,
+    )?);
+
+    Ok(instructions)
+}
+
+/// Represents the instructions and quotes for swapping a single token into a new position.
+#[derive(Debug)]
+pub struct ZapInPositionInstruction {
+    /// The public key of the position NFT that represents ownership of the newly opened position.
+    pub position_mint: Pubkey,
+
+    /// The quote of the swap balancing the deposit. `None` if the position only takes the input token.
+    pub swap_quote: Option<ExactInSwapQuote>,
+
+    /// The quote for the deposit into the position. `liquidity_delta` is the expected liquidity of the position.
+    pub quote: IncreaseLiquidityQuote,
+
+    /// A vector of `Instruction` objects required to swap and open the position.
+    pub instructions: Vec<Instruction>,
+
+    /// A vector of `Keypair` objects representing additional signers required for the instructions.
+    pub additional_signers: Vec<Keypair>,
+
+    /// The cost of initializing the position, measured in lamports.
+    pub initialization_cost: u64,
+}
+
+/// Generates instructions to open a position funded with a single token.
+///
+/// Part of the input is swapped to the other token of the pool first, so that the balances match the
+/// deposit ratio of the tick range at the current price (see `position_ratio_x64`). The position is then
+/// opened with the remaining input and the minimum output of the swap, priced at the sqrt price after the swap.
+/// The swapped amount is searched so that the swap fee and price impact are accounted for, and only a small
+/// amount of either token is left over.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `pool_address` - The public key of the fusion pool.
+/// * `input_mint` - The mint of the input token. It must be one of the tokens of the pool.
+/// * `input_amount` - The amount of the input token to deposit.
+/// * `tick_range` - The lower and upper tick indexes of the position.
+/// * `slippage_tolerance_bps` - An optional slippage tolerance in basis points. Defaults to the global setting if not provided.
+/// * `funder` - An optional public key of the funder and owner of the position. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing `ZapInPositionInstruction` on success:
+///
+/// * `position_mint` - The mint address of the position NFT.
+/// * `swap_quote` - The quote of the swap, if any.
+/// * `quote` - The quote for the deposit, including the expected liquidity.
+/// * `instructions` - A vector of `Instruction` objects required to swap and open the position.
+/// * `additional_signers` - A vector of `Keypair` objects representing additional signers required for the instructions.
+/// * `initialization_cost` - The cost of initializing the position, measured in lamports.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The funder account is invalid.
+/// - The input mint is not a token of the pool.
+/// - The pool or the mint accounts cannot be fetched.
+/// - The swap or the deposit cannot be quoted.
+/// - The funder doesn't hold the input amount.
+#[allow(clippy::too_many_arguments)]
+pub async fn zap_in_position_instructions(
+    rpc: &RpcClient,
+    pool_address: Pubkey,
+    input_mint: Pubkey,
+    input_amount: u64,
+    tick_range: (i32, i32),
+    slippage_tolerance_bps: Option<u16>,
+    funder: Option<Pubkey>,
+) -> Result<ZapInPositionInstruction, Box<dyn Error>> {
+    let funder = funder.unwrap_or(*FUNDER.try_lock()?);
+    let slippage_tolerance_bps = slippage_tolerance_bps.unwrap_or(*SLIPPAGE_TOLERANCE_BPS.try_lock()?);
+    let tick_array_radius = *TICK_ARRAY_RADIUS.try_lock()?;
+    if funder == Pubkey::default() {
+        return Err("Funder must be provided".into());
+    }
+
+    let fusion_pool = FusionPool::from_bytes(&fetch_account(rpc, &pool_address).await?.data)?;
+    let input_is_a = if input_mint == fusion_pool.token_mint_a {
+        true
+    } else if input_mint == fusion_pool.token_mint_b {
+        false
+    } else {
+        return Err(format!("Mint {} is not a token of the pool {}", input_mint, pool_address).into());
+    };
+    let output_mint = if input_is_a {
+        fusion_pool.token_mint_b
+    } else {
+        fusion_pool.token_mint_a
+    };
+
+    let tick_range = order_tick_indexes(tick_range.0, tick_range.1);
+    let tick_lower_index = get_initializable_tick_index(tick_range.tick_lower_index, fusion_pool.tick_spacing, Some(false));
+    let tick_upper_index = get_initializable_tick_index(tick_range.tick_upper_index, fusion_pool.tick_spacing, Some(true));
+
+    let mint_infos = fetch_multiple_accounts(rpc, &[fusion_pool.token_mint_a, fusion_pool.token_mint_b]).await?;
+    let mint_a_info = mint_infos[0].as_ref().ok_or(format!("Mint a not found: {}", fusion_pool.token_mint_a))?;
+    let mint_b_info = mint_infos[1].as_ref().ok_or(format!("Mint b not found: {}", fusion_pool.token_mint_b))?;
+
+    let epoch = rpc.get_epoch_info().await?.epoch;
+    let conservative_transfer_fee = *CONSERVATIVE_TRANSFER_FEE.try_lock()?;
+    let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), epoch, conservative_transfer_fee);
+    let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), epoch, conservative_transfer_fee);
+
+    // The amount of the input token to swap is searched by bisection, so that after the swap the balances
+    // match the deposit ratio of the tick range at the new price, including the swap fee and price impact.
+    let ratio = position_ratio_x64(fusion_pool.sqrt_price, tick_lower_index, tick_upper_index);
+    let swap_ratio = if input_is_a { ratio.ratio_b } else { ratio.ratio_a };
+
+    let mut swap = None;
+    if swap_ratio > 0 {
+        let tick_arrays = fetch_tick_arrays_or_default(rpc, pool_address, &fusion_pool, tick_array_radius, input_is_a).await?;
+        let quote_swap = |amount: u64| {
+            swap_quote_by_input_token(
+                amount,
+                input_is_a,
+                slippage_tolerance_bps,
+                fusion_pool.clone().into(),
+                to_tick_arrays(&tick_arrays)?,
+                transfer_fee_a,
+                transfer_fee_b,
+            )
+            .map_err(|e| map_quote_error(e, tick_arrays.len()))
+        };
+        let needs_more_swap = |swap_quote: &ExactInSwapQuote| -> bool {
+            let (amount_a, amount_b) = if input_is_a {
+                (input_amount - swap_quote.token_in, swap_quote.token_est_out)
+            } else {
+                (swap_quote.token_est_out, input_amount - swap_quote.token_in)
+            };
+            let sqrt_price = swap_quote.next_sqrt_price;
+            match position_status(sqrt_price, tick_lower_index, tick_upper_index) {
+                PositionStatus::PriceBelowRange => !input_is_a,
+                PositionStatus::PriceAboveRange => input_is_a,
+                PositionStatus::PriceInRange => {
+                    let liquidity_a =
+                        increase_liquidity_quote_a(amount_a, 0, sqrt_price, tick_lower_index, tick_upper_index, transfer_fee_a, transfer_fee_b);
+                    let liquidity_b =
+                        increase_liquidity_quote_b(amount_b, 0, sqrt_price, tick_lower_index, tick_upper_index, transfer_fee_a, transfer_fee_b);
+                    match (liquidity_a, liquidity_b) {
+                        (Ok(a), Ok(b)) if input_is_a => a.liquidity_delta > b.liquidity_delta,
+                        (Ok(a), Ok(b)) => b.liquidity_delta > a.liquidity_delta,
+                        _ => false,
+                    }
+                }
+                PositionStatus::Invalid => false,
+            }
+        };
+
+        let swap_amount = if swap_ratio >= 1 << 64 {
+            input_amount
+        } else {
+            let (mut low, mut high) = (0, input_amount);
+            while high - low > 1 {
+                let mid = low + (high - low) / 2;
+                match quote_swap(mid) {
+                    Ok(swap_quote) if needs_more_swap(&swap_quote) => low = mid,
+                    _ => high = mid,
+                }
+            }
+            low
+        };
+
+        if swap_amount > 0 {
+            let swap_quote = quote_swap(swap_amount)?;
+            swap = Some((swap_amount, swap_quote, tick_arrays));
+        }
+    }
+
+    let (sqrt_price, amount_a, amount_b) = match &swap {
+        Some((_, swap_quote, _)) if input_is_a => (swap_quote.next_sqrt_price, input_amount - swap_quote.token_in, swap_quote.token_min_out),
+        Some((_, swap_quote, _)) => (swap_quote.next_sqrt_price, swap_quote.token_min_out, input_amount - swap_quote.token_in),
+        None if input_is_a => (fusion_pool.sqrt_price, input_amount, 0),
+        None => (fusion_pool.sqrt_price, 0, input_amount),
+    };
+
+    let liquidity_a = increase_liquidity_quote_a(amount_a, 0, sqrt_price, tick_lower_index, tick_upper_index, transfer_fee_a, transfer_fee_b)?;
+    let liquidity_b = increase_liquidity_quote_b(amount_b, 0, sqrt_price, tick_lower_index, tick_upper_index, transfer_fee_a, transfer_fee_b)?;
+    let liquidity = match position_status(sqrt_price, tick_lower_index, tick_upper_index) {
+        PositionStatus::PriceBelowRange => liquidity_a.liquidity_delta,
+        PositionStatus::PriceAboveRange => liquidity_b.liquidity_delta,
+        PositionStatus::PriceInRange => liquidity_a.liquidity_delta.min(liquidity_b.liquidity_delta),
+        PositionStatus::Invalid => return Err("Invalid tick range".into()),
+    };
+    if liquidity == 0 {
+        return Err("The input amount is too small to open a position".into());
+    }
+    let quote =
+        increase_liquidity_quote(liquidity, slippage_tolerance_bps, sqrt_price, tick_lower_index, tick_upper_index, transfer_fee_a, transfer_fee_b)?;
+
+    let token_accounts = prepare_token_accounts_instructions(
+        rpc,
+        funder,
+        vec![
+            TokenAccountStrategy::WithBalance(input_mint, input_amount),
+            TokenAccountStrategy::WithoutBalance(output_mint),
+        ],
+    )
+    .await?;
+    let token_owner_account_a = *token_accounts
+        .token_account_addresses
+        .get(&fusion_pool.token_mint_a)
+        .ok_or("Token A owner account not found")?;
+    let token_owner_account_b = *token_accounts
+        .token_account_addresses
+        .get(&fusion_pool.token_mint_b)
+        .ok_or("Token B owner account not found")?;
+
+    let mut instructions: Vec<Instruction> = Vec::new();
+    instructions.extend(token_accounts.create_instructions);
+
+    if let Some((swap_amount, swap_quote, tick_arrays)) = &swap {
+        instructions.push(
+            swap_instruction(
+                rpc,
+                pool_address,
+                &fusion_pool,
+                mint_a_info,
+                mint_b_info,
+                tick_arrays,
+                &SwapQuote::ExactIn(*swap_quote),
+                *swap_amount,
+                input_is_a,
+                token_owner_account_a,
+                token_owner_account_b,
+                funder,
+            )
+            .await?,
+        );
+    }
+
+    let (position_mint, open_position_instructions, initialization_cost) = open_position_with_token_accounts_instructions(
+        rpc,
+        pool_address,
+        &fusion_pool,
+        tick_lower_index,
+        tick_upper_index,
+        &quote,
+        mint_a_info,
+        mint_b_info,
+        token_owner_account_a,
+        token_owner_account_b,
+        funder,
+    )
+    .await?;
+    instructions.extend(open_position_instructions);
+    instructions.extend(token_accounts.cleanup_instructions);
+
+    let mut additional_signers = vec![position_mint];
+    additional_signers.extend(token_accounts.additional_signers);
+
+    Ok(ZapInPositionInstruction {
+        position_mint: additional_signers[0].pubkey(),
+        swap_quote: swap.map(|x| x.1),
+        quote,
+        instructions,
+        additional_signers,
+        initialization_cost,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::tests::{setup_ata_with_amount, setup_fusion_pool, setup_mint_with_decimals, setup_position, setup_position_bundle, RpcContext};
+    use crate::{increase_liquidity_instructions, IncreaseLiquidityParam};
     use serial_test::serial;
+    use solana_keypair::Keypair;
     use solana_program_test::tokio;
     use solana_signer::Signer;
     use std::error::Error;
@@ -364,4 +773,190 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_fetch_position_metadata() -> Result<(), Box<dyn Error>> {
+        let ctx = RpcContext::new().await;
+        let mint_a = setup_mint_with_decimals(&ctx, 9).await?;
+        let mint_b = setup_mint_with_decimals(&ctx, 9).await?;
+        let fusion_pool = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 300).await?;
+
+        let position_mint = setup_position(&ctx, fusion_pool, None, None).await?;
+        let metadata = fetch_position_metadata(&ctx.rpc, position_mint)
+            .await?
+            .expect("Position mint has no metadata");
+        assert_eq!(metadata.mint, position_mint);
+        assert!(!metadata.name.is_empty());
+        assert!(!metadata.symbol.is_empty());
+        assert!(!metadata.uri.is_empty());
+
+        // A regular token mint has no metadata extension.
+        assert!(fetch_position_metadata(&ctx.rpc, mint_a).await?.is_none());
+
+        Ok(())
+    }
+
+    async fn get_position_token_amount(ctx: &RpcContext, owner: Pubkey, position_mint: Pubkey) -> Result<u64, Box<dyn Error>> {
+        let address = get_associated_token_address_with_program_id(&owner, &position_mint, &spl_token_2022::ID);
+        let account = ctx.rpc.get_account(&address).await?;
+        Ok(StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)?.base.amount)
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_transfer_position() -> Result<(), Box<dyn Error>> {
+        let ctx = RpcContext::new().await;
+        let mint_a = setup_mint_with_decimals(&ctx, 9).await?;
+        let mint_b = setup_mint_with_decimals(&ctx, 9).await?;
+        let fusion_pool = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 300).await?;
+        let position_mint = setup_position(&ctx, fusion_pool, None, None).await?;
+
+        let from = ctx.signer.pubkey();
+        let to = Keypair::new().pubkey();
+        let instructions = transfer_position_instructions(&ctx.rpc, position_mint, from, to).await?;
+        ctx.send_transaction(instructions).await?;
+
+        assert_eq!(get_position_token_amount(&ctx, to, position_mint).await?, 1);
+        assert_eq!(get_position_token_amount(&ctx, from, position_mint).await?, 0);
+
+        // The previous owner no longer holds the position NFT.
+        assert!(transfer_position_instructions(&ctx.rpc, position_mint, from, to).await.is_err());
+
+        Ok(())
+    }
+
+    async fn get_token_balance(ctx: &RpcContext, address: Pubkey) -> Result<u64, Box<dyn Error>> {
+        let account = ctx.rpc.get_account(&address).await?;
+        Ok(StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)?.base.amount)
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_zap_in_position() -> Result<(), Box<dyn Error>> {
+        let ctx = RpcContext::new().await;
+        let mint_a = setup_mint_with_decimals(&ctx, 9).await?;
+        let mint_b = setup_mint_with_decimals(&ctx, 9).await?;
+        let (mint_a, mint_b) = if mint_a < mint_b { (mint_a, mint_b) } else { (mint_b, mint_a) };
+        let ata_a = setup_ata_with_amount(&ctx, mint_a, 10_000_000_000).await?;
+        let ata_b = setup_ata_with_amount(&ctx, mint_b, 10_000_000_000).await?;
+        let fusion_pool = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 300).await?;
+
+        let liquidity_position_mint = setup_position(&ctx, fusion_pool, Some((-1920, 1920)), None).await?;
+        let liquidity_ix = increase_liquidity_instructions(
+            &ctx.rpc,
+            liquidity_position_mint,
+            IncreaseLiquidityParam::TokenA(5_000_000_000),
+            Some(100),
+            Some(ctx.signer.pubkey()),
+        )
+        .await?;
+        ctx.send_transaction_with_signers(liquidity_ix.instructions, liquidity_ix.additional_signers.iter().collect())
+            .await?;
+
+        let before_a = get_token_balance(&ctx, ata_a).await?;
+        let before_b = get_token_balance(&ctx, ata_b).await?;
+
+        let input_amount = 100_000_000;
+        let zap_ix =
+            zap_in_position_instructions(&ctx.rpc, fusion_pool, mint_a, input_amount, (-640, 1280), Some(0), Some(ctx.signer.pubkey())).await?;
+        let swap_quote = zap_ix.swap_quote.expect("The zap should swap part of the input");
+        ctx.send_transaction_with_signers(zap_ix.instructions, zap_ix.additional_signers.iter().collect())
+            .await?;
+
+        let position_address = get_position_address(&zap_ix.position_mint)?.0;
+        let position = Position::from_bytes(&ctx.rpc.get_account(&position_address).await?.data)?;
+        assert_eq!(position.liquidity, zap_ix.quote.liquidity_delta);
+        assert_eq!((position.tick_lower_index, position.tick_upper_index), (-640, 1280));
+
+        // Token A pays for the swap and the deposit, while the swap output of token B is deposited.
+        let spent_a = before_a - get_token_balance(&ctx, ata_a).await?;
+        let left_b = get_token_balance(&ctx, ata_b).await? - before_b;
+        assert!(spent_a <= input_amount);
+        let deposited_a = spent_a - swap_quote.token_in;
+        let deposited_b = swap_quote.token_est_out - left_b;
+
+        // The deposit leaves little of the input unused.
+        assert!(input_amount - spent_a < input_amount / 1000);
+        assert!(
+            left_b < swap_quote.token_est_out / 1000,
+            "left_a={} left_b={} out={}",
+            input_amount - spent_a,
+            left_b,
+            swap_quote.token_est_out
+        );
+
+        // The value share of token A in the deposit matches the ratio of the range at the pool price after the swap.
+        let pool = FusionPool::from_bytes(&ctx.rpc.get_account(&fusion_pool).await?.data)?;
+        let price = (pool.sqrt_price as f64 / (1u128 << 64) as f64).powi(2);
+        let value_a = deposited_a as f64 * price;
+        let share_a = value_a / (value_a + deposited_b as f64);
+        let ratio = position_ratio_x64(pool.sqrt_price, -640, 1280);
+        let target_share_a = ratio.ratio_a as f64 / (1u128 << 64) as f64;
+        assert!((share_a - target_share_a).abs() < 0.001, "share_a={} target={}", share_a, target_share_a);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_zap_in_position_without_swap() -> Result<(), Box<dyn Error>> {
+        let ctx = RpcContext::new().await;
+        let mint_a = setup_mint_with_decimals(&ctx, 9).await?;
+        let mint_b = setup_mint_with_decimals(&ctx, 9).await?;
+        let (mint_a, mint_b) = if mint_a < mint_b { (mint_a, mint_b) } else { (mint_b, mint_a) };
+        setup_ata_with_amount(&ctx, mint_a, 1_000_000_000).await?;
+        let fusion_pool = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 300).await?;
+
+        // Above the current price, the position only takes token A.
+        let zap_ix = zap_in_position_instructions(&ctx.rpc, fusion_pool, mint_a, 1_000_000, (128, 640), None, Some(ctx.signer.pubkey())).await?;
+        assert!(zap_ix.swap_quote.is_none());
+        assert_eq!(zap_ix.quote.token_est_b, 0);
+        ctx.send_transaction_with_signers(zap_ix.instructions, zap_ix.additional_signers.iter().collect())
+            .await?;
+
+        let position_address = get_position_address(&zap_ix.position_mint)?.0;
+        let position = Position::from_bytes(&ctx.rpc.get_account(&position_address).await?.data)?;
+        assert_eq!(position.liquidity, zap_ix.quote.liquidity_delta);
+
+        // The input mint must be a token of the pool.
+        let result =
+            zap_in_position_instructions(&ctx.rpc, fusion_pool, Pubkey::new_unique(), 1_000_000, (128, 640), None, Some(ctx.signer.pubkey())).await;
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_liquidity_histogram() -> Result<(), Box<dyn Error>> {
+        let ctx = RpcContext::new().await;
+        let mint_a = setup_mint_with_decimals(&ctx, 9).await?;
+        let mint_b = setup_mint_with_decimals(&ctx, 9).await?;
+        setup_ata_with_amount(&ctx, mint_a, 1_000_000_000).await?;
+        setup_ata_with_amount(&ctx, mint_b, 1_000_000_000).await?;
+        let fusion_pool = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 300).await?;
+
+        for (tick_range, liquidity) in [((-192, 192), 1_000_000), ((0, 384), 2_000_000), ((-384, -192), 4_000_000)] {
+            let position_mint = setup_position(&ctx, fusion_pool, Some(tick_range), None).await?;
+            let liquidity_ix = increase_liquidity_instructions(
+                &ctx.rpc,
+                position_mint,
+                IncreaseLiquidityParam::Liquidity(liquidity),
+                Some(100),
+                Some(ctx.signer.pubkey()),
+            )
+            .await?;
+            ctx.send_transaction_with_signers(liquidity_ix.instructions, liquidity_ix.additional_signers.iter().collect())
+                .await?;
+        }
+
+        let positions = fetch_positions_in_fusion_pool(&ctx.rpc, fusion_pool).await?;
+        assert_eq!(positions.len(), 3);
+
+        let histogram = get_liquidity_histogram(&positions);
+        assert_eq!(histogram, vec![(-384, 4_000_000), (-192, 1_000_000), (0, 3_000_000), (192, 2_000_000), (384, 0)]);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,252 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use fusionamm_client::{
+    get_bundled_position_address, get_position_bundle_address, FusionPool, InitializePositionBundle, OpenBundledPosition,
+    OpenBundledPositionInstructionArgs, PositionBundle,
+};
+use fusionamm_core::{get_initializable_tick_index, order_tick_indexes, POSITION_BUNDLE_SIZE};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_instruction::Instruction;
+use solana_keypair::Keypair;
+use solana_program::rent::Rent;
+use solana_program::sysvar::SysvarId;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use std::error::Error;
+
+use crate::{fetch_account, FUNDER};
+
+/// Represents the instructions and addresses for creating a position bundle.
+#[derive(Debug)]
+pub struct OpenPositionBundleInstruction {
+    /// The public key of the position bundle NFT mint.
+    pub position_bundle_mint: Pubkey,
+
+    /// The address of the position bundle account.
+    pub position_bundle_address: Pubkey,
+
+    /// A vector of `Instruction` objects required to create the position bundle.
+    pub instructions: Vec<Instruction>,
+
+    /// A vector of `Keypair` objects representing additional signers required for the instructions.
+    pub additional_signers: Vec<Keypair>,
+}
+
+/// Represents the instructions and addresses for opening positions in a position bundle.
+#[derive(Debug)]
+pub struct OpenBundledPositionsInstruction {
+    /// The addresses of the opened bundled positions, in the order of the requested tick ranges.
+    pub bundled_positions: Vec<Pubkey>,
+
+    /// The bundle indexes of the opened bundled positions, in the order of the requested tick ranges.
+    pub bundle_indexes: Vec<u16>,
+
+    /// A vector of `Instruction` objects required to open the bundled positions.
+    pub instructions: Vec<Instruction>,
+}
+
+/// Creates a position bundle, which can hold up to 256 positions represented by a single NFT.
+///
+/// The position bundle NFT is minted to the associated token account of the funder, which is created by the program.
+///
+/// # Arguments
+///
+/// * `funder` - An optional public key of the funder and owner of the position bundle. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing `OpenPositionBundleInstruction` on success:
+///
+/// * `position_bundle_mint` - The mint address of the position bundle NFT.
+/// * `position_bundle_address` - The address of the position bundle account.
+/// * `instructions` - A vector of `Instruction` objects required to create the position bundle.
+/// * `additional_signers` - A vector of `Keypair` objects representing additional signers required for the instructions.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The funder account is invalid.
+pub fn open_position_bundle_instructions(funder: Option<Pubkey>) -> Result<OpenPositionBundleInstruction, Box<dyn Error>> {
+    let funder = funder.unwrap_or(*FUNDER.try_lock()?);
+    if funder == Pubkey::default() {
+        return Err("Funder must be provided".into());
+    }
+
+    let position_bundle_mint = Keypair::new();
+    let position_bundle_address = get_position_bundle_address(&position_bundle_mint.pubkey())?.0;
+    let position_bundle_token_account = get_associated_token_address_with_program_id(&funder, &position_bundle_mint.pubkey(), &spl_token::ID);
+
+    let instruction = InitializePositionBundle {
+        position_bundle: position_bundle_address,
+        position_bundle_mint: position_bundle_mint.pubkey(),
+        position_bundle_token_account,
+        position_bundle_owner: funder,
+        funder,
+        token_program: spl_token::ID,
+        system_program: solana_program::system_program::id(),
+        rent: Rent::id(),
+        associated_token_program: spl_associated_token_account::ID,
+    }
+    .instruction();
+
+    Ok(OpenPositionBundleInstruction {
+        position_bundle_mint: position_bundle_mint.pubkey(),
+        position_bundle_address,
+        instructions: vec![instruction],
+        additional_signers: vec![position_bundle_mint],
+    })
+}
+
+/// Opens empty positions in a position bundle, one for each of the given tick ranges.
+///
+/// The positions take the lowest free bundle indexes. The tick indexes are rounded outwards to the nearest
+/// initializable tick indexes of the pool. Liquidity can be added with `increase_liquidity_instructions`.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `position_bundle_mint` - The mint address of the position bundle NFT.
+/// * `pool_address` - The public key of the fusion pool.
+/// * `tick_ranges` - The lower and upper tick indexes of the positions to open.
+/// * `funder` - An optional public key of the funder and owner of the position bundle. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing `OpenBundledPositionsInstruction` on success:
+///
+/// * `bundled_positions` - The addresses of the opened bundled positions.
+/// * `bundle_indexes` - The bundle indexes of the opened bundled positions.
+/// * `instructions` - A vector of `Instruction` objects required to open the bundled positions.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The funder account is invalid.
+/// - The position bundle or the pool accounts cannot be fetched.
+/// - The position bundle doesn't have enough free bundle indexes.
+pub async fn open_bundled_position_instructions(
+    rpc: &RpcClient,
+    position_bundle_mint: Pubkey,
+    pool_address: Pubkey,
+    tick_ranges: &[(i32, i32)],
+    funder: Option<Pubkey>,
+) -> Result<OpenBundledPositionsInstruction, Box<dyn Error>> {
+    let funder = funder.unwrap_or(*FUNDER.try_lock()?);
+    if funder == Pubkey::default() {
+        return Err("Funder must be provided".into());
+    }
+
+    let position_bundle_address = get_position_bundle_address(&position_bundle_mint)?.0;
+    let position_bundle = PositionBundle::from_bytes(&fetch_account(rpc, &position_bundle_address).await?.data)?;
+    let fusion_pool = FusionPool::from_bytes(&fetch_account(rpc, &pool_address).await?.data)?;
+    let position_bundle_token_account = get_associated_token_address_with_program_id(&funder, &position_bundle_mint, &spl_token::ID);
+
+    let free_bundle_indexes: Vec<u16> = (0..POSITION_BUNDLE_SIZE)
+        .filter(|i| position_bundle.position_bitmap[i / 8] & (1 << (i % 8)) == 0)
+        .map(|i| i as u16)
+        .take(tick_ranges.len())
+        .collect();
+    if free_bundle_indexes.len() < tick_ranges.len() {
+        return Err(format!("Position bundle {} has only {} free positions", position_bundle_address, free_bundle_indexes.len()).into());
+    }
+
+    let mut bundled_positions: Vec<Pubkey> = Vec::new();
+    let mut instructions: Vec<Instruction> = Vec::new();
+
+    for (&(lower_tick_index, upper_tick_index), &bundle_index) in tick_ranges.iter().zip(free_bundle_indexes.iter()) {
+        let tick_range = order_tick_indexes(lower_tick_index, upper_tick_index);
+        let bundled_position_address = get_bundled_position_address(&position_bundle_mint, bundle_index as u8)?.0;
+
+        instructions.push(
+            OpenBundledPosition {
+                bundled_position: bundled_position_address,
+                position_bundle: position_bundle_address,
+                position_bundle_token_account,
+                position_bundle_authority: funder,
+                fusion_pool: pool_address,
+                funder,
+                system_program: solana_program::system_program::id(),
+                rent: Rent::id(),
+            }
+            .instruction(OpenBundledPositionInstructionArgs {
+                bundle_index,
+                tick_lower_index: get_initializable_tick_index(tick_range.tick_lower_index, fusion_pool.tick_spacing, Some(false)),
+                tick_upper_index: get_initializable_tick_index(tick_range.tick_upper_index, fusion_pool.tick_spacing, Some(true)),
+            }),
+        );
+        bundled_positions.push(bundled_position_address);
+    }
+
+    Ok(OpenBundledPositionsInstruction {
+        bundled_positions,
+        bundle_indexes: free_bundle_indexes,
+        instructions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use fusionamm_client::{get_position_bundle_address, Position, PositionBundle};
+    use serial_test::serial;
+    use solana_program_test::tokio;
+    use solana_signer::Signer;
+
+    use crate::{
+        open_bundled_position_instructions, open_position_bundle_instructions,
+        tests::{setup_fusion_pool, setup_mint_with_decimals, RpcContext},
+    };
+
+    #[tokio::test]
+    #[serial]
+    async fn test_open_position_bundle_with_two_positions() {
+        let ctx = RpcContext::new().await;
+
+        let mint_a = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+        let mint_b = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+        let (mint_a, mint_b) = if mint_a < mint_b { (mint_a, mint_b) } else { (mint_b, mint_a) };
+        let pool_address = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 3000).await.unwrap();
+
+        let bundle_ix = open_position_bundle_instructions(Some(ctx.signer.pubkey())).unwrap();
+        assert_eq!(bundle_ix.position_bundle_address, get_position_bundle_address(&bundle_ix.position_bundle_mint).unwrap().0);
+        ctx.send_transaction_with_signers(bundle_ix.instructions, bundle_ix.additional_signers.iter().collect())
+            .await
+            .unwrap();
+
+        let positions_ix = open_bundled_position_instructions(
+            &ctx.rpc,
+            bundle_ix.position_bundle_mint,
+            pool_address,
+            &[(-128, 128), (100, -300)],
+            Some(ctx.signer.pubkey()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(positions_ix.bundle_indexes, vec![0, 1]);
+        ctx.send_transaction(positions_ix.instructions).await.unwrap();
+
+        let position_bundle = PositionBundle::from_bytes(&ctx.rpc.get_account(&bundle_ix.position_bundle_address).await.unwrap().data).unwrap();
+        assert_eq!(position_bundle.position_bitmap[0], 0b11);
+
+        let position_0 = Position::from_bytes(&ctx.rpc.get_account(&positions_ix.bundled_positions[0]).await.unwrap().data).unwrap();
+        assert_eq!(position_0.fusion_pool, pool_address);
+        assert_eq!((position_0.tick_lower_index, position_0.tick_upper_index), (-128, 128));
+        assert_eq!(position_0.liquidity, 0);
+
+        let position_1 = Position::from_bytes(&ctx.rpc.get_account(&positions_ix.bundled_positions[1]).await.unwrap().data).unwrap();
+        assert_eq!(position_1.fusion_pool, pool_address);
+        assert_eq!((position_1.tick_lower_index, position_1.tick_upper_index), (-320, 128));
+
+        // The next positions take the following free bundle indexes.
+        let positions_ix =
+            open_bundled_position_instructions(&ctx.rpc, bundle_ix.position_bundle_mint, pool_address, &[(-64, 64)], Some(ctx.signer.pubkey()))
+                .await
+                .unwrap();
+        assert_eq!(positions_ix.bundle_indexes, vec![2]);
+    }
+}
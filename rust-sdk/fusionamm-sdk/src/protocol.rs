@@ -0,0 +1,439 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use crate::token::{
+    get_current_transfer_fee, get_transfer_hook_extra_accounts, prepare_token_accounts_instructions, RemainingAccounts, TokenAccountStrategy,
+};
+use crate::{fetch_multiple_accounts, CONSERVATIVE_TRANSFER_FEE};
+use fusionamm_client::{
+    get_fusion_pools_config_address, AccountsType, CollectProtocolFees, CollectProtocolFeesInstructionArgs, FusionPool, FusionPoolsConfig,
+    SetProtocolFeeRate, SetProtocolFeeRateInstructionArgs,
+};
+use fusionamm_core::{try_apply_transfer_fee, CollectFeesQuote, MAX_PROTOCOL_FEE_RATE};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_instruction::Instruction;
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use std::error::Error;
+
+/// Represents the instructions and quote for collecting the protocol fees of a pool.
+#[derive(Debug)]
+pub struct CollectProtocolFeesInstruction {
+    /// A vector of `Instruction` objects required to collect the protocol fees.
+    pub instructions: Vec<Instruction>,
+
+    /// A vector of `Keypair` objects representing additional signers required for the instructions.
+    pub additional_signers: Vec<Keypair>,
+
+    /// The amounts of protocol fees received by the authority, net of token transfer fees:
+    /// - `fee_owed_a` - The amount of protocol fees received in token A.
+    /// - `fee_owed_b` - The amount of protocol fees received in token B.
+    pub fees_quote: CollectFeesQuote,
+}
+
+/// Generates instructions to collect the protocol fees accumulated by a fusion pool.
+///
+/// The fees are transferred from the pool vaults to the token accounts of the config's
+/// collect protocol fees authority. Missing token accounts are created beforehand.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to a Solana RPC client for fetching accounts and pool data.
+/// * `pool_address` - The public key of the fusion pool.
+/// * `authority` - The public key of the collect protocol fees authority. It must match the pools config.
+///
+/// # Returns
+///
+/// A `Result` containing `CollectProtocolFeesInstruction` on success:
+///
+/// * `instructions` - A vector of `Instruction` objects required to collect the protocol fees.
+/// * `additional_signers` - A vector of `Keypair` objects representing additional signers required for the instructions.
+/// * `fees_quote` - The protocol fee amounts received by the authority after transfer fees.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The pool or the pools config accounts cannot be fetched.
+/// - The provided authority is not the config's collect protocol fees authority.
+pub async fn collect_protocol_fees_instructions(
+    rpc: &RpcClient,
+    pool_address: Pubkey,
+    authority: Pubkey,
+) -> Result<CollectProtocolFeesInstruction, Box<dyn Error>> {
+    let config_address = get_fusion_pools_config_address()?.0;
+    let account_infos = fetch_multiple_accounts(rpc, &[pool_address, config_address]).await?;
+
+    let pool_info = account_infos[0].as_ref().ok_or(format!("Fusion pool {} not found", pool_address))?;
+    let pool = FusionPool::from_bytes(&pool_info.data)?;
+
+    let config_info = account_infos[1].as_ref().ok_or("Fusion pools config not found")?;
+    let config = FusionPoolsConfig::from_bytes(&config_info.data)?;
+
+    if authority != config.collect_protocol_fees_authority {
+        return Err(format!("{} is not the collect protocol fees authority", authority).into());
+    }
+
+    let mint_infos = fetch_multiple_accounts(rpc, &[pool.token_mint_a, pool.token_mint_b]).await?;
+    let mint_a_info = mint_infos[0].as_ref().ok_or("Token A mint info not found")?;
+    let mint_b_info = mint_infos[1].as_ref().ok_or("Token B mint info not found")?;
+
+    let current_epoch = rpc.get_epoch_info().await?.epoch;
+
+    let conservative_transfer_fee = *CONSERVATIVE_TRANSFER_FEE.try_lock()?;
+    let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), current_epoch, conservative_transfer_fee);
+    let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), current_epoch, conservative_transfer_fee);
+
+    let fees_quote = CollectFeesQuote {
+        fee_owed_a: match transfer_fee_a {
+            Some(transfer_fee) => try_apply_transfer_fee(pool.protocol_fee_owed_a, transfer_fee)?,
+            None => pool.protocol_fee_owed_a,
+        },
+        fee_owed_b: match transfer_fee_b {
+            Some(transfer_fee) => try_apply_transfer_fee(pool.protocol_fee_owed_b, transfer_fee)?,
+            None => pool.protocol_fee_owed_b,
+        },
+    };
+
+    let token_accounts = prepare_token_accounts_instructions(
+        rpc,
+        authority,
+        vec![
+            TokenAccountStrategy::WithoutBalance(pool.token_mint_a),
+            TokenAccountStrategy::WithoutBalance(pool.token_mint_b),
+        ],
+    )
+    .await?;
+
+    let token_destination_a = token_accounts
+        .token_account_addresses
+        .get(&pool.token_mint_a)
+        .ok_or("Token A destination account not found")?;
+    let token_destination_b = token_accounts
+        .token_account_addresses
+        .get(&pool.token_mint_b)
+        .ok_or("Token B destination account not found")?;
+
+    let mut remaining_accounts = RemainingAccounts::default();
+    remaining_accounts.add(
+        AccountsType::TransferHookA,
+        get_transfer_hook_extra_accounts(
+            rpc,
+            pool.token_mint_a,
+            mint_a_info,
+            pool.token_vault_a,
+            *token_destination_a,
+            pool_address,
+            pool.protocol_fee_owed_a,
+        )
+        .await?,
+    );
+    remaining_accounts.add(
+        AccountsType::TransferHookB,
+        get_transfer_hook_extra_accounts(
+            rpc,
+            pool.token_mint_b,
+            mint_b_info,
+            pool.token_vault_b,
+            *token_destination_b,
+            pool_address,
+            pool.protocol_fee_owed_b,
+        )
+        .await?,
+    );
+
+    let mut instructions: Vec<Instruction> = Vec::new();
+    instructions.extend(token_accounts.create_instructions);
+    instructions.push(
+        CollectProtocolFees {
+            fusion_pools_config: config_address,
+            fusion_pool: pool_address,
+            collect_protocol_fees_authority: authority,
+            token_mint_a: pool.token_mint_a,
+            token_mint_b: pool.token_mint_b,
+            token_vault_a: pool.token_vault_a,
+            token_vault_b: pool.token_vault_b,
+            token_destination_a: *token_destination_a,
+            token_destination_b: *token_destination_b,
+            token_program_a: mint_a_info.owner,
+            token_program_b: mint_b_info.owner,
+            memo_program: spl_memo::ID,
+        }
+        .instruction_with_remaining_accounts(
+            CollectProtocolFeesInstructionArgs {
+                remaining_accounts_info: remaining_accounts.info(),
+            },
+            &remaining_accounts.accounts,
+        ),
+    );
+    instructions.extend(token_accounts.cleanup_instructions);
+
+    Ok(CollectProtocolFeesInstruction {
+        instructions,
+        additional_signers: token_accounts.additional_signers,
+        fees_quote,
+    })
+}
+
+/// Generates the instruction to update the protocol fee rate of a fusion pool.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `pool_address` - The public key of the fusion pool.
+/// * `new_protocol_fee_rate` - The new protocol fee rate, denominated in basis points of the swap fee.
+/// * `authority` - The public key of the fee authority. It must match the pools config.
+///
+/// # Returns
+///
+/// A `Result` containing the instructions to update the protocol fee rate.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The pool or the pools config accounts cannot be fetched.
+/// - The provided authority is not the config's fee authority.
+/// - The new protocol fee rate exceeds `MAX_PROTOCOL_FEE_RATE`.
+pub async fn set_protocol_fee_rate_instructions(
+    rpc: &RpcClient,
+    pool_address: Pubkey,
+    new_protocol_fee_rate: u16,
+    authority: Pubkey,
+) -> Result<Vec<Instruction>, Box<dyn Error>> {
+    if new_protocol_fee_rate > MAX_PROTOCOL_FEE_RATE {
+        return Err(format!("Protocol fee rate {} exceeds the maximum of {}", new_protocol_fee_rate, MAX_PROTOCOL_FEE_RATE).into());
+    }
+
+    let config_address = get_fusion_pools_config_address()?.0;
+    let account_infos = fetch_multiple_accounts(rpc, &[pool_address, config_address]).await?;
+
+    let pool_info = account_infos[0].as_ref().ok_or(format!("Fusion pool {} not found", pool_address))?;
+    FusionPool::from_bytes(&pool_info.data)?;
+
+    let config_info = account_infos[1].as_ref().ok_or("Fusion pools config not found")?;
+    let config = FusionPoolsConfig::from_bytes(&config_info.data)?;
+
+    if authority != config.fee_authority {
+        return Err(format!("{} is not the fee authority", authority).into());
+    }
+
+    Ok(vec![SetProtocolFeeRate {
+        fusion_pools_config: config_address,
+        fusion_pool: pool_address,
+        fee_authority: authority,
+    }
+    .instruction(SetProtocolFeeRateInstructionArgs {
+        protocol_fee_rate: new_protocol_fee_rate,
+    })])
+}
+
+#[cfg(test)]
+mod tests {
+    use fusionamm_client::{
+        decode_instruction, get_fusion_pools_config_address, get_token_badge_address, AccountsType, DecodedFusionInstruction, FusionPool,
+        InitializeTokenBadge, RemainingAccountsInfo, RemainingAccountsSlice, SetProtocolFeeRate, SetProtocolFeeRateInstructionArgs,
+    };
+    use serial_test::serial;
+    use solana_instruction::AccountMeta;
+    use solana_keypair::Keypair;
+    use solana_program::program_pack::Pack;
+    use solana_program_test::tokio;
+    use solana_pubkey::Pubkey;
+    use solana_signer::Signer;
+    use spl_token::state::Account as TokenAccount;
+    use spl_token_2022::extension::ExtensionType;
+    use spl_transfer_hook_interface::get_extra_account_metas_address;
+    use std::error::Error;
+
+    use crate::{
+        collect_protocol_fees_instructions, increase_liquidity_instructions, order_mints, set_protocol_fee_rate_instructions, swap_instructions,
+        tests::{
+            setup_ata_with_amount, setup_fusion_pool, setup_mint_te, setup_mint_with_decimals, setup_position,
+            setup_transfer_hook_validation_account, RpcContext, TEST_TRANSFER_HOOK_PROGRAM_ID,
+        },
+        IncreaseLiquidityParam, SwapType,
+    };
+
+    async fn get_token_balance(ctx: &RpcContext, address: Pubkey) -> Result<u64, Box<dyn Error>> {
+        let account = ctx.rpc.get_account(&address).await?;
+        Ok(TokenAccount::unpack(&account.data)?.amount)
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_collect_protocol_fees() {
+        let ctx = RpcContext::new().await;
+
+        let mint_a = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+        let mint_b = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+        let (mint_a, mint_b) = if mint_a < mint_b { (mint_a, mint_b) } else { (mint_b, mint_a) };
+        let ata_a = setup_ata_with_amount(&ctx, mint_a, 1_000_000_000).await.unwrap();
+        let ata_b = setup_ata_with_amount(&ctx, mint_b, 1_000_000_000).await.unwrap();
+
+        let pool_address = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 3000).await.unwrap();
+        ctx.send_transaction(vec![SetProtocolFeeRate {
+            fusion_pools_config: get_fusion_pools_config_address().unwrap().0,
+            fusion_pool: pool_address,
+            fee_authority: ctx.signer.pubkey(),
+        }
+        .instruction(SetProtocolFeeRateInstructionArgs { protocol_fee_rate: 2500 })])
+            .await
+            .unwrap();
+
+        let position_mint = setup_position(&ctx, pool_address, Some((-192, 192)), None).await.unwrap();
+        let liquidity_ix = increase_liquidity_instructions(
+            &ctx.rpc,
+            position_mint,
+            IncreaseLiquidityParam::Liquidity(10_000_000),
+            Some(100),
+            Some(ctx.signer.pubkey()),
+        )
+        .await
+        .unwrap();
+        ctx.send_transaction_with_signers(liquidity_ix.instructions, liquidity_ix.additional_signers.iter().collect())
+            .await
+            .unwrap();
+
+        let swap_ix = swap_instructions(&ctx.rpc, pool_address, 10_000, mint_a, SwapType::ExactIn, Some(100), Some(ctx.signer.pubkey()))
+            .await
+            .unwrap();
+        ctx.send_transaction_with_signers(swap_ix.instructions, swap_ix.additional_signers.iter().collect())
+            .await
+            .unwrap();
+
+        let pool = FusionPool::from_bytes(&ctx.rpc.get_account(&pool_address).await.unwrap().data).unwrap();
+        assert!(pool.protocol_fee_owed_a > 0);
+
+        let before_a = get_token_balance(&ctx, ata_a).await.unwrap();
+        let before_b = get_token_balance(&ctx, ata_b).await.unwrap();
+
+        let collect_ix = collect_protocol_fees_instructions(&ctx.rpc, pool_address, ctx.signer.pubkey())
+            .await
+            .unwrap();
+        assert_eq!(collect_ix.fees_quote.fee_owed_a, pool.protocol_fee_owed_a);
+        assert_eq!(collect_ix.fees_quote.fee_owed_b, pool.protocol_fee_owed_b);
+        ctx.send_transaction_with_signers(collect_ix.instructions, collect_ix.additional_signers.iter().collect())
+            .await
+            .unwrap();
+
+        assert_eq!(get_token_balance(&ctx, ata_a).await.unwrap(), before_a + pool.protocol_fee_owed_a);
+        assert_eq!(get_token_balance(&ctx, ata_b).await.unwrap(), before_b + pool.protocol_fee_owed_b);
+
+        let pool = FusionPool::from_bytes(&ctx.rpc.get_account(&pool_address).await.unwrap().data).unwrap();
+        assert_eq!(pool.protocol_fee_owed_a, 0);
+        assert_eq!(pool.protocol_fee_owed_b, 0);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_collect_protocol_fees_with_transfer_hook() -> Result<(), Box<dyn Error>> {
+        let ctx = RpcContext::new().await;
+
+        let mint_hook = setup_mint_te(&ctx, &[ExtensionType::TransferHook]).await?;
+        let mint_other = setup_mint_with_decimals(&ctx, 6).await?;
+        let extra_account = setup_transfer_hook_validation_account(&ctx, mint_hook).await?;
+
+        // Mints with a transfer hook require a token badge.
+        ctx.send_transaction(vec![InitializeTokenBadge {
+            fusion_pools_config: get_fusion_pools_config_address()?.0,
+            token_badge_authority: ctx.signer.pubkey(),
+            token_mint: mint_hook,
+            token_badge: get_token_badge_address(&mint_hook)?.0,
+            funder: ctx.signer.pubkey(),
+            system_program: solana_program::system_program::id(),
+        }
+        .instruction()])
+            .await?;
+
+        let [mint_a, mint_b] = order_mints(mint_hook, mint_other)?;
+        let pool_address = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 300).await?;
+
+        // The test hook program can't execute transfers, so the owed protocol fees are written directly to the pool.
+        let mut pool_account = ctx.rpc.get_account(&pool_address).await?;
+        let protocol_fee_owed_a_offset = 8 + 1 + 2 + 32 * 4 + 2 * 6 + 16 + 16 + 4;
+        let protocol_fee_owed_b_offset = protocol_fee_owed_a_offset + 8;
+        pool_account.data[protocol_fee_owed_a_offset..protocol_fee_owed_a_offset + 8].copy_from_slice(&1_000u64.to_le_bytes());
+        pool_account.data[protocol_fee_owed_b_offset..protocol_fee_owed_b_offset + 8].copy_from_slice(&2_000u64.to_le_bytes());
+        ctx.set_account(pool_address, pool_account).await;
+        let pool = FusionPool::from_bytes(&ctx.rpc.get_account(&pool_address).await?.data)?;
+        assert_eq!((pool.protocol_fee_owed_a, pool.protocol_fee_owed_b), (1_000, 2_000));
+
+        let collect_ix = collect_protocol_fees_instructions(&ctx.rpc, pool_address, ctx.signer.pubkey()).await?;
+        let collect_ix = collect_ix.instructions.iter().find(|ix| {
+            let accounts: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+            matches!(decode_instruction(&ix.data, &accounts), Some(DecodedFusionInstruction::CollectProtocolFees { .. }))
+        });
+        let collect_ix = collect_ix.ok_or("CollectProtocolFees instruction not found")?;
+        let accounts: Vec<Pubkey> = collect_ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        let Some(DecodedFusionInstruction::CollectProtocolFees { args, .. }) = decode_instruction(&collect_ix.data, &accounts) else {
+            unreachable!();
+        };
+
+        let accounts_type = if mint_a == mint_hook {
+            AccountsType::TransferHookA
+        } else {
+            AccountsType::TransferHookB
+        };
+        assert_eq!(
+            args.remaining_accounts_info,
+            Some(RemainingAccountsInfo {
+                slices: vec![RemainingAccountsSlice { accounts_type, length: 3 }],
+            })
+        );
+
+        // The extra accounts are appended after the accounts of the instruction.
+        let validation_address = get_extra_account_metas_address(&mint_hook, &TEST_TRANSFER_HOOK_PROGRAM_ID);
+        assert_eq!(
+            collect_ix.accounts[collect_ix.accounts.len() - 3..],
+            [
+                AccountMeta::new(extra_account, false),
+                AccountMeta::new_readonly(TEST_TRANSFER_HOOK_PROGRAM_ID, false),
+                AccountMeta::new_readonly(validation_address, false),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_collect_protocol_fees_wrong_authority() {
+        let ctx = RpcContext::new().await;
+
+        let mint_a = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+        let mint_b = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+        let (mint_a, mint_b) = if mint_a < mint_b { (mint_a, mint_b) } else { (mint_b, mint_a) };
+        let pool_address = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 3000).await.unwrap();
+
+        let result = collect_protocol_fees_instructions(&ctx.rpc, pool_address, Keypair::new().pubkey()).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_set_protocol_fee_rate() {
+        let ctx = RpcContext::new().await;
+
+        let mint_a = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+        let mint_b = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+        let (mint_a, mint_b) = if mint_a < mint_b { (mint_a, mint_b) } else { (mint_b, mint_a) };
+        let pool_address = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 3000).await.unwrap();
+
+        let instructions = set_protocol_fee_rate_instructions(&ctx.rpc, pool_address, 2000, ctx.signer.pubkey())
+            .await
+            .unwrap();
+        ctx.send_transaction(instructions).await.unwrap();
+
+        let pool = FusionPool::from_bytes(&ctx.rpc.get_account(&pool_address).await.unwrap().data).unwrap();
+        assert_eq!(pool.protocol_fee_rate, 2000);
+
+        let result = set_protocol_fee_rate_instructions(&ctx.rpc, pool_address, 2501, ctx.signer.pubkey()).await;
+        assert!(result.unwrap_err().to_string().contains("exceeds the maximum"));
+
+        let result = set_protocol_fee_rate_instructions(&ctx.rpc, pool_address, 2000, Keypair::new().pubkey()).await;
+        assert!(result.unwrap_err().to_string().contains("is not the fee authority"));
+    }
+}
@@ -9,22 +9,23 @@
 //
 
 use crate::{
-    token::{get_current_transfer_fee, prepare_token_accounts_instructions, TokenAccountStrategy},
-    FUNDER, SLIPPAGE_TOLERANCE_BPS,
-};
-use fusionamm_client::{
-    get_tick_array_address, AccountsType, FusionPool, RemainingAccountsInfo, RemainingAccountsSlice, Swap, SwapInstructionArgs, TickArray,
+    fetch_account, fetch_multiple_accounts,
+    token::{
+        get_current_transfer_fee, get_transfer_hook_extra_accounts, prepare_token_accounts_instructions, RemainingAccounts, TokenAccountStrategy,
+    },
+    CONSERVATIVE_TRANSFER_FEE, FUNDER, SLIPPAGE_TOLERANCE_BPS, TICK_ARRAY_RADIUS,
 };
+use fusionamm_client::{get_tick_array_address, AccountsType, FusionPool, Swap, SwapInstructionArgs, TickArray};
 use fusionamm_core::{
-    get_tick_array_start_tick_index, swap_quote_by_input_token, swap_quote_by_output_token, ExactInSwapQuote, ExactOutSwapQuote, TickArrayFacade,
-    TickFacade, TICK_ARRAY_SIZE,
+    get_tick_array_start_tick_index, swap_quote_by_input_token, swap_quote_by_output_token, CoreError, ExactInSwapQuote, ExactOutSwapQuote,
+    FusionPoolFacade, TickArrayFacade, TickArrays, TickFacade, TransferFee, INVALID_TICK_ARRAY_SEQUENCE, MAX_SWAP_TICK_ARRAYS, TICK_ARRAY_SIZE,
 };
+use solana_account::Account;
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_instruction::{AccountMeta, Instruction};
 use solana_keypair::Keypair;
 use solana_pubkey::Pubkey;
 use std::{error::Error, iter::zip};
-// TODO: transfer hooks
 
 /// Represents the type of a swap operation.
 ///
@@ -73,28 +74,29 @@ fn uninitialized_tick_array(start_tick_index: i32) -> TickArrayFacade {
     }
 }
 
-async fn fetch_tick_arrays_or_default(
+pub(crate) async fn fetch_tick_arrays_or_default(
     rpc: &RpcClient,
     fusion_pool_address: Pubkey,
     fusion_pool: &FusionPool,
-) -> Result<[(Pubkey, TickArrayFacade); 5], Box<dyn Error>> {
+    tick_array_radius: u16,
+    a_to_b: bool,
+) -> Result<Vec<(Pubkey, TickArrayFacade)>, Box<dyn Error>> {
     let tick_array_start_index = get_tick_array_start_tick_index(fusion_pool.tick_current_index, fusion_pool.tick_spacing);
     let offset = fusion_pool.tick_spacing as i32 * TICK_ARRAY_SIZE as i32;
+    let direction_offset = if a_to_b { -offset } else { offset };
 
-    let tick_array_indexes = [
-        tick_array_start_index,
-        tick_array_start_index + offset,
-        tick_array_start_index + offset * 2,
-        tick_array_start_index - offset,
-        tick_array_start_index - offset * 2,
-    ];
+    // The tick arrays in the swap direction come first, so they are kept when the list is truncated.
+    let mut tick_array_indexes = vec![tick_array_start_index];
+    tick_array_indexes.extend((1..=tick_array_radius as i32).map(|i| tick_array_start_index + direction_offset * i));
+    tick_array_indexes.extend((1..=tick_array_radius as i32).map(|i| tick_array_start_index - direction_offset * i));
+    tick_array_indexes.truncate(MAX_SWAP_TICK_ARRAYS);
 
     let tick_array_addresses: Vec<Pubkey> = tick_array_indexes
         .iter()
         .map(|&x| get_tick_array_address(&fusion_pool_address, x).map(|y| y.0))
         .collect::<Result<Vec<Pubkey>, _>>()?;
 
-    let tick_array_infos = rpc.get_multiple_accounts(&tick_array_addresses).await?;
+    let tick_array_infos = fetch_multiple_accounts(rpc, &tick_array_addresses).await?;
 
     let maybe_tick_arrays: Vec<Option<TickArrayFacade>> = tick_array_infos
         .iter()
@@ -108,12 +110,243 @@ async fn fetch_tick_arrays_or_default(
         .map(|(i, x)| x.unwrap_or(uninitialized_tick_array(tick_array_indexes[i])))
         .collect::<Vec<TickArrayFacade>>();
 
-    let result: [(Pubkey, TickArrayFacade); 5] = zip(tick_array_addresses, tick_arrays)
-        .collect::<Vec<(Pubkey, TickArrayFacade)>>()
-        .try_into()
-        .map_err(|_| "Failed to convert tick arrays to array".to_string())?;
+    Ok(zip(tick_array_addresses, tick_arrays).collect())
+}
+
+pub(crate) fn to_tick_arrays(tick_arrays: &[(Pubkey, TickArrayFacade)]) -> Result<TickArrays, Box<dyn Error>> {
+    let tick_arrays = match tick_arrays.iter().map(|x| x.1).collect::<Vec<_>>()[..] {
+        [a] => TickArrays::One(a),
+        [a, b] => TickArrays::Two(a, b),
+        [a, b, c] => TickArrays::Three(a, b, c),
+        [a, b, c, d] => TickArrays::Four(a, b, c, d),
+        [a, b, c, d, e] => TickArrays::Five(a, b, c, d, e),
+        [a, b, c, d, e, f] => TickArrays::Six(a, b, c, d, e, f),
+        _ => return Err("Failed to convert tick arrays to array".into()),
+    };
+    Ok(tick_arrays)
+}
+
+pub(crate) fn map_quote_error(error: CoreError, tick_array_count: usize) -> Box<dyn Error> {
+    if error == INVALID_TICK_ARRAY_SEQUENCE {
+        format!(
+            "Insufficient tick arrays: the swap moves the price beyond the {} tick arrays fetched around the current price, increase the tick array radius",
+            tick_array_count
+        )
+        .into()
+    } else {
+        error.into()
+    }
+}
+
+/// Builds the swap instruction for a quote, given the token accounts of the signer.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn swap_instruction(
+    rpc: &RpcClient,
+    fusion_pool_address: Pubkey,
+    fusion_pool: &FusionPool,
+    mint_a_info: &Account,
+    mint_b_info: &Account,
+    tick_arrays: &[(Pubkey, TickArrayFacade)],
+    quote: &SwapQuote,
+    amount: u64,
+    a_to_b: bool,
+    token_owner_account_a: Pubkey,
+    token_owner_account_b: Pubkey,
+    signer: Pubkey,
+) -> Result<Instruction, Box<dyn Error>> {
+    let specified_input = matches!(quote, SwapQuote::ExactIn(_));
+    let other_amount_threshold = match quote {
+        SwapQuote::ExactIn(quote) => quote.token_min_out,
+        SwapQuote::ExactOut(quote) => quote.token_max_in,
+    };
+
+    let supplemental_tick_arrays: Vec<AccountMeta> = tick_arrays.iter().skip(3).map(|x| AccountMeta::new(x.0, false)).collect();
+
+    let (amount_a, amount_b) = match quote {
+        SwapQuote::ExactIn(quote) if a_to_b => (quote.token_in, quote.token_est_out),
+        SwapQuote::ExactIn(quote) => (quote.token_est_out, quote.token_in),
+        SwapQuote::ExactOut(quote) if a_to_b => (quote.token_est_in, quote.token_out),
+        SwapQuote::ExactOut(quote) => (quote.token_out, quote.token_est_in),
+    };
+    let (source_a, destination_a, authority_a) = if a_to_b {
+        (token_owner_account_a, fusion_pool.token_vault_a, signer)
+    } else {
+        (fusion_pool.token_vault_a, token_owner_account_a, fusion_pool_address)
+    };
+    let (source_b, destination_b, authority_b) = if a_to_b {
+        (fusion_pool.token_vault_b, token_owner_account_b, fusion_pool_address)
+    } else {
+        (token_owner_account_b, fusion_pool.token_vault_b, signer)
+    };
+
+    let mut remaining_accounts = RemainingAccounts::default();
+    remaining_accounts.add(AccountsType::SupplementalTickArrays, Some(supplemental_tick_arrays));
+    remaining_accounts.add(
+        AccountsType::TransferHookA,
+        get_transfer_hook_extra_accounts(rpc, fusion_pool.token_mint_a, mint_a_info, source_a, destination_a, authority_a, amount_a).await?,
+    );
+    remaining_accounts.add(
+        AccountsType::TransferHookB,
+        get_transfer_hook_extra_accounts(rpc, fusion_pool.token_mint_b, mint_b_info, source_b, destination_b, authority_b, amount_b).await?,
+    );
+
+    Ok(Swap {
+        token_program_a: mint_a_info.owner,
+        token_program_b: mint_b_info.owner,
+        memo_program: spl_memo::ID,
+        token_authority: signer,
+        fusion_pool: fusion_pool_address,
+        token_mint_a: fusion_pool.token_mint_a,
+        token_mint_b: fusion_pool.token_mint_b,
+        token_owner_account_a,
+        token_vault_a: fusion_pool.token_vault_a,
+        token_owner_account_b,
+        token_vault_b: fusion_pool.token_vault_b,
+        tick_array0: tick_arrays[0].0,
+        tick_array1: tick_arrays.get(1).unwrap_or(&tick_arrays[0]).0,
+        tick_array2: tick_arrays.get(2).unwrap_or(&tick_arrays[0]).0,
+    }
+    .instruction_with_remaining_accounts(
+        SwapInstructionArgs {
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit: 0,
+            amount_specified_is_input: specified_input,
+            a_to_b,
+            remaining_accounts_info: remaining_accounts.info(),
+        },
+        &remaining_accounts.accounts,
+    ))
+}
+
+/// Represents the decoded accounts required to quote a swap against a fusion pool.
+#[derive(Debug, Clone)]
+pub struct SwapContext {
+    /// The public key of the fusion pool.
+    pub fusion_pool_address: Pubkey,
+
+    /// The decoded fusion pool account.
+    pub fusion_pool: FusionPool,
+
+    /// The account of the token A mint.
+    pub mint_a_info: Account,
+
+    /// The account of the token B mint.
+    pub mint_b_info: Account,
+
+    /// The tick arrays around the current price, ordered by start tick index.
+    /// Tick arrays that are not initialized are returned as empty tick arrays.
+    pub tick_arrays: Vec<(Pubkey, TickArrayFacade)>,
+}
+
+/// Fetches and decodes the accounts required to quote a swap in either direction.
+///
+/// The fusion pool is fetched first to derive the addresses of its mints and tick arrays. The mints and the
+/// tick arrays within the global tick array radius on both sides of the current price are then fetched
+/// in a single `get_multiple_accounts` request.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `fusion_pool_address` - The public key of the fusion pool.
+///
+/// # Returns
+///
+/// A `Result` containing the `SwapContext` of the pool.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The fusion pool or the token mint accounts are not found or have invalid data.
+/// - Any RPC request to the blockchain fails.
+pub async fn fetch_swap_context(rpc: &RpcClient, fusion_pool_address: Pubkey) -> Result<SwapContext, Box<dyn Error>> {
+    let tick_array_radius = *TICK_ARRAY_RADIUS.try_lock()? as i32;
+
+    let fusion_pool = FusionPool::from_bytes(&fetch_account(rpc, &fusion_pool_address).await?.data)?;
+
+    let tick_array_start_index = get_tick_array_start_tick_index(fusion_pool.tick_current_index, fusion_pool.tick_spacing);
+    let offset = fusion_pool.tick_spacing as i32 * TICK_ARRAY_SIZE as i32;
+    let tick_array_indexes: Vec<i32> = (-tick_array_radius..=tick_array_radius)
+        .map(|i| tick_array_start_index + offset * i)
+        .collect();
+    let tick_array_addresses: Vec<Pubkey> = tick_array_indexes
+        .iter()
+        .map(|&x| get_tick_array_address(&fusion_pool_address, x).map(|y| y.0))
+        .collect::<Result<Vec<Pubkey>, _>>()?;
+
+    let mut addresses = vec![fusion_pool.token_mint_a, fusion_pool.token_mint_b];
+    addresses.extend(&tick_array_addresses);
+    let mut account_infos = fetch_multiple_accounts(rpc, &addresses).await?;
+    let tick_array_infos = account_infos.split_off(2);
+
+    let mint_b_info = account_infos
+        .pop()
+        .flatten()
+        .ok_or(format!("Mint b not found: {}", fusion_pool.token_mint_b))?;
+    let mint_a_info = account_infos
+        .pop()
+        .flatten()
+        .ok_or(format!("Mint a not found: {}", fusion_pool.token_mint_a))?;
+
+    let tick_arrays: Vec<TickArrayFacade> = tick_array_infos
+        .iter()
+        .zip(tick_array_indexes)
+        .map(|(info, start_tick_index)| {
+            info.as_ref()
+                .and_then(|x| TickArray::from_bytes(&x.data).ok())
+                .map(|x| x.into())
+                .unwrap_or(uninitialized_tick_array(start_tick_index))
+        })
+        .collect();
 
-    Ok(result)
+    Ok(SwapContext {
+        fusion_pool_address,
+        fusion_pool,
+        mint_a_info,
+        mint_b_info,
+        tick_arrays: zip(tick_array_addresses, tick_arrays).collect(),
+    })
+}
+
+/// Computes a swap quote for an exact input amount against a given pool state.
+///
+/// Unlike `swap_instructions`, no accounts are fetched, so the quote can be computed against a pool state
+/// constructed by the caller, e.g. a simulated or future state for backtesting.
+///
+/// # Arguments
+///
+/// * `fusion_pool` - The state of the fusion pool.
+/// * `tick_arrays` - The tick arrays needed for the swap.
+/// * `token_in` - The input token amount.
+/// * `specified_token_a` - If `true`, the input token is token A. Otherwise, it is token B.
+/// * `slippage_tolerance_bps` - The slippage tolerance in basis points.
+/// * `transfer_fees` - The transfer fees of token A and token B.
+///
+/// # Returns
+///
+/// A `Result` containing the `ExactInSwapQuote` of the swap.
+///
+/// # Errors
+///
+/// This function will return an error if the quote cannot be computed, e.g. if the tick arrays don't cover the swap.
+pub fn swap_quote_with_state(
+    fusion_pool: FusionPoolFacade,
+    tick_arrays: TickArrays,
+    token_in: u64,
+    specified_token_a: bool,
+    slippage_tolerance_bps: u16,
+    transfer_fees: (Option<TransferFee>, Option<TransferFee>),
+) -> Result<ExactInSwapQuote, Box<dyn Error>> {
+    let (transfer_fee_a, transfer_fee_b) = transfer_fees;
+    Ok(swap_quote_by_input_token(
+        token_in,
+        specified_token_a,
+        slippage_tolerance_bps,
+        fusion_pool,
+        tick_arrays,
+        transfer_fee_a,
+        transfer_fee_b,
+    )?)
 }
 
 #[cfg(not(doctest))]
@@ -121,6 +354,7 @@ async fn fetch_tick_arrays_or_default(
 ///
 /// This function generates instructions for executing swaps, supporting both exact input and exact output scenarios.
 /// It calculates the necessary accounts, tick arrays, and swap quote using the provided parameters.
+/// The number of tick arrays fetched on each side of the current price is controlled by the global tick array radius.
 ///
 /// # Arguments
 ///
@@ -145,6 +379,7 @@ async fn fetch_tick_arrays_or_default(
 /// Returns an error if:
 /// - The signer is invalid or missing.
 /// - The FusionPool or token mint accounts are not found or have invalid data.
+/// - The swap moves the price beyond the fetched tick arrays.
 /// - Any RPC request to the blockchain fails.
 ///
 /// # Example
@@ -192,48 +427,57 @@ pub async fn swap_instructions(
     signer: Option<Pubkey>,
 ) -> Result<SwapInstructions, Box<dyn Error>> {
     let slippage_tolerance_bps = slippage_tolerance_bps.unwrap_or(*SLIPPAGE_TOLERANCE_BPS.try_lock()?);
+    let tick_array_radius = *TICK_ARRAY_RADIUS.try_lock()?;
     let signer = signer.unwrap_or(*FUNDER.try_lock()?);
     if signer == Pubkey::default() {
         return Err("Signer must be provided".into());
     }
 
-    let fusion_pool_info = rpc.get_account(&fusion_pool_address).await?;
+    let fusion_pool_info = fetch_account(rpc, &fusion_pool_address).await?;
     let fusion_pool = FusionPool::from_bytes(&fusion_pool_info.data)?;
     let specified_input = swap_type == SwapType::ExactIn;
     let specified_token_a = specified_mint == fusion_pool.token_mint_a;
     let a_to_b = specified_token_a == specified_input;
 
-    let tick_arrays = fetch_tick_arrays_or_default(rpc, fusion_pool_address, &fusion_pool).await?;
+    let tick_arrays = fetch_tick_arrays_or_default(rpc, fusion_pool_address, &fusion_pool, tick_array_radius, a_to_b).await?;
 
-    let mint_infos = rpc.get_multiple_accounts(&[fusion_pool.token_mint_a, fusion_pool.token_mint_b]).await?;
+    let mint_infos = fetch_multiple_accounts(rpc, &[fusion_pool.token_mint_a, fusion_pool.token_mint_b]).await?;
 
     let mint_a_info = mint_infos[0].as_ref().ok_or(format!("Mint a not found: {}", fusion_pool.token_mint_a))?;
 
     let mint_b_info = mint_infos[1].as_ref().ok_or(format!("Mint b not found: {}", fusion_pool.token_mint_b))?;
 
     let current_epoch = rpc.get_epoch_info().await?.epoch;
-    let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), current_epoch);
-    let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), current_epoch);
+
+    let conservative_transfer_fee = *CONSERVATIVE_TRANSFER_FEE.try_lock()?;
+    let transfer_fee_a = get_current_transfer_fee(Some(mint_a_info), current_epoch, conservative_transfer_fee);
+    let transfer_fee_b = get_current_transfer_fee(Some(mint_b_info), current_epoch, conservative_transfer_fee);
 
     let quote = match swap_type {
-        SwapType::ExactIn => SwapQuote::ExactIn(swap_quote_by_input_token(
-            amount,
-            specified_token_a,
-            slippage_tolerance_bps,
-            fusion_pool.clone().into(),
-            tick_arrays.map(|x| x.1).into(),
-            transfer_fee_a,
-            transfer_fee_b,
-        )?),
-        SwapType::ExactOut => SwapQuote::ExactOut(swap_quote_by_output_token(
-            amount,
-            specified_token_a,
-            slippage_tolerance_bps,
-            fusion_pool.clone().into(),
-            tick_arrays.map(|x| x.1).into(),
-            transfer_fee_a,
-            transfer_fee_b,
-        )?),
+        SwapType::ExactIn => SwapQuote::ExactIn(
+            swap_quote_by_input_token(
+                amount,
+                specified_token_a,
+                slippage_tolerance_bps,
+                fusion_pool.clone().into(),
+                to_tick_arrays(&tick_arrays)?,
+                transfer_fee_a,
+                transfer_fee_b,
+            )
+            .map_err(|e| map_quote_error(e, tick_arrays.len()))?,
+        ),
+        SwapType::ExactOut => SwapQuote::ExactOut(
+            swap_quote_by_output_token(
+                amount,
+                specified_token_a,
+                slippage_tolerance_bps,
+                fusion_pool.clone().into(),
+                to_tick_arrays(&tick_arrays)?,
+                transfer_fee_a,
+                transfer_fee_b,
+            )
+            .map_err(|e| map_quote_error(e, tick_arrays.len()))?,
+        ),
     };
 
     let max_in_amount = match quote {
@@ -257,11 +501,6 @@ pub async fn swap_instructions(
 
     instructions.extend(token_accounts.create_instructions);
 
-    let other_amount_threshold = match quote {
-        SwapQuote::ExactIn(quote) => quote.token_min_out,
-        SwapQuote::ExactOut(quote) => quote.token_max_in,
-    };
-
     let token_owner_account_a = token_accounts
         .token_account_addresses
         .get(&fusion_pool.token_mint_a)
@@ -271,38 +510,21 @@ pub async fn swap_instructions(
         .get(&fusion_pool.token_mint_b)
         .ok_or("Token B owner account not found")?;
 
-    let swap_instruction = Swap {
-        token_program_a: mint_a_info.owner,
-        token_program_b: mint_b_info.owner,
-        memo_program: spl_memo::ID,
-        token_authority: signer,
-        fusion_pool: fusion_pool_address,
-        token_mint_a: fusion_pool.token_mint_a,
-        token_mint_b: fusion_pool.token_mint_b,
-        token_owner_account_a: *token_owner_account_a,
-        token_vault_a: fusion_pool.token_vault_a,
-        token_owner_account_b: *token_owner_account_b,
-        token_vault_b: fusion_pool.token_vault_b,
-        tick_array0: tick_arrays[0].0,
-        tick_array1: tick_arrays[1].0,
-        tick_array2: tick_arrays[2].0,
-    }
-    .instruction_with_remaining_accounts(
-        SwapInstructionArgs {
-            amount,
-            other_amount_threshold,
-            sqrt_price_limit: 0,
-            amount_specified_is_input: specified_input,
-            a_to_b,
-            remaining_accounts_info: Some(RemainingAccountsInfo {
-                slices: vec![RemainingAccountsSlice {
-                    accounts_type: AccountsType::SupplementalTickArrays,
-                    length: 2,
-                }],
-            }),
-        },
-        &[AccountMeta::new(tick_arrays[3].0, false), AccountMeta::new(tick_arrays[4].0, false)],
-    );
+    let swap_instruction = swap_instruction(
+        rpc,
+        fusion_pool_address,
+        &fusion_pool,
+        mint_a_info,
+        mint_b_info,
+        &tick_arrays,
+        &quote,
+        amount,
+        a_to_b,
+        *token_owner_account_a,
+        *token_owner_account_b,
+        signer,
+    )
+    .await?;
 
     instructions.push(swap_instruction);
     instructions.extend(token_accounts.cleanup_instructions);
@@ -319,6 +541,8 @@ mod tests {
     use std::collections::HashMap;
     use std::error::Error;
 
+    use fusionamm_client::get_tick_array_address;
+    use fusionamm_core::{swap_quote_by_input_token, FusionPoolFacade, TickArrays, TransferFee, TICK_ARRAY_SIZE};
     use rstest::rstest;
     use serial_test::serial;
     use solana_client::nonblocking::rpc_client::RpcClient;
@@ -330,8 +554,9 @@ mod tests {
     use spl_token::state::Account as TokenAccount;
     use spl_token_2022::{extension::StateWithExtensionsOwned, state::Account as TokenAccount2022, ID as TOKEN_2022_PROGRAM_ID};
 
+    use super::uninitialized_tick_array;
     use crate::{
-        increase_liquidity_instructions, swap_instructions,
+        fetch_swap_context, increase_liquidity_instructions, reset_configuration, set_tick_array_radius, swap_instructions, swap_quote_with_state,
         tests::{
             setup_ata_te, setup_ata_with_amount, setup_fusion_pool, setup_mint_te, setup_mint_te_fee, setup_mint_with_decimals, setup_position,
             RpcContext, SetupAtaConfig,
@@ -539,4 +764,102 @@ mod tests {
                 .unwrap();
         });
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_swap_with_insufficient_tick_array_radius() {
+        let ctx = RpcContext::new().await;
+
+        let mint_a = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+        let mint_b = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+        let (mint_a, mint_b) = if mint_a < mint_b { (mint_a, mint_b) } else { (mint_b, mint_a) };
+        setup_ata_with_amount(&ctx, mint_a, 1_000_000_000).await.unwrap();
+        setup_ata_with_amount(&ctx, mint_b, 1_000_000_000).await.unwrap();
+
+        // The pool price is at the start of a tick array, so an A to B swap immediately moves into the previous one.
+        let pool_address = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 300).await.unwrap();
+        let position_mint = setup_position(&ctx, pool_address, Some((-192, 192)), None).await.unwrap();
+        let liquidity_ix = increase_liquidity_instructions(
+            &ctx.rpc,
+            position_mint,
+            IncreaseLiquidityParam::Liquidity(10_000_000),
+            Some(100),
+            Some(ctx.signer.pubkey()),
+        )
+        .await
+        .unwrap();
+        ctx.send_transaction_with_signers(liquidity_ix.instructions, liquidity_ix.additional_signers.iter().collect())
+            .await
+            .unwrap();
+
+        set_tick_array_radius(0).unwrap();
+        let result = swap_instructions(&ctx.rpc, pool_address, 1000, mint_a, SwapType::ExactIn, Some(100), Some(ctx.signer.pubkey())).await;
+        assert!(result.unwrap_err().to_string().starts_with("Insufficient tick arrays"));
+
+        set_tick_array_radius(1).unwrap();
+        let swap_ix = swap_instructions(&ctx.rpc, pool_address, 1000, mint_a, SwapType::ExactIn, Some(100), Some(ctx.signer.pubkey()))
+            .await
+            .unwrap();
+        reset_configuration().unwrap();
+
+        ctx.send_transaction_with_signers(swap_ix.instructions, swap_ix.additional_signers.iter().collect())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_fetch_swap_context() {
+        let ctx = RpcContext::new().await;
+
+        let mint_a = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+        let mint_b = setup_mint_with_decimals(&ctx, 6).await.unwrap();
+        let (mint_a, mint_b) = if mint_a < mint_b { (mint_a, mint_b) } else { (mint_b, mint_a) };
+        let pool_address = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 3000).await.unwrap();
+        setup_position(&ctx, pool_address, Some((-192, 192)), None).await.unwrap();
+
+        let context = fetch_swap_context(&ctx.rpc, pool_address).await.unwrap();
+        assert_eq!(context.fusion_pool_address, pool_address);
+        assert_eq!(context.fusion_pool.token_mint_a, mint_a);
+        assert_eq!(context.fusion_pool.token_mint_b, mint_b);
+        assert_eq!(context.mint_a_info.owner, spl_token::ID);
+        assert_eq!(context.mint_b_info.owner, spl_token::ID);
+        assert_eq!(context.mint_a_info.data, ctx.rpc.get_account(&mint_a).await.unwrap().data);
+        assert_eq!(context.mint_b_info.data, ctx.rpc.get_account(&mint_b).await.unwrap().data);
+
+        // The default radius of 2 returns 5 tick arrays centered on the tick array of the current price.
+        let start_tick_indexes: Vec<i32> = context.tick_arrays.iter().map(|x| x.1.start_tick_index).collect();
+        assert_eq!(start_tick_indexes, vec![-11264, -5632, 0, 5632, 11264]);
+        for (address, tick_array) in &context.tick_arrays {
+            assert_eq!(*address, get_tick_array_address(&pool_address, tick_array.start_tick_index).unwrap().0);
+        }
+    }
+
+    #[test]
+    fn test_swap_quote_with_state() {
+        let fusion_pool = FusionPoolFacade {
+            tick_spacing: 2,
+            fee_rate: 3000,
+            liquidity: 100_000_000_000,
+            sqrt_price: 1 << 64,
+            tick_current_index: 0,
+            ..FusionPoolFacade::default()
+        };
+        let tick_arrays = || {
+            TickArrays::Three(
+                uninitialized_tick_array(-(TICK_ARRAY_SIZE as i32) * 2),
+                uninitialized_tick_array(0),
+                uninitialized_tick_array(TICK_ARRAY_SIZE as i32 * 2),
+            )
+        };
+        let transfer_fee_a = Some(TransferFee {
+            fee_bps: 100,
+            max_fee: 1_000_000,
+        });
+
+        let quote = swap_quote_with_state(fusion_pool, tick_arrays(), 1_000_000, true, 100, (transfer_fee_a, None)).unwrap();
+        let expected = swap_quote_by_input_token(1_000_000, true, 100, fusion_pool, tick_arrays(), transfer_fee_a, None).unwrap();
+        assert_eq!(quote, expected);
+        assert!(quote.token_est_out > 0);
+    }
 }
@@ -15,7 +15,7 @@ use fusionamm_client::{
     InitializeTickArray, InitializeTickArrayInstructionArgs, OpenBundledPosition, OpenBundledPositionInstructionArgs, OpenPosition,
     OpenPositionInstructionArgs, FP_NFT_UPDATE_AUTH,
 };
-use fusionamm_core::{get_initializable_tick_index, get_tick_array_start_tick_index, tick_index_to_sqrt_price, TICK_ARRAY_SIZE};
+use fusionamm_core::{get_initializable_tick_index, get_tick_array_start_tick_index, tick_array_start_indices_for_range, tick_index_to_sqrt_price};
 use solana_program::sysvar::rent::ID as RENT_PROGRAM_ID;
 use solana_pubkey::Pubkey;
 use solana_sdk_ids::system_program;
@@ -32,27 +32,9 @@ pub async fn init_tick_arrays_for_range(
     upper_tick_index: i32,
     spacing: u16,
 ) -> Result<(), Box<dyn Error>> {
-    let (low, high) = if lower_tick_index <= upper_tick_index {
-        (lower_tick_index, upper_tick_index)
-    } else {
-        (upper_tick_index, lower_tick_index)
-    };
-
-    let offset = (spacing as i32) * (TICK_ARRAY_SIZE as i32);
-
-    let start_low = get_tick_array_start_tick_index(low, spacing);
-    let start_high = get_tick_array_start_tick_index(high, spacing);
-
-    let (begin, end) = if start_low <= start_high {
-        (start_low, start_high)
-    } else {
-        (start_high, start_low)
-    };
-
     let mut instructions = vec![];
 
-    let mut current = begin;
-    while current <= end {
+    for current in tick_array_start_indices_for_range(lower_tick_index, upper_tick_index, spacing) {
         let (tick_array_addr, _) = get_tick_array_address(&fusion_pool, current)?;
 
         let account_result = ctx.rpc.get_account(&tick_array_addr).await;
@@ -67,8 +49,6 @@ pub async fn init_tick_arrays_for_range(
                 .instruction(InitializeTickArrayInstructionArgs { start_tick_index: current }),
             );
         }
-
-        current += offset;
     }
 
     if !instructions.is_empty() {
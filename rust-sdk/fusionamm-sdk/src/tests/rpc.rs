@@ -8,7 +8,9 @@
 // See the LICENSE file in the project root for license information.
 //
 
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::{error::Error, str::FromStr};
 
 use async_trait::async_trait;
@@ -17,14 +19,20 @@ use base64::Engine;
 use fusionamm_client::{get_fusion_pools_config_address, FUSIONAMM_ID, FUSION_POOLS_CONFIG_DISCRIMINATOR};
 use serde_json::{from_value, to_value, Value};
 use solana_account::Account;
-use solana_account_decoder::{encode_ui_account, UiAccountEncoding};
+use solana_account_decoder::{
+    encode_ui_account,
+    parse_account_data::{AccountAdditionalDataV3, SplTokenAdditionalDataV2},
+    UiAccountEncoding,
+};
 use solana_client::client_error::Result as ClientResult;
 use solana_client::{
     client_error::{ClientError, ClientErrorKind},
     nonblocking::rpc_client::RpcClient,
     rpc_client::{RpcClientConfig, SerializableTransaction},
-    rpc_request::RpcRequest,
-    rpc_response::{Response, RpcBlockhash, RpcResponseContext, RpcVersionInfo},
+    rpc_config::RpcProgramAccountsConfig,
+    rpc_filter::RpcFilterType,
+    rpc_request::{RpcError, RpcRequest, RpcResponseErrorData},
+    rpc_response::{Response, RpcBlockhash, RpcKeyedAccount, RpcResponseContext, RpcVersionInfo},
     rpc_sender::{RpcSender, RpcTransportStats},
 };
 use solana_commitment_config::CommitmentLevel;
@@ -41,6 +49,8 @@ use solana_signer::Signer;
 use solana_transaction::versioned::VersionedTransaction;
 use solana_version::Version;
 use spl_memo::build_memo;
+use spl_token_2022::extension::StateWithExtensions;
+use spl_token_2022::state::{Account as TokenAccount, Mint};
 
 use crate::tests::anchor_programs;
 
@@ -53,6 +63,9 @@ pub struct RpcContext {
     pub signer: Keypair,
     keypairs: Vec<Keypair>,
     keypair_index: AtomicUsize,
+    get_account_failures: Arc<AtomicUsize>,
+    get_account_failures_permanent: Arc<AtomicBool>,
+    context: Arc<Mutex<ProgramTestContext>>,
 }
 
 impl RpcContext {
@@ -98,8 +111,19 @@ impl RpcContext {
         for (name, pubkey) in PROGRAMS.iter() {
             test.add_program(name, *pubkey, None);
         }
-        let context = Mutex::new(test.start_with_context().await);
-        let rpc = RpcClient::new_sender(MockRpcSender { context }, RpcClientConfig::default());
+        let context = Arc::new(Mutex::new(test.start_with_context().await));
+        let known_accounts = Mutex::new(HashSet::from([config]));
+        let get_account_failures = Arc::new(AtomicUsize::new(0));
+        let get_account_failures_permanent = Arc::new(AtomicBool::new(false));
+        let rpc = RpcClient::new_sender(
+            MockRpcSender {
+                context: context.clone(),
+                known_accounts,
+                get_account_failures: get_account_failures.clone(),
+                get_account_failures_permanent: get_account_failures_permanent.clone(),
+            },
+            RpcClientConfig::default(),
+        );
 
         let mut keypairs = (0..100).map(|_| Keypair::new()).collect::<Vec<_>>();
         keypairs.sort_by_key(|x| x.pubkey());
@@ -109,9 +133,29 @@ impl RpcContext {
             signer,
             keypairs,
             keypair_index: AtomicUsize::new(0),
+            get_account_failures,
+            get_account_failures_permanent,
+            context,
         }
     }
 
+    /// Writes an account directly to the test validator.
+    pub async fn set_account(&self, address: Pubkey, account: Account) {
+        self.context.lock().await.set_account(&address, &account.into());
+    }
+
+    /// Makes the next `count` getAccountInfo and getMultipleAccounts requests fail with a transient error.
+    pub fn fail_next_get_account_requests(&self, count: usize) {
+        self.get_account_failures_permanent.store(false, Ordering::Relaxed);
+        self.get_account_failures.store(count, Ordering::Relaxed);
+    }
+
+    /// Makes the next `count` getAccountInfo and getMultipleAccounts requests fail with an invalid params error.
+    pub fn reject_next_get_account_requests(&self, count: usize) {
+        self.get_account_failures_permanent.store(true, Ordering::Relaxed);
+        self.get_account_failures.store(count, Ordering::Relaxed);
+    }
+
     pub fn get_next_keypair(&self) -> &Keypair {
         let index = self.keypair_index.fetch_add(1, Ordering::Relaxed);
         &self.keypairs[index]
@@ -152,7 +196,20 @@ fn to_wire_account(address: &Pubkey, account: Option<Account>, encoding: UiAccou
     }
 }
 
-async fn send(context: &mut ProgramTestContext, method: &str, params: &Vec<Value>) -> Result<Value, Box<dyn Error>> {
+fn filter_allows(filter: &RpcFilterType, data: &[u8]) -> bool {
+    match filter {
+        RpcFilterType::DataSize(size) => data.len() as u64 == *size,
+        RpcFilterType::Memcmp(compare) => compare.bytes_match(data),
+        RpcFilterType::TokenAccountState => false,
+    }
+}
+
+async fn send(
+    context: &mut ProgramTestContext,
+    known_accounts: &mut HashSet<Pubkey>,
+    method: &str,
+    params: &[Value],
+) -> Result<Value, Box<dyn Error>> {
     let slot = context.banks_client.get_root_slot().await?;
 
     let response = match method {
@@ -188,6 +245,77 @@ async fn send(context: &mut ProgramTestContext, method: &str, params: &Vec<Value
                 value: accounts,
             })?
         }
+        "getProgramAccounts" => {
+            // Banks client can't scan accounts, so only the accounts referenced by previous transactions are searched.
+            let program_id = Pubkey::from_str(params[0].as_str().unwrap_or_default())?;
+            let config = params
+                .get(1)
+                .cloned()
+                .map(from_value::<RpcProgramAccountsConfig>)
+                .transpose()?
+                .unwrap_or_default();
+            let encoding = config.account_config.encoding.unwrap_or(UiAccountEncoding::Base64);
+            let filters = config.filters.unwrap_or_default();
+            let mut accounts: Vec<RpcKeyedAccount> = Vec::new();
+            for address in known_accounts.iter() {
+                let account = context
+                    .banks_client
+                    .get_account_with_commitment(*address, CommitmentLevel::Confirmed)
+                    .await?;
+                if let Some(account) = account.filter(|x| x.owner == program_id) {
+                    if filters.iter().all(|filter| filter_allows(filter, &account.data)) {
+                        accounts.push(RpcKeyedAccount {
+                            pubkey: address.to_string(),
+                            account: encode_ui_account(address, &account, encoding, None, None),
+                        });
+                    }
+                }
+            }
+            to_value(accounts)?
+        }
+        "getTokenAccountsByOwner" => {
+            // Like getProgramAccounts, only the accounts referenced by previous transactions are searched.
+            let owner = Pubkey::from_str(params[0].as_str().unwrap_or_default())?;
+            let program_id = params[1].get("programId").and_then(|x| x.as_str()).map(Pubkey::from_str).transpose()?;
+            let mint = params[1].get("mint").and_then(|x| x.as_str()).map(Pubkey::from_str).transpose()?;
+            let encoding = get_encoding(&params[2]);
+            let mut accounts: Vec<RpcKeyedAccount> = Vec::new();
+            for address in known_accounts.iter() {
+                let account = context
+                    .banks_client
+                    .get_account_with_commitment(*address, CommitmentLevel::Confirmed)
+                    .await?;
+                let Some(account) = account.filter(|x| x.owner == spl_token::ID || x.owner == spl_token_2022::ID) else {
+                    continue;
+                };
+                let Ok(token_account) = StateWithExtensions::<TokenAccount>::unpack(&account.data) else {
+                    continue;
+                };
+                if token_account.base.owner != owner
+                    || program_id.is_some_and(|x| x != account.owner)
+                    || mint.is_some_and(|x| x != token_account.base.mint)
+                {
+                    continue;
+                }
+                let mint_account = context
+                    .banks_client
+                    .get_account_with_commitment(token_account.base.mint, CommitmentLevel::Confirmed)
+                    .await?
+                    .ok_or("Mint not found")?;
+                let decimals = StateWithExtensions::<Mint>::unpack(&mint_account.data)?.base.decimals;
+                let additional_data = AccountAdditionalDataV3 {
+                    spl_token_additional_data: Some(SplTokenAdditionalDataV2::with_decimals(decimals)),
+                };
+                accounts.push(RpcKeyedAccount {
+                    pubkey: address.to_string(),
+                    account: encode_ui_account(address, &account, encoding, Some(additional_data), None),
+                });
+            }
+            to_value(Response {
+                context: RpcResponseContext { slot, api_version: None },
+                value: accounts,
+            })?
+        }
         "getMinimumBalanceForRentExemption" => {
             let data_len = params[0].as_u64().unwrap_or(0) as usize;
             let rent = context.banks_client.get_rent().await?;
@@ -211,6 +339,7 @@ async fn send(context: &mut ProgramTestContext, method: &str, params: &Vec<Value
             if let Err(e) = meta.result {
                 return Err(e.to_string().into());
             }
+            known_accounts.extend(transaction.message.static_account_keys());
             let signature = transaction.get_signature();
             let signature_base58 = bs58::encode(signature).into_string();
             to_value(signature_base58)?
@@ -237,7 +366,10 @@ async fn send(context: &mut ProgramTestContext, method: &str, params: &Vec<Value
 }
 
 struct MockRpcSender {
-    context: Mutex<ProgramTestContext>,
+    context: Arc<Mutex<ProgramTestContext>>,
+    known_accounts: Mutex<HashSet<Pubkey>>,
+    get_account_failures: Arc<AtomicUsize>,
+    get_account_failures_permanent: Arc<AtomicBool>,
 }
 
 #[async_trait]
@@ -247,8 +379,26 @@ impl RpcSender for MockRpcSender {
         let method = request_json["method"].as_str().unwrap_or_default();
         let default_params = Vec::new();
         let params = request_json["params"].as_array().unwrap_or(&default_params);
+        if (method == "getAccountInfo" || method == "getMultipleAccounts")
+            && self
+                .get_account_failures
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| x.checked_sub(1))
+                .is_ok()
+        {
+            let kind = if self.get_account_failures_permanent.load(Ordering::Relaxed) {
+                ClientErrorKind::RpcError(RpcError::RpcResponseError {
+                    code: -32602,
+                    message: "Invalid params".to_string(),
+                    data: RpcResponseErrorData::Empty,
+                })
+            } else {
+                ClientErrorKind::Io(std::io::Error::new(std::io::ErrorKind::ConnectionReset, "Transient RPC error"))
+            };
+            return Err(ClientError::new_with_request(kind, request));
+        }
         let mut context = self.context.lock().await;
-        let response = send(&mut context, method, params)
+        let mut known_accounts = self.known_accounts.lock().await;
+        let response = send(&mut context, &mut known_accounts, method, params)
             .await
             .map_err(|e| ClientError::new_with_request(ClientErrorKind::Custom(e.to_string()), request))?;
 
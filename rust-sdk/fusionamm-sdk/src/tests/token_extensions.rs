@@ -9,21 +9,27 @@
 //
 
 use super::rpc::RpcContext;
+use solana_account::Account;
 use solana_program::system_instruction::create_account;
 use solana_pubkey::Pubkey;
 use solana_signer::Signer;
 use spl_associated_token_account::{get_associated_token_address_with_program_id, instruction::create_associated_token_account_idempotent};
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, state::ExtraAccountMetaList};
 use spl_token_2022::{
     extension::{
         transfer_fee::instruction::{initialize_transfer_fee_config, set_transfer_fee},
-        ExtensionType,
+        transfer_hook, ExtensionType,
     },
-    instruction::{initialize_mint2, mint_to},
+    instruction::{initialize_mint2, initialize_permanent_delegate, mint_to},
     state::Mint,
     ID as TOKEN_2022_PROGRAM_ID,
 };
+use spl_transfer_hook_interface::{get_extra_account_metas_address, instruction::ExecuteInstruction};
 use std::error::Error;
 
+/// The transfer hook program set on mints with the transfer hook extension. The program is not deployed.
+pub const TEST_TRANSFER_HOOK_PROGRAM_ID: Pubkey = Pubkey::new_from_array([7; 32]);
+
 #[derive(Default)]
 pub struct SetupAtaConfig {
     pub amount: Option<u64>,
@@ -51,6 +57,17 @@ pub async fn setup_mint_te(ctx: &RpcContext, extensions: &[ExtensionType]) -> Re
                 1_000_000_000, // 1 token (matching program)
             )?);
         }
+        if extension == &ExtensionType::PermanentDelegate {
+            instructions.push(initialize_permanent_delegate(&TOKEN_2022_PROGRAM_ID, &mint.pubkey(), &ctx.signer.pubkey())?);
+        }
+        if extension == &ExtensionType::TransferHook {
+            instructions.push(transfer_hook::instruction::initialize(
+                &TOKEN_2022_PROGRAM_ID,
+                &mint.pubkey(),
+                Some(ctx.signer.pubkey()),
+                Some(TEST_TRANSFER_HOOK_PROGRAM_ID),
+            )?);
+        }
     }
 
     // 3. Initialize mint
@@ -84,6 +101,28 @@ pub async fn setup_mint_te_fee(ctx: &RpcContext) -> Result<Pubkey, Box<dyn Error
     setup_mint_te(ctx, &[ExtensionType::TransferFeeConfig]).await
 }
 
+/// Creates the validation account of the test transfer hook program for a mint.
+/// The validation account requires one extra writable account, which is returned.
+pub async fn setup_transfer_hook_validation_account(ctx: &RpcContext, mint: Pubkey) -> Result<Pubkey, Box<dyn Error>> {
+    let extra_account = Pubkey::new_unique();
+    let validation_address = get_extra_account_metas_address(&mint, &TEST_TRANSFER_HOOK_PROGRAM_ID);
+    let extra_account_metas = [ExtraAccountMeta::new_with_pubkey(&extra_account, false, true)?];
+    let mut data = vec![0; ExtraAccountMetaList::size_of(extra_account_metas.len())?];
+    ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &extra_account_metas)?;
+    ctx.set_account(
+        validation_address,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: TEST_TRANSFER_HOOK_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .await;
+    Ok(extra_account)
+}
+
 pub async fn setup_ata_te(ctx: &RpcContext, mint: Pubkey, config: Option<SetupAtaConfig>) -> Result<Pubkey, Box<dyn Error>> {
     let config = config.unwrap_or_default();
     let ata = get_associated_token_address_with_program_id(&ctx.signer.pubkey(), &mint, &TOKEN_2022_PROGRAM_ID);
@@ -0,0 +1,109 @@
+//
+// Copyright (c) Cryptic Dot
+//
+// Licensed under FusionAMM SDK Source-Available License v1.0
+// See the LICENSE file in the project root for license information.
+//
+
+use fusionamm_client::{get_tick_array_address, FusionPool, InitializeTickArray, InitializeTickArrayInstructionArgs};
+use fusionamm_core::get_tick_array_start_tick_index;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+use std::error::Error;
+
+use crate::{fetch_account, fetch_multiple_accounts, FUNDER};
+
+/// Generates the instruction to initialize a tick array of a fusion pool.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `pool_address` - The public key of the fusion pool.
+/// * `start_tick_index` - The start tick index of the tick array. It must be aligned to the tick array size of the pool.
+/// * `funder` - An optional public key of the account paying for the tick array. Defaults to the global funder if not provided.
+///
+/// # Returns
+///
+/// A `Result` containing the `InitializeTickArray` instruction, or an empty vector if the tick array is already initialized.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The funder account is invalid.
+/// - The pool account cannot be fetched.
+/// - The start tick index isn't the start of a tick array of the pool.
+pub async fn initialize_tick_array_instructions(
+    rpc: &RpcClient,
+    pool_address: Pubkey,
+    start_tick_index: i32,
+    funder: Option<Pubkey>,
+) -> Result<Vec<Instruction>, Box<dyn Error>> {
+    let funder = funder.unwrap_or(*FUNDER.try_lock()?);
+    if funder == Pubkey::default() {
+        return Err("Funder must be provided".into());
+    }
+
+    let fusion_pool = FusionPool::from_bytes(&fetch_account(rpc, &pool_address).await?.data)?;
+    if get_tick_array_start_tick_index(start_tick_index, fusion_pool.tick_spacing) != start_tick_index {
+        return Err(format!("Tick index {} is not the start of a tick array", start_tick_index).into());
+    }
+
+    let tick_array_address = get_tick_array_address(&pool_address, start_tick_index)?.0;
+    let tick_array_infos = fetch_multiple_accounts(rpc, &[tick_array_address]).await?;
+    if tick_array_infos[0].is_some() {
+        return Ok(vec![]);
+    }
+
+    Ok(vec![InitializeTickArray {
+        fusion_pool: pool_address,
+        funder,
+        tick_array: tick_array_address,
+        system_program: solana_program::system_program::id(),
+    }
+    .instruction(InitializeTickArrayInstructionArgs { start_tick_index })])
+}
+
+#[cfg(test)]
+mod tests {
+    use fusionamm_client::{get_tick_array_address, TickArray};
+    use serial_test::serial;
+    use solana_program_test::tokio;
+    use solana_signer::Signer;
+
+    use crate::{
+        initialize_tick_array_instructions,
+        tests::{setup_fusion_pool, setup_mint_with_decimals, RpcContext},
+    };
+
+    #[tokio::test]
+    #[serial]
+    async fn test_initialize_tick_array() {
+        let ctx = RpcContext::new().await;
+
+        let mint_a = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+        let mint_b = setup_mint_with_decimals(&ctx, 9).await.unwrap();
+        let (mint_a, mint_b) = if mint_a < mint_b { (mint_a, mint_b) } else { (mint_b, mint_a) };
+        let pool_address = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 3000).await.unwrap();
+
+        // The tick array size of the pool is 88 * 64 = 5632.
+        let result = initialize_tick_array_instructions(&ctx.rpc, pool_address, 64, Some(ctx.signer.pubkey())).await;
+        assert!(result.is_err());
+
+        let instructions = initialize_tick_array_instructions(&ctx.rpc, pool_address, 11264, Some(ctx.signer.pubkey()))
+            .await
+            .unwrap();
+        assert_eq!(instructions.len(), 1);
+        ctx.send_transaction(instructions).await.unwrap();
+
+        let tick_array_address = get_tick_array_address(&pool_address, 11264).unwrap().0;
+        let tick_array = TickArray::from_bytes(&ctx.rpc.get_account(&tick_array_address).await.unwrap().data).unwrap();
+        assert_eq!(tick_array.start_tick_index, 11264);
+        assert_eq!(tick_array.fusion_pool, pool_address);
+
+        let instructions = initialize_tick_array_instructions(&ctx.rpc, pool_address, 11264, Some(ctx.signer.pubkey()))
+            .await
+            .unwrap();
+        assert!(instructions.is_empty());
+    }
+}
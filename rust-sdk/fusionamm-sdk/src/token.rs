@@ -8,25 +8,31 @@
 // See the LICENSE file in the project root for license information.
 //
 
-use crate::{NativeMintWrappingStrategy, NATIVE_MINT_WRAPPING_STRATEGY};
+use crate::{fetch_multiple_accounts, NativeMintWrappingStrategy, NATIVE_MINT_WRAPPING_STRATEGY};
+use fusionamm_client::{AccountsType, RemainingAccountsInfo, RemainingAccountsSlice};
 use fusionamm_core::TransferFee;
 use solana_account::Account as SolanaAccount;
 use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_instruction::Instruction;
+use solana_instruction::{AccountMeta, Instruction};
 use solana_keypair::Keypair;
-use solana_program::hash::hashv;
 use solana_program::system_instruction::{create_account, create_account_with_seed, transfer};
 use solana_program_error::ProgramError;
 use solana_pubkey::Pubkey;
 use solana_signer::Signer;
-use spl_associated_token_account::{get_associated_token_address_with_program_id, instruction::create_associated_token_account};
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction::{create_associated_token_account, create_associated_token_account_idempotent},
+};
 use spl_token::instruction::{close_account, initialize_account3, sync_native};
 use spl_token::solana_program::program_pack::Pack;
 use spl_token::{native_mint, ID as TOKEN_PROGRAM_ID};
 use spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use spl_token_2022::extension::transfer_hook;
 use spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+use spl_token_2022::instruction::transfer_checked;
 use spl_token_2022::state::{Account, Mint};
 use spl_token_2022::ID as TOKEN_2022_PROGRAM_ID;
+use spl_transfer_hook_interface::offchain::add_extra_account_metas_for_execute;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::{collections::HashMap, error::Error};
 
@@ -60,7 +66,7 @@ pub(crate) async fn prepare_token_accounts_instructions(
     let native_mint_index = mint_addresses.iter().position(|&x| x == spl_token::native_mint::ID);
     let has_native_mint = native_mint_index.is_some();
 
-    let maybe_mint_account_infos = rpc.get_multiple_accounts(&mint_addresses).await?;
+    let maybe_mint_account_infos = fetch_multiple_accounts(rpc, &mint_addresses).await?;
     let mint_account_infos: Vec<&SolanaAccount> = maybe_mint_account_infos
         .iter()
         .map(|x| x.as_ref().ok_or(ProgramError::UninitializedAccount))
@@ -72,7 +78,7 @@ pub(crate) async fn prepare_token_accounts_instructions(
         .map(|(i, x)| get_associated_token_address_with_program_id(&owner, &mint_addresses[i], &x.owner))
         .collect();
 
-    let ata_account_infos = rpc.get_multiple_accounts(&ata_addresses).await?;
+    let ata_account_infos = fetch_multiple_accounts(rpc, &ata_addresses).await?;
 
     let mut token_account_addresses: HashMap<Pubkey, Pubkey> = HashMap::new();
     let mut create_instructions: Vec<Instruction> = Vec::new();
@@ -94,7 +100,7 @@ pub(crate) async fn prepare_token_accounts_instructions(
             continue;
         }
 
-        create_instructions.push(create_associated_token_account(&owner, &ata_address, &mint_address, &mint_account_infos[i].owner));
+        create_instructions.push(create_associated_token_account_idempotent(&owner, &owner, &mint_address, &mint_account_infos[i].owner));
     }
 
     for i in 0..mint_addresses.len() {
@@ -127,17 +133,14 @@ pub(crate) async fn prepare_token_accounts_instructions(
     }
 
     if has_native_mint && native_mint_wrapping_strategy == NativeMintWrappingStrategy::Keypair {
-        let keypair = Keypair::new();
         let mut lamports = rpc.get_minimum_balance_for_rent_exemption(Account::LEN).await?;
 
         if let TokenAccountStrategy::WithBalance(_, balance) = spec[native_mint_index.unwrap_or(0)] {
             lamports += balance;
         }
 
-        create_instructions.push(create_account(&owner, &keypair.pubkey(), lamports, Account::LEN as u64, &TOKEN_PROGRAM_ID));
-
-        create_instructions.push(initialize_account3(&TOKEN_PROGRAM_ID, &keypair.pubkey(), &native_mint::ID, &owner)?);
-
+        let keypair = Keypair::new();
+        create_instructions.extend(create_keypair_native_mint_account_instructions(&owner, &keypair.pubkey(), lamports)?);
         cleanup_instructions.push(close_account(&TOKEN_PROGRAM_ID, &keypair.pubkey(), &owner, &owner, &[])?);
 
         token_account_addresses.insert(native_mint::ID, keypair.pubkey());
@@ -151,20 +154,8 @@ pub(crate) async fn prepare_token_accounts_instructions(
             lamports += balance;
         }
 
-        // Generating secure seed takes longer and is not really needed here.
-        // With date, it should only create collisions if the same owner
-        // creates multiple accounts at exactly the same time (in ms)
-        let seed = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or(Duration::from_secs(0))
-            .as_millis()
-            .to_string();
-        let pubkey = Pubkey::new_from_array(hashv(&[owner.to_bytes().as_ref(), seed.as_bytes(), TOKEN_PROGRAM_ID.to_bytes().as_ref()]).to_bytes());
-
-        create_instructions.push(create_account_with_seed(&owner, &pubkey, &owner, &seed, lamports, Account::LEN as u64, &TOKEN_PROGRAM_ID));
-
-        create_instructions.push(initialize_account3(&TOKEN_PROGRAM_ID, &pubkey, &native_mint::ID, &owner)?);
-
+        let (pubkey, instructions) = create_seed_native_mint_account_instructions(&owner, lamports)?;
+        create_instructions.extend(instructions);
         cleanup_instructions.push(close_account(&TOKEN_PROGRAM_ID, &pubkey, &owner, &owner, &[])?);
 
         token_account_addresses.insert(native_mint::ID, pubkey);
@@ -204,21 +195,277 @@ pub(crate) async fn prepare_token_accounts_instructions(
     })
 }
 
-pub(crate) fn get_current_transfer_fee(mint_account_info: Option<&SolanaAccount>, current_epoch: u64) -> Option<TransferFee> {
+fn create_keypair_native_mint_account_instructions(owner: &Pubkey, account: &Pubkey, lamports: u64) -> Result<Vec<Instruction>, ProgramError> {
+    Ok(vec![
+        create_account(owner, account, lamports, Account::LEN as u64, &TOKEN_PROGRAM_ID),
+        initialize_account3(&TOKEN_PROGRAM_ID, account, &native_mint::ID, owner)?,
+    ])
+}
+
+/// Derives the address of a wrapped SOL token account created with the `Seed` wrapping strategy.
+///
+/// The account is created with `create_account_with_seed`, using the owner as the base and the
+/// token program as the account owner, so its address is `sha256(owner || seed || token_program)`.
+/// The seed used by this SDK is the creation timestamp in milliseconds.
+///
+/// # Arguments
+///
+/// * `owner` - The public key of the wallet which owns and funds the token account.
+/// * `seed` - The seed string used to create the account. At most 32 bytes long.
+///
+/// # Returns
+///
+/// The address of the wrapped SOL token account.
+///
+/// # Errors
+///
+/// Returns an error if the seed is longer than 32 bytes.
+pub fn get_seed_wrapped_sol_address(owner: &Pubkey, seed: &str) -> Result<Pubkey, Box<dyn Error>> {
+    Ok(Pubkey::create_with_seed(owner, seed, &TOKEN_PROGRAM_ID)?)
+}
+
+fn create_seed_native_mint_account_instructions(owner: &Pubkey, lamports: u64) -> Result<(Pubkey, Vec<Instruction>), Box<dyn Error>> {
+    // Generating secure seed takes longer and is not really needed here.
+    // With date, it should only create collisions if the same owner
+    // creates multiple accounts at exactly the same time (in ms)
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::from_secs(0))
+        .as_millis()
+        .to_string();
+    let pubkey = get_seed_wrapped_sol_address(owner, &seed)?;
+
+    let instructions = vec![
+        create_account_with_seed(owner, &pubkey, owner, &seed, lamports, Account::LEN as u64, &TOKEN_PROGRAM_ID),
+        initialize_account3(&TOKEN_PROGRAM_ID, &pubkey, &native_mint::ID, owner)?,
+    ];
+
+    Ok((pubkey, instructions))
+}
+
+/// Represents the instructions for wrapping SOL into a native mint token account.
+#[derive(Debug)]
+pub struct WrapSolInstructions {
+    /// The instructions which create and fund the wrapped SOL token account.
+    pub instructions: Vec<Instruction>,
+
+    /// The instructions which close the auxiliary token account and return the lamports to the owner.
+    /// Empty for the `Ata` strategy, use `unwrap_sol_instructions` to close the ATA.
+    pub cleanup_instructions: Vec<Instruction>,
+
+    /// The address of the wrapped SOL token account.
+    pub token_account_address: Pubkey,
+
+    /// A vector of `Keypair` objects representing additional signers required for the instructions.
+    pub additional_signers: Vec<Keypair>,
+}
+
+#[cfg(not(doctest))]
+/// Generates instructions to wrap SOL into a native mint token account.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `owner` - The public key of the owner of the wrapped SOL.
+/// * `lamports` - The amount of lamports to wrap.
+/// * `strategy` - The wrapping strategy to use:
+///   - `Ata` creates the native mint ATA if needed, transfers the lamports and syncs the balance.
+///   - `Keypair` and `Seed` create an auxiliary token account funded with the lamports.
+///
+/// # Returns
+///
+/// A `Result` containing `WrapSolInstructions` on success.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The strategy is `NativeMintWrappingStrategy::None`.
+/// - The RPC requests fail.
+pub async fn wrap_sol_instructions(
+    rpc: &RpcClient,
+    owner: Pubkey,
+    lamports: u64,
+    strategy: NativeMintWrappingStrategy,
+) -> Result<WrapSolInstructions, Box<dyn Error>> {
+    let mut instructions: Vec<Instruction> = Vec::new();
+    let mut cleanup_instructions: Vec<Instruction> = Vec::new();
+    let mut additional_signers: Vec<Keypair> = Vec::new();
+
+    let token_account_address = match strategy {
+        NativeMintWrappingStrategy::Ata => {
+            let ata_address = get_associated_token_address_with_program_id(&owner, &native_mint::ID, &TOKEN_PROGRAM_ID);
+            let ata_account_info = fetch_multiple_accounts(rpc, &[ata_address]).await?;
+            if ata_account_info[0].is_none() {
+                instructions.push(create_associated_token_account(&owner, &owner, &native_mint::ID, &TOKEN_PROGRAM_ID));
+            }
+            instructions.push(transfer(&owner, &ata_address, lamports));
+            instructions.push(sync_native(&TOKEN_PROGRAM_ID, &ata_address)?);
+            ata_address
+        }
+        NativeMintWrappingStrategy::Keypair => {
+            let rent = rpc.get_minimum_balance_for_rent_exemption(Account::LEN).await?;
+            let keypair = Keypair::new();
+            let pubkey = keypair.pubkey();
+            instructions.extend(create_keypair_native_mint_account_instructions(&owner, &pubkey, rent + lamports)?);
+            cleanup_instructions.push(close_account(&TOKEN_PROGRAM_ID, &pubkey, &owner, &owner, &[])?);
+            additional_signers.push(keypair);
+            pubkey
+        }
+        NativeMintWrappingStrategy::Seed => {
+            let rent = rpc.get_minimum_balance_for_rent_exemption(Account::LEN).await?;
+            let (pubkey, create_instructions) = create_seed_native_mint_account_instructions(&owner, rent + lamports)?;
+            instructions.extend(create_instructions);
+            cleanup_instructions.push(close_account(&TOKEN_PROGRAM_ID, &pubkey, &owner, &owner, &[])?);
+            pubkey
+        }
+        NativeMintWrappingStrategy::None => return Err("Native mint wrapping strategy None does not support wrapping SOL".into()),
+    };
+
+    Ok(WrapSolInstructions {
+        instructions,
+        cleanup_instructions,
+        token_account_address,
+        additional_signers,
+    })
+}
+
+#[cfg(not(doctest))]
+/// Generates instructions to unwrap SOL held in the owner's native mint ATA.
+///
+/// The ATA is closed and all of its lamports, including the rent, are returned to the owner.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `owner` - The public key of the owner of the wrapped SOL.
+///
+/// # Returns
+///
+/// A `Result` containing the close account instruction.
+///
+/// # Errors
+///
+/// This function will return an error if the native mint ATA does not exist.
+pub async fn unwrap_sol_instructions(rpc: &RpcClient, owner: Pubkey) -> Result<Vec<Instruction>, Box<dyn Error>> {
+    let ata_address = get_associated_token_address_with_program_id(&owner, &native_mint::ID, &TOKEN_PROGRAM_ID);
+    let ata_account_info = fetch_multiple_accounts(rpc, &[ata_address]).await?;
+    if ata_account_info[0].is_none() {
+        return Err(format!("Native mint token account {} not found", ata_address).into());
+    }
+
+    Ok(vec![close_account(&TOKEN_PROGRAM_ID, &ata_address, &owner, &owner, &[])?])
+}
+
+/// Returns the transfer fee of a Token-2022 mint at the given epoch, or `None` if the mint has no transfer fee.
+///
+/// If `conservative` is set, the fee of the next epoch is taken into account as well and the higher of
+/// both fees is returned. This covers transactions landing after an epoch boundary at which a newer
+/// transfer fee takes effect.
+pub(crate) fn get_current_transfer_fee(mint_account_info: Option<&SolanaAccount>, current_epoch: u64, conservative: bool) -> Option<TransferFee> {
     let token_mint_data = &mint_account_info?.data;
     let token_mint_unpacked = StateWithExtensions::<Mint>::unpack(token_mint_data).ok()?;
 
     if let Ok(transfer_fee_config) = token_mint_unpacked.get_extension::<TransferFeeConfig>() {
         let fee = transfer_fee_config.get_epoch_fee(current_epoch);
-        return Some(TransferFee {
-            fee_bps: fee.transfer_fee_basis_points.into(),
-            max_fee: fee.maximum_fee.into(),
-        });
+        let mut fee_bps: u16 = fee.transfer_fee_basis_points.into();
+        let mut max_fee: u64 = fee.maximum_fee.into();
+        if conservative {
+            let next_fee = transfer_fee_config.get_epoch_fee(current_epoch.saturating_add(1));
+            fee_bps = fee_bps.max(next_fee.transfer_fee_basis_points.into());
+            max_fee = max_fee.max(next_fee.maximum_fee.into());
+        }
+        return Some(TransferFee { fee_bps, max_fee });
     }
 
     None
 }
 
+/// Resolves the extra accounts required by the transfer hook program of a Token-2022 mint.
+///
+/// The extra accounts are read from the validation account of the transfer hook program and
+/// must be passed to the FusionAMM instructions that transfer the token.
+///
+/// # Arguments
+///
+/// * `rpc` - A reference to the Solana RPC client.
+/// * `mint_address` - The public key of the token mint.
+/// * `mint_info` - The mint account.
+/// * `source` - The token account the tokens are transferred from.
+/// * `destination` - The token account the tokens are transferred to.
+/// * `authority` - The authority of the source token account.
+/// * `amount` - The transferred amount.
+///
+/// # Returns
+///
+/// `None` if the mint has no transfer hook program. Otherwise, the resolved extra accounts
+/// followed by the transfer hook program and its validation account.
+pub async fn get_transfer_hook_extra_accounts(
+    rpc: &RpcClient,
+    mint_address: Pubkey,
+    mint_info: &SolanaAccount,
+    source: Pubkey,
+    destination: Pubkey,
+    authority: Pubkey,
+    amount: u64,
+) -> Result<Option<Vec<AccountMeta>>, Box<dyn Error>> {
+    if mint_info.owner != TOKEN_2022_PROGRAM_ID {
+        return Ok(None);
+    }
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_info.data)?;
+    let Some(program_id) = transfer_hook::get_program_id(&mint) else {
+        return Ok(None);
+    };
+
+    let mut instruction =
+        transfer_checked(&TOKEN_2022_PROGRAM_ID, &source, &mint_address, &destination, &authority, &[], amount, mint.base.decimals)?;
+    let transfer_accounts_len = instruction.accounts.len();
+    add_extra_account_metas_for_execute(
+        &mut instruction,
+        &program_id,
+        &source,
+        &mint_address,
+        &destination,
+        &authority,
+        amount,
+        |address| async move {
+            let accounts = fetch_multiple_accounts(rpc, &[address]).await.map_err(|e| e.to_string())?;
+            Ok(accounts.into_iter().next().flatten().map(|account| account.data))
+        },
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(Some(instruction.accounts.split_off(transfer_accounts_len)))
+}
+
+/// Collects the remaining accounts of an instruction along with the slices that describe them.
+#[derive(Debug, Default)]
+pub(crate) struct RemainingAccounts {
+    pub slices: Vec<RemainingAccountsSlice>,
+    pub accounts: Vec<AccountMeta>,
+}
+
+impl RemainingAccounts {
+    /// Appends the accounts as a new slice. Does nothing if the accounts are not provided.
+    pub fn add(&mut self, accounts_type: AccountsType, accounts: Option<Vec<AccountMeta>>) {
+        if let Some(accounts) = accounts {
+            self.slices.push(RemainingAccountsSlice {
+                accounts_type,
+                length: accounts.len() as u8,
+            });
+            self.accounts.extend(accounts);
+        }
+    }
+
+    pub fn info(&self) -> Option<RemainingAccountsInfo> {
+        if self.slices.is_empty() {
+            None
+        } else {
+            Some(RemainingAccountsInfo { slices: self.slices.clone() })
+        }
+    }
+}
+
 /// Orders two mint addresses by their canonical byte order.
 ///
 /// This function compares two Solana `Pubkey` values and returns an array where the first element
@@ -232,11 +479,18 @@ pub(crate) fn get_current_transfer_fee(mint_account_info: Option<&SolanaAccount>
 /// # Returns
 ///
 /// An array `[Pubkey, Pubkey]` where the first element is the smaller mint address and the second is the larger.
-pub fn order_mints(mint1: Pubkey, mint2: Pubkey) -> [Pubkey; 2] {
+///
+/// # Errors
+///
+/// Returns an error if both mint addresses are the same.
+pub fn order_mints(mint1: Pubkey, mint2: Pubkey) -> Result<[Pubkey; 2], Box<dyn Error>> {
+    if mint1 == mint2 {
+        return Err(format!("Token mints must be different, got {} twice", mint1).into());
+    }
     if mint1.lt(&mint2) {
-        [mint1, mint2]
+        Ok([mint1, mint2])
     } else {
-        [mint2, mint1]
+        Ok([mint2, mint1])
     }
 }
 
@@ -264,8 +518,16 @@ pub fn get_account_data_size(_token_program_id: Pubkey, mint_info: &SolanaAccoun
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tests::{setup_ata, setup_ata_te, setup_ata_with_amount, setup_mint, setup_mint_te, setup_mint_te_fee, RpcContext};
+    use crate::tests::{
+        setup_ata, setup_ata_te, setup_ata_with_amount, setup_fusion_pool, setup_mint, setup_mint_te, setup_mint_te_fee, setup_position,
+        setup_transfer_hook_validation_account, RpcContext, SetupAtaConfig, TEST_TRANSFER_HOOK_PROGRAM_ID,
+    };
+    use crate::{increase_liquidity_instructions, IncreaseLiquidityParam};
+    use fusionamm_client::{
+        decode_instruction, get_fusion_pools_config_address, get_token_badge_address, DecodedFusionInstruction, InitializeTokenBadge,
+    };
     use serial_test::serial;
+    use spl_transfer_hook_interface::get_extra_account_metas_address;
 
     use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
 
@@ -277,13 +539,141 @@ mod tests {
         let mint1 = Pubkey::from_str("Jd4M8bfJG3sAkd82RsGWyEXoaBXQP7njFzBwEaCTuDa").unwrap();
         let mint2 = Pubkey::from_str("BRjpCHtyQLNCo8gqRUr8jtdAj5AjPYQaoqbvcZiHok1k").unwrap();
 
-        let [mint_a, mint_b] = order_mints(mint1, mint2);
+        let [mint_a, mint_b] = order_mints(mint1, mint2).unwrap();
         assert_eq!(mint_a, mint1);
         assert_eq!(mint_b, mint2);
 
-        let [mint_c, mint_d] = order_mints(mint2, mint1);
+        let [mint_c, mint_d] = order_mints(mint2, mint1).unwrap();
         assert_eq!(mint_c, mint1);
         assert_eq!(mint_d, mint2);
+
+        assert!(order_mints(mint1, mint1).is_err());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_transfer_hook_extra_accounts() {
+        let ctx = RpcContext::new().await;
+        let mint = setup_mint_te(&ctx, &[ExtensionType::TransferHook]).await.unwrap();
+
+        // The validation account of the transfer hook program requires one extra writable account.
+        let extra_account = setup_transfer_hook_validation_account(&ctx, mint).await.unwrap();
+        let validation_address = get_extra_account_metas_address(&mint, &TEST_TRANSFER_HOOK_PROGRAM_ID);
+
+        let mint_info = ctx.rpc.get_account(&mint).await.unwrap();
+        let extra_accounts =
+            get_transfer_hook_extra_accounts(&ctx.rpc, mint, &mint_info, Pubkey::new_unique(), Pubkey::new_unique(), ctx.signer.pubkey(), 1_000)
+                .await
+                .unwrap();
+        let expected_accounts = vec![
+            AccountMeta::new(extra_account, false),
+            AccountMeta::new_readonly(TEST_TRANSFER_HOOK_PROGRAM_ID, false),
+            AccountMeta::new_readonly(validation_address, false),
+        ];
+        assert_eq!(extra_accounts, Some(expected_accounts.clone()));
+
+        let mut remaining_accounts = RemainingAccounts::default();
+        remaining_accounts.add(AccountsType::TransferHookA, extra_accounts);
+        remaining_accounts.add(AccountsType::TransferHookB, None);
+        assert_eq!(remaining_accounts.accounts, expected_accounts);
+        assert_eq!(
+            remaining_accounts.info().unwrap().slices,
+            vec![RemainingAccountsSlice {
+                accounts_type: AccountsType::TransferHookA,
+                length: 3,
+            }]
+        );
+
+        // Mints without the transfer hook extension don't require extra accounts.
+        let fee_mint = setup_mint_te_fee(&ctx).await.unwrap();
+        let fee_mint_info = ctx.rpc.get_account(&fee_mint).await.unwrap();
+        let extra_accounts = get_transfer_hook_extra_accounts(
+            &ctx.rpc,
+            fee_mint,
+            &fee_mint_info,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            ctx.signer.pubkey(),
+            1_000,
+        )
+        .await
+        .unwrap();
+        assert_eq!(extra_accounts, None);
+        assert_eq!(RemainingAccounts::default().info(), None);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_transfer_hook_extra_accounts_in_increase_liquidity() {
+        let ctx = RpcContext::new().await;
+        let mint_hook = setup_mint_te(&ctx, &[ExtensionType::TransferHook]).await.unwrap();
+        let mint_other = setup_mint(&ctx).await.unwrap();
+        let extra_account = setup_transfer_hook_validation_account(&ctx, mint_hook).await.unwrap();
+        let validation_address = get_extra_account_metas_address(&mint_hook, &TEST_TRANSFER_HOOK_PROGRAM_ID);
+        setup_ata_te(&ctx, mint_hook, Some(SetupAtaConfig { amount: Some(1_000_000_000) }))
+            .await
+            .unwrap();
+        setup_ata_with_amount(&ctx, mint_other, 1_000_000_000).await.unwrap();
+
+        // Mints with a transfer hook require a token badge.
+        ctx.send_transaction(vec![InitializeTokenBadge {
+            fusion_pools_config: get_fusion_pools_config_address().unwrap().0,
+            token_badge_authority: ctx.signer.pubkey(),
+            token_mint: mint_hook,
+            token_badge: get_token_badge_address(&mint_hook).unwrap().0,
+            funder: ctx.signer.pubkey(),
+            system_program: solana_program::system_program::id(),
+        }
+        .instruction()])
+            .await
+            .unwrap();
+
+        let [mint_a, mint_b] = order_mints(mint_hook, mint_other).unwrap();
+        let pool_address = setup_fusion_pool(&ctx, mint_a, mint_b, 64, 300).await.unwrap();
+        let position_mint = setup_position(&ctx, pool_address, Some((-128, 128)), None).await.unwrap();
+
+        // The hook program can't execute the transfer, so the instruction is only inspected.
+        let increase_ix = increase_liquidity_instructions(
+            &ctx.rpc,
+            position_mint,
+            IncreaseLiquidityParam::Liquidity(1_000_000),
+            Some(100),
+            Some(ctx.signer.pubkey()),
+        )
+        .await
+        .unwrap();
+        let increase_ix = increase_ix
+            .instructions
+            .iter()
+            .find(|ix| {
+                let accounts: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+                matches!(decode_instruction(&ix.data, &accounts), Some(DecodedFusionInstruction::IncreaseLiquidity { .. }))
+            })
+            .unwrap();
+        let accounts: Vec<Pubkey> = increase_ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        let Some(DecodedFusionInstruction::IncreaseLiquidity { args, .. }) = decode_instruction(&increase_ix.data, &accounts) else {
+            unreachable!();
+        };
+
+        let accounts_type = if mint_a == mint_hook {
+            AccountsType::TransferHookA
+        } else {
+            AccountsType::TransferHookB
+        };
+        assert_eq!(
+            args.remaining_accounts_info,
+            Some(RemainingAccountsInfo {
+                slices: vec![RemainingAccountsSlice { accounts_type, length: 3 }],
+            })
+        );
+        assert_eq!(
+            increase_ix.accounts[increase_ix.accounts.len() - 3..],
+            [
+                AccountMeta::new(extra_account, false),
+                AccountMeta::new_readonly(TEST_TRANSFER_HOOK_PROGRAM_ID, false),
+                AccountMeta::new_readonly(validation_address, false),
+            ]
+        );
     }
 
     // 2. Regular Token Tests
@@ -524,6 +914,18 @@ mod tests {
         assert_eq!(token_account.amount, amount);
     }
 
+    #[test]
+    fn test_seed_wrapped_sol_address() {
+        let owner = Pubkey::new_unique();
+        let (pubkey, instructions) = create_seed_native_mint_account_instructions(&owner, 1_000_000).unwrap();
+        // CreateAccountWithSeed data: u32 discriminator, base pubkey, u64 seed length, seed bytes.
+        let seed_len = u64::from_le_bytes(instructions[0].data[36..44].try_into().unwrap()) as usize;
+        let seed = String::from_utf8(instructions[0].data[44..44 + seed_len].to_vec()).unwrap();
+        assert_eq!(get_seed_wrapped_sol_address(&owner, &seed).unwrap(), pubkey);
+        assert_eq!(instructions[0].accounts[1].pubkey, pubkey);
+        assert!(get_seed_wrapped_sol_address(&owner, &"a".repeat(33)).is_err());
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_native_token_balance() -> Result<(), Box<dyn Error>> {
@@ -554,22 +956,48 @@ mod tests {
         let mint_account = ctx.rpc.get_account(&mint_te).await?;
 
         // Test transfer fee at epoch 0
-        let older = get_current_transfer_fee(Some(&mint_account), 0).unwrap();
+        let older = get_current_transfer_fee(Some(&mint_account), 0, false).unwrap();
         assert_eq!(older.fee_bps, 100); // 1%
         assert_eq!(older.max_fee, 1_000_000_000); // 1 token
 
         // Test transfer fee at epoch 2
-        let newer = get_current_transfer_fee(Some(&mint_account), 2).unwrap();
+        let newer = get_current_transfer_fee(Some(&mint_account), 2, false).unwrap();
         assert_eq!(newer.fee_bps, 150); // 1.5%
         assert_eq!(newer.max_fee, 1_000_000_000); // 1 token
 
         // Test with no fee
-        let no_fee_result = get_current_transfer_fee(None, 0);
+        let no_fee_result = get_current_transfer_fee(None, 0, false);
         assert!(no_fee_result.is_none());
 
         Ok(())
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_conservative_transfer_fee() -> Result<(), Box<dyn Error>> {
+        let ctx = RpcContext::new().await;
+
+        // The newer transfer fee of 1.5% takes effect at the epoch after the current one
+        let mint_te = setup_mint_te_fee(&ctx).await?;
+        let mint_account = ctx.rpc.get_account(&mint_te).await?;
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_account.data)?;
+        let newer_fee_epoch: u64 = mint.get_extension::<TransferFeeConfig>()?.newer_transfer_fee.epoch.into();
+        let current_epoch = newer_fee_epoch - 1;
+
+        let fee = get_current_transfer_fee(Some(&mint_account), current_epoch, false).unwrap();
+        assert_eq!(fee.fee_bps, 100);
+
+        let conservative_fee = get_current_transfer_fee(Some(&mint_account), current_epoch, true).unwrap();
+        assert_eq!(conservative_fee.fee_bps, 150);
+        assert_eq!(conservative_fee.max_fee, 1_000_000_000);
+
+        // Once the newer fee is in effect, the conservative estimate matches the current fee
+        let conservative_fee = get_current_transfer_fee(Some(&mint_account), newer_fee_epoch, true).unwrap();
+        assert_eq!(conservative_fee.fee_bps, 150);
+
+        Ok(())
+    }
+
     // 5. Mixed Token Types Test
     #[tokio::test]
     #[serial]
@@ -617,4 +1045,117 @@ mod tests {
         assert_eq!(accounts[1].as_ref().unwrap().owner, TOKEN_PROGRAM_ID); // Regular
         assert_eq!(accounts[2].as_ref().unwrap().owner, TOKEN_2022_PROGRAM_ID); // Token-2022
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_wrap_and_unwrap_sol_ata() {
+        let ctx = RpcContext::new().await;
+        let owner = ctx.signer.pubkey();
+        let amount = 1_000_000u64;
+        let ata = get_associated_token_address_with_program_id(&owner, &native_mint::ID, &TOKEN_PROGRAM_ID);
+
+        let result = wrap_sol_instructions(&ctx.rpc, owner, amount, NativeMintWrappingStrategy::Ata)
+            .await
+            .unwrap();
+        assert_eq!(result.token_account_address, ata);
+        assert_eq!(
+            result.instructions,
+            vec![
+                create_associated_token_account(&owner, &owner, &native_mint::ID, &TOKEN_PROGRAM_ID),
+                transfer(&owner, &ata, amount),
+                sync_native(&TOKEN_PROGRAM_ID, &ata).unwrap(),
+            ]
+        );
+        assert!(result.cleanup_instructions.is_empty());
+        assert!(result.additional_signers.is_empty());
+
+        ctx.send_transaction(result.instructions).await.unwrap();
+        let account = ctx.rpc.get_account(&ata).await.unwrap();
+        assert_eq!(Account::unpack(&account.data).unwrap().amount, amount);
+
+        // The existing ATA is topped up without being recreated.
+        let result = wrap_sol_instructions(&ctx.rpc, owner, amount, NativeMintWrappingStrategy::Ata)
+            .await
+            .unwrap();
+        assert_eq!(result.instructions, vec![transfer(&owner, &ata, amount), sync_native(&TOKEN_PROGRAM_ID, &ata).unwrap()]);
+        ctx.send_transaction(result.instructions).await.unwrap();
+        let account = ctx.rpc.get_account(&ata).await.unwrap();
+        assert_eq!(Account::unpack(&account.data).unwrap().amount, amount * 2);
+
+        let instructions = unwrap_sol_instructions(&ctx.rpc, owner).await.unwrap();
+        assert_eq!(instructions, vec![close_account(&TOKEN_PROGRAM_ID, &ata, &owner, &owner, &[]).unwrap()]);
+        ctx.send_transaction(instructions).await.unwrap();
+        let accounts = ctx.rpc.get_multiple_accounts(&[ata]).await.unwrap();
+        assert!(accounts[0].is_none());
+
+        assert!(unwrap_sol_instructions(&ctx.rpc, owner).await.is_err());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_wrap_sol_keypair() {
+        let ctx = RpcContext::new().await;
+        let owner = ctx.signer.pubkey();
+        let amount = 1_000_000u64;
+        let rent = ctx.rpc.get_minimum_balance_for_rent_exemption(Account::LEN).await.unwrap();
+
+        let result = wrap_sol_instructions(&ctx.rpc, owner, amount, NativeMintWrappingStrategy::Keypair)
+            .await
+            .unwrap();
+        assert_eq!(result.additional_signers.len(), 1);
+        let token_address = result.additional_signers[0].pubkey();
+        assert_eq!(result.token_account_address, token_address);
+        assert_eq!(
+            result.instructions,
+            vec![
+                create_account(&owner, &token_address, rent + amount, Account::LEN as u64, &TOKEN_PROGRAM_ID),
+                initialize_account3(&TOKEN_PROGRAM_ID, &token_address, &native_mint::ID, &owner).unwrap(),
+            ]
+        );
+        assert_eq!(result.cleanup_instructions, vec![close_account(&TOKEN_PROGRAM_ID, &token_address, &owner, &owner, &[]).unwrap()]);
+
+        ctx.send_transaction_with_signers(result.instructions, result.additional_signers.iter().collect())
+            .await
+            .unwrap();
+        let account = ctx.rpc.get_account(&token_address).await.unwrap();
+        assert_eq!(Account::unpack(&account.data).unwrap().amount, amount);
+
+        ctx.send_transaction(result.cleanup_instructions).await.unwrap();
+        let accounts = ctx.rpc.get_multiple_accounts(&[token_address]).await.unwrap();
+        assert!(accounts[0].is_none());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_wrap_sol_seed() {
+        let ctx = RpcContext::new().await;
+        let owner = ctx.signer.pubkey();
+        let amount = 1_000_000u64;
+
+        let result = wrap_sol_instructions(&ctx.rpc, owner, amount, NativeMintWrappingStrategy::Seed)
+            .await
+            .unwrap();
+        let token_address = result.token_account_address;
+        assert!(result.additional_signers.is_empty());
+        assert_eq!(result.instructions.len(), 2);
+        assert_eq!(result.instructions[0].program_id, solana_program::system_program::ID);
+        assert_eq!(result.instructions[1], initialize_account3(&TOKEN_PROGRAM_ID, &token_address, &native_mint::ID, &owner).unwrap());
+        assert_eq!(result.cleanup_instructions, vec![close_account(&TOKEN_PROGRAM_ID, &token_address, &owner, &owner, &[]).unwrap()]);
+
+        ctx.send_transaction(result.instructions).await.unwrap();
+        let account = ctx.rpc.get_account(&token_address).await.unwrap();
+        assert_eq!(Account::unpack(&account.data).unwrap().amount, amount);
+
+        ctx.send_transaction(result.cleanup_instructions).await.unwrap();
+        let accounts = ctx.rpc.get_multiple_accounts(&[token_address]).await.unwrap();
+        assert!(accounts[0].is_none());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_wrap_sol_none() {
+        let ctx = RpcContext::new().await;
+        let result = wrap_sol_instructions(&ctx.rpc, ctx.signer.pubkey(), 1_000_000, NativeMintWrappingStrategy::None).await;
+        assert!(result.is_err());
+    }
 }
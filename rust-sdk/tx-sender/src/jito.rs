@@ -17,12 +17,20 @@ use std::time::Duration;
 use tokio::task::JoinHandle;
 use tokio::time::sleep;
 use tokio_tungstenite::connect_async;
-use tokio_tungstenite::tungstenite::client::IntoClientRequest;
 use tokio_tungstenite::tungstenite::Message;
 
 pub const MIN_JITO_TIP_LAMPORTS: u64 = 1000;
 pub const MIN_JITO_TIP_SOL: f64 = MIN_JITO_TIP_LAMPORTS as f64 / LAMPORTS_PER_SOL as f64;
 
+/// Timeout of the requests to the Jito Block Engine API
+pub const JITO_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Maximum number of idle connections to the Jito Block Engine API
+pub const JITO_POOL_MAX_IDLE: usize = 4;
+
+/// Jito tip stream WebSocket URL
+pub const JITO_TIP_STREAM_URL: &str = "wss://bundles.jito.wtf/api/v1/bundles/tip_stream";
+const JITO_TIP_STREAM_RECONNECT_DELAY: Duration = Duration::from_millis(500);
+
 /// Jito tip accounts
 pub const JITO_TIP_ACCOUNTS: [&str; 8] = [
     "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
@@ -132,7 +140,30 @@ pub async fn get_bundle_statuses(client: Client, bundle_ids: Vec<String>, jito_a
     Ok(response)
 }
 
+/// Starts streaming Jito tips from the default tip stream URL.
+/// The task panics after 5 consecutive connection attempts without receiving tips.
+/// Use `start_jito_tips_stream_with_config` to receive the error instead.
 pub fn start_jito_tips_stream<F, Fut>(on_update: F) -> JoinHandle<()>
+where
+    F: Fn(JitoTipInfo) -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = ()> + Send + 'static,
+{
+    let stream = start_jito_tips_stream_with_config(JITO_TIP_STREAM_URL.to_string(), Some(5), on_update);
+    tokio::spawn(async move {
+        if let Err(e) = stream.await.expect("Jito tip stream task failed") {
+            panic!("{}", e);
+        }
+    })
+}
+
+/// Starts streaming Jito tips from the provided WebSocket URL and reconnects if the connection is lost.
+///
+/// # Arguments
+/// * `ws_url` - The Jito tip stream WebSocket URL
+/// * `max_reconnect_attempts` - The number of consecutive connection attempts without receiving tips
+///   after which the task finishes with an error. `None` reconnects forever.
+/// * `on_update` - The callback invoked with every received tip
+pub fn start_jito_tips_stream_with_config<F, Fut>(ws_url: String, max_reconnect_attempts: Option<u32>, on_update: F) -> JoinHandle<Result<()>>
 where
     F: Fn(JitoTipInfo) -> Fut + Send + 'static,
     Fut: std::future::Future<Output = ()> + Send + 'static,
@@ -141,31 +172,41 @@ where
         let mut connect_attempts = 0;
         loop {
             connect_attempts += 1;
-            let request = "wss://bundles.jito.wtf/api/v1/bundles/tip_stream".into_client_request().unwrap();
 
-            let (ws_stream, _) = connect_async(request).await.expect("Failed to connect");
-            info!(target: "log", "Connected to Jito tip stream");
+            match connect_async(ws_url.as_str()).await {
+                Ok((ws_stream, _)) => {
+                    info!(target: "log", "Connected to Jito tip stream");
 
-            let (_, mut read) = ws_stream.split();
+                    let (_, mut read) = ws_stream.split();
 
-            while let Some(msg) = read.next().await {
-                match msg {
-                    Ok(Message::Text(text)) => {
-                        //info!(target: "log", "Received: {}", text);
-                        let tips = serde_json::from_slice::<Vec<JitoTipInfo>>(text.as_bytes()).expect("Failed to parse Jito tip stream");
-                        if !tips.is_empty() {
-                            connect_attempts = 0;
-                            on_update(tips[0].clone()).await;
+                    while let Some(msg) = read.next().await {
+                        match msg {
+                            Ok(Message::Text(text)) => match serde_json::from_slice::<Vec<JitoTipInfo>>(text.as_bytes()) {
+                                Ok(tips) => {
+                                    if !tips.is_empty() {
+                                        connect_attempts = 0;
+                                        on_update(tips[0].clone()).await;
+                                    }
+                                }
+                                Err(e) => error!(target: "log", "Failed to parse Jito tip stream message: {}", e),
+                            },
+                            Ok(_) => {}
+                            Err(e) => error!(target: "log", "Jito tip stream webSocket error: {}", e),
                         }
                     }
-                    Ok(_) => {}
-                    Err(e) => error!(target: "log", "Jito tip stream webSocket error: {}", e),
+
+                    warn!(target: "log", "Jito tip stream disconnected");
                 }
+                Err(e) => error!(target: "log", "Failed to connect to Jito tip stream: {}", e),
             }
 
-            if connect_attempts >= 5 {
-                panic!("Failed to connect to Jito tip stream after 5 attempts");
+            if let Some(max_reconnect_attempts) = max_reconnect_attempts {
+                if connect_attempts >= max_reconnect_attempts {
+                    return Err(anyhow!("Failed to connect to Jito tip stream after {} attempts", connect_attempts));
+                }
             }
+
+            sleep(JITO_TIP_STREAM_RECONNECT_DELAY).await;
         }
     })
 }
@@ -184,3 +225,52 @@ pub fn get_jito_api_url_by_region(region: &str) -> String {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::SinkExt;
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+    use tokio_tungstenite::accept_async;
+
+    fn tip_message(time: &str) -> Message {
+        Message::text(format!(
+            r#"[{{"time":"{}","landed_tips_25th_percentile":0.1,"landed_tips_50th_percentile":0.2,"landed_tips_75th_percentile":0.3,"landed_tips_95th_percentile":0.4,"landed_tips_99th_percentile":0.5,"ema_landed_tips_50th_percentile":0.6}}]"#,
+            time
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_jito_tips_stream_reconnects() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let ws_url = format!("ws://{}", listener.local_addr().unwrap());
+
+        // Each connection delivers one tip and is closed by the server. The listener is dropped afterwards.
+        let server = tokio::spawn(async move {
+            for time in ["first", "second"] {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut ws_stream = accept_async(stream).await.unwrap();
+                ws_stream.send(tip_message(time)).await.unwrap();
+                ws_stream.close(None).await.unwrap();
+            }
+        });
+
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let handle = start_jito_tips_stream_with_config(ws_url, Some(2), move |tip| {
+            let sender = sender.clone();
+            async move {
+                sender.send(tip).unwrap();
+            }
+        });
+
+        assert_eq!(receiver.recv().await.unwrap().time, "first");
+        let tip = receiver.recv().await.unwrap();
+        assert_eq!(tip.time, "second");
+        assert_eq!(tip.landed_tips_50th_percentile, 0.2);
+        server.await.unwrap();
+
+        // The server is gone, so the stream gives up after the configured number of attempts.
+        assert!(handle.await.unwrap().is_err());
+    }
+}
@@ -5,9 +5,14 @@
 // See the LICENSE file in the project root for license information.
 //
 
+use serde_json::json;
 use solana_client::client_error::ClientError;
 use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_pubkey::Pubkey;
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_response::RpcPrioritizationFee;
+use solana_commitment_config::CommitmentConfig;
+use solana_program::sysvar;
+use solana_pubkey::{pubkey, Pubkey};
 use std::collections::HashMap;
 
 #[derive(Copy, Clone, PartialEq)]
@@ -20,22 +25,97 @@ pub enum PriorityFeeLevel {
     Ultimate,
 }
 
+/// The number of most recent fees aggregated into one percentile sample.
+pub const DEFAULT_PRIORITY_FEE_CHUNK_SIZE: usize = 150;
+/// The number of chunks averaged by the priority fee estimation.
+pub const DEFAULT_PRIORITY_FEE_NUM_CHUNKS: usize = 3;
+
+/// Well-known program IDs and sysvars. They are used by most transactions, so their recent prioritization fees
+/// don't reflect the contention of the accounts written by a transaction.
+pub const PRIORITY_FEE_IGNORED_ACCOUNTS: [Pubkey; 12] = [
+    solana_system_interface::program::ID,
+    solana_compute_budget_interface::ID,
+    pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
+    pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"),
+    pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"),
+    pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr"),
+    sysvar::clock::ID,
+    sysvar::epoch_schedule::ID,
+    sysvar::instructions::ID,
+    sysvar::recent_blockhashes::ID,
+    sysvar::rent::ID,
+    sysvar::slot_hashes::ID,
+];
+
+/// Removes the well-known program IDs and sysvars listed in `PRIORITY_FEE_IGNORED_ACCOUNTS` from the accounts
+/// used to estimate the priority fee.
+pub fn filter_priority_fee_accounts(addresses: Vec<Pubkey>) -> Vec<Pubkey> {
+    addresses
+        .into_iter()
+        .filter(|address| !PRIORITY_FEE_IGNORED_ACCOUNTS.contains(address))
+        .collect()
+}
+
+/// Estimates the priority fee from the recent prioritization fees of the given accounts.
+///
+/// If a commitment is provided, it's sent with the `getRecentPrioritizationFees` request, and only the fees of the slots
+/// that have reached it are used, since not every RPC node honors the commitment of this request.
+/// Otherwise, all fees returned by the RPC node are used.
 #[allow(clippy::result_large_err)]
-pub async fn get_priority_fee_estimate(client: &RpcClient, addresses: Vec<Pubkey>, level: PriorityFeeLevel) -> Result<u64, ClientError> {
-    let recent_prioritization_fees = client.get_recent_prioritization_fees(&addresses).await?;
-    if recent_prioritization_fees.is_empty() {
-        return Ok(0);
-    }
-    let mut sorted_fees: Vec<_> = recent_prioritization_fees.into_iter().collect();
-    sorted_fees.sort_by(|a, b| b.slot.cmp(&a.slot));
-    let chunk_size = 150;
-    let chunks: Vec<_> = sorted_fees.chunks(chunk_size).take(3).collect();
-    let mut percentiles: HashMap<u8, u64> = HashMap::new();
-    for chunk in chunks.iter() {
-        let fees: Vec<u64> = chunk.iter().map(|fee| fee.prioritization_fee).collect();
-        percentiles = calculate_percentiles(&fees);
+pub async fn get_priority_fee_estimate(
+    client: &RpcClient,
+    addresses: Vec<Pubkey>,
+    level: PriorityFeeLevel,
+    commitment: Option<CommitmentConfig>,
+) -> Result<u64, ClientError> {
+    get_priority_fee_estimate_with_sampling(client, addresses, level, commitment, DEFAULT_PRIORITY_FEE_NUM_CHUNKS, DEFAULT_PRIORITY_FEE_CHUNK_SIZE)
+        .await
+}
+
+/// Estimates the priority fee using the most recent `num_chunks * chunk_size` fees.
+///
+/// The fees are split by slot (most recent first) into chunks of `chunk_size`, percentiles are computed
+/// for each chunk and the requested percentile is averaged across all chunks.
+#[allow(clippy::result_large_err)]
+pub async fn get_priority_fee_estimate_with_sampling(
+    client: &RpcClient,
+    addresses: Vec<Pubkey>,
+    level: PriorityFeeLevel,
+    commitment: Option<CommitmentConfig>,
+    num_chunks: usize,
+    chunk_size: usize,
+) -> Result<u64, ClientError> {
+    let recent_prioritization_fees = get_recent_prioritization_fees(client, &addresses, commitment).await?;
+    Ok(estimate_priority_fee(recent_prioritization_fees, level, num_chunks, chunk_size))
+}
+
+/// Fetches the recent prioritization fees at the given commitment and drops the fees of the slots above
+/// the current slot at that commitment.
+#[allow(clippy::result_large_err)]
+async fn get_recent_prioritization_fees(
+    client: &RpcClient,
+    addresses: &[Pubkey],
+    commitment: Option<CommitmentConfig>,
+) -> Result<Vec<RpcPrioritizationFee>, ClientError> {
+    match commitment {
+        Some(commitment) => {
+            let addresses: Vec<String> = addresses.iter().map(|address| address.to_string()).collect();
+            let recent_prioritization_fees: Vec<RpcPrioritizationFee> = client
+                .send(RpcRequest::GetRecentPrioritizationFees, json!([addresses, { "commitment": commitment.commitment }]))
+                .await?;
+            let slot = client.get_slot_with_commitment(commitment).await?;
+            Ok(recent_prioritization_fees.into_iter().filter(|fee| fee.slot <= slot).collect())
+        }
+        None => client.get_recent_prioritization_fees(addresses).await,
     }
+}
 
+fn estimate_priority_fee(
+    recent_prioritization_fees: Vec<RpcPrioritizationFee>,
+    level: PriorityFeeLevel,
+    num_chunks: usize,
+    chunk_size: usize,
+) -> u64 {
     let percentile = match level {
         PriorityFeeLevel::None => 0,
         PriorityFeeLevel::Low => 70,
@@ -45,20 +125,29 @@ pub async fn get_priority_fee_estimate(client: &RpcClient, addresses: Vec<Pubkey
         PriorityFeeLevel::Ultimate => 95,
     };
 
-    let fee = if percentile == 0 {
-        0
-    } else {
-        *percentiles.get(&percentile).unwrap_or(&0)
-    };
+    if percentile == 0 || recent_prioritization_fees.is_empty() || num_chunks == 0 || chunk_size == 0 {
+        return 0;
+    }
+
+    let mut sorted_fees = recent_prioritization_fees;
+    sorted_fees.sort_by(|a, b| b.slot.cmp(&a.slot));
+    let chunks: Vec<_> = sorted_fees.chunks(chunk_size).take(num_chunks).collect();
 
-    Ok(fee)
+    let mut total: u128 = 0;
+    for chunk in chunks.iter() {
+        let fees: Vec<u64> = chunk.iter().map(|fee| fee.prioritization_fee).collect();
+        let percentiles = calculate_percentiles(&fees);
+        total += *percentiles.get(&percentile).unwrap_or(&0) as u128;
+    }
+
+    (total / chunks.len() as u128) as u64
 }
 
 fn calculate_percentiles(fees: &[u64]) -> HashMap<u8, u64> {
     let mut sorted_fees = fees.to_vec();
     sorted_fees.sort_unstable();
     let len = sorted_fees.len();
-    let percentiles = vec![10, 25, 50, 60, 70, 75, 80, 85, 90, 100];
+    let percentiles = vec![10, 25, 50, 60, 70, 75, 80, 85, 90, 95, 100];
     percentiles
         .into_iter()
         .map(|p| {
@@ -67,3 +156,100 @@ fn calculate_percentiles(fees: &[u64]) -> HashMap<u8, u64> {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use serde_json::Value;
+    use solana_client::client_error::Result as ClientResult;
+    use solana_client::rpc_client::RpcClientConfig;
+    use solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+    use std::sync::{Arc, Mutex};
+
+    /// Records the requests and answers with the prioritization fees of slots 1 to 3 and a current slot of 2.
+    struct RecordingRpcSender {
+        requests: Arc<Mutex<Vec<(RpcRequest, Value)>>>,
+    }
+
+    #[async_trait]
+    impl RpcSender for RecordingRpcSender {
+        async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+            self.requests.lock().unwrap().push((request, params));
+            match request {
+                RpcRequest::GetSlot => Ok(json!(2)),
+                _ => Ok(json!([
+                    { "slot": 1, "prioritizationFee": 1000 },
+                    { "slot": 2, "prioritizationFee": 1000 },
+                    { "slot": 3, "prioritizationFee": 5000 },
+                ])),
+            }
+        }
+
+        fn get_transport_stats(&self) -> RpcTransportStats {
+            RpcTransportStats::default()
+        }
+
+        fn url(&self) -> String {
+            "recording".to_string()
+        }
+    }
+
+    fn test_fees(slots: std::ops::Range<u64>, fee: impl Fn(u64) -> u64) -> Vec<RpcPrioritizationFee> {
+        slots
+            .map(|slot| RpcPrioritizationFee {
+                slot,
+                prioritization_fee: fee(slot),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_estimate_priority_fee_averages_chunks() {
+        // Three chunks of 150 slots with constant fees of 3000, 2000 and 1000 (most recent first),
+        // preceded by older slots which must be ignored.
+        let mut fees = test_fees(0..150, |_| 1_000_000);
+        fees.extend(test_fees(150..300, |_| 1000));
+        fees.extend(test_fees(300..450, |_| 2000));
+        fees.extend(test_fees(450..600, |_| 3000));
+
+        assert_eq!(estimate_priority_fee(fees.clone(), PriorityFeeLevel::Medium, 3, 150), 2000);
+        assert_eq!(estimate_priority_fee(fees.clone(), PriorityFeeLevel::Medium, 1, 150), 3000);
+        assert_eq!(estimate_priority_fee(fees.clone(), PriorityFeeLevel::Medium, 2, 150), 2500);
+        assert_eq!(estimate_priority_fee(fees.clone(), PriorityFeeLevel::Medium, 4, 150), 251_500);
+        assert_eq!(estimate_priority_fee(fees.clone(), PriorityFeeLevel::None, 3, 150), 0);
+    }
+
+    #[test]
+    fn test_estimate_priority_fee_percentiles() {
+        let fees = test_fees(0..100, |slot| slot + 1);
+        assert_eq!(estimate_priority_fee(fees.clone(), PriorityFeeLevel::Low, 1, 100), 70);
+        assert_eq!(estimate_priority_fee(fees.clone(), PriorityFeeLevel::High, 1, 100), 80);
+        assert_eq!(estimate_priority_fee(fees.clone(), PriorityFeeLevel::Ultimate, 1, 100), 95);
+        assert_eq!(estimate_priority_fee(vec![], PriorityFeeLevel::Ultimate, 1, 100), 0);
+    }
+
+    #[tokio::test]
+    async fn test_priority_fee_estimate_commitment() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let client = RpcClient::new_sender(RecordingRpcSender { requests: requests.clone() }, RpcClientConfig::default());
+        let address = Pubkey::new_unique();
+
+        // The fee of slot 3 is ignored because the slot hasn't reached the commitment yet.
+        let fee = get_priority_fee_estimate(&client, vec![address], PriorityFeeLevel::Ultimate, Some(CommitmentConfig::confirmed()))
+            .await
+            .unwrap();
+        assert_eq!(fee, 1000);
+
+        let fee = get_priority_fee_estimate(&client, vec![address], PriorityFeeLevel::Ultimate, None)
+            .await
+            .unwrap();
+        assert_eq!(fee, 5000);
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0], (RpcRequest::GetRecentPrioritizationFees, json!([[address.to_string()], { "commitment": "confirmed" }])));
+        assert_eq!(requests[1], (RpcRequest::GetSlot, json!([{ "commitment": "confirmed" }])));
+        assert_eq!(requests[2], (RpcRequest::GetRecentPrioritizationFees, json!([[address.to_string()]])));
+    }
+}
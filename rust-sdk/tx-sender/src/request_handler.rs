@@ -11,6 +11,7 @@ use reqwest::{Client, Method, RequestBuilder, Response, StatusCode, Url};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::fmt::Debug;
+use std::time::Duration;
 
 /// Manages HTTP requests for the `Helius` client
 ///
@@ -30,6 +31,23 @@ impl RequestHandler {
         Ok(Self { http_client: client.clone() })
     }
 
+    /// Creates a new instance of `RequestHandler` with its own `reqwest::Client`
+    ///
+    /// # Arguments
+    /// * `timeout` - The timeout for connecting to the server and for the whole request
+    /// * `pool_max_idle` - The maximum number of idle connections kept open per host
+    ///
+    /// # Errors
+    /// Returns an error if the HTTP client cannot be built
+    pub fn with_config(timeout: Duration, pool_max_idle: usize) -> Result<Self> {
+        let client = Client::builder()
+            .connect_timeout(timeout)
+            .timeout(timeout)
+            .pool_max_idle_per_host(pool_max_idle)
+            .build()?;
+        Ok(Self { http_client: client })
+    }
+
     /// Asynchronously sends a specified HTTP request using the `RequestBuilder`
     ///
     /// # Arguments
@@ -129,3 +147,31 @@ impl RequestHandler {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio::net::TcpListener;
+    use tokio::time::{sleep, timeout};
+
+    #[tokio::test]
+    async fn test_request_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+
+        // The server accepts the connection but never responds.
+        let server = tokio::spawn(async move {
+            let (_stream, _) = listener.accept().await.unwrap();
+            sleep(Duration::from_secs(30)).await;
+        });
+
+        let handler = RequestHandler::with_config(Duration::from_millis(200), 1).unwrap();
+        let result = timeout(Duration::from_secs(5), handler.send::<Value, Value>(Method::POST, url, Some(&json!({ "id": 1 }))))
+            .await
+            .expect("The request should time out instead of hanging");
+        assert!(result.is_err());
+
+        server.abort();
+    }
+}
@@ -5,15 +5,20 @@
 // See the LICENSE file in the project root for license information.
 //
 
-use crate::jito::{get_jito_api_url_by_region, poll_jito_bundle_statuses, send_jito_bundle, JITO_TIP_ACCOUNTS, MIN_JITO_TIP_LAMPORTS};
-use crate::priority_fee::get_priority_fee_estimate;
+use crate::jito::{
+    get_jito_api_url_by_region, poll_jito_bundle_statuses, send_jito_bundle, JITO_POOL_MAX_IDLE, JITO_REQUEST_TIMEOUT, JITO_TIP_ACCOUNTS,
+    MIN_JITO_TIP_LAMPORTS,
+};
+use crate::priority_fee::{filter_priority_fee_accounts, get_priority_fee_estimate};
+use crate::request_handler::RequestHandler;
 use crate::PriorityFeeLevel;
 use log::warn;
 use rand::Rng;
-use reqwest::Client;
 use solana_client::client_error::{ClientError, ClientErrorKind};
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_client::rpc_config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig};
+use solana_client::rpc_custom_error::{JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY, JSON_RPC_SERVER_ERROR_TRANSACTION_SIGNATURE_VERIFICATION_FAILURE};
+use solana_client::rpc_request::RpcError;
 use solana_client::rpc_response::{Response, RpcSimulateTransactionResult};
 use solana_commitment_config::{CommitmentConfig, CommitmentLevel};
 use solana_compute_budget_interface::ComputeBudgetInstruction;
@@ -30,13 +35,20 @@ use solana_transaction::versioned::VersionedTransaction;
 use solana_transaction_error::TransactionError;
 use solana_transaction_status::TransactionConfirmationStatus;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::time::{Duration, Instant};
 use tokio::time::sleep;
 
 const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
 const DEFAULT_TRANSACTION_TIMEOUT_SECONDS: u64 = 60;
 const DEFAULT_COMPUTE_UNIT_MARGIN_MULTIPLIER: f64 = 1.15;
+const MICRO_LAMPORTS_PER_LAMPORT: u64 = 1_000_000;
+
+/// HTTP client shared by all Jito bundle sends so the connection pool is reused.
+static JITO_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+/// The maximum size of a serialized transaction in bytes.
+pub const MAX_TRANSACTION_SIZE: usize = 1232;
 
 #[derive(Clone)]
 pub struct SmartTxConfig {
@@ -49,6 +61,23 @@ pub struct SmartTxConfig {
     pub sig_verify_on_simulation: bool,
     /// The default timeout is 60 seconds.
     pub transaction_timeout: Option<Duration>,
+    /// Caps the compute unit limit below the 1.4M ceiling. No additional cap is applied by default.
+    pub max_compute_unit_limit: Option<u32>,
+    /// Simulates, builds and signs the transaction without sending it.
+    pub dry_run: bool,
+    /// The number of simulation attempts used to estimate compute units. The default is 5.
+    pub simulation_retries: u8,
+    /// Sets the compute unit price on Jito transactions as well. Only the tip is paid by default.
+    pub jito_include_priority_fee: bool,
+    /// Receives the events emitted while the transaction is simulated, sent and confirmed.
+    pub observer: Option<Arc<dyn TxEventObserver>>,
+    /// The number of times the transaction is rebuilt with a new blockhash and sent again if sending fails
+    /// with a recoverable error (expired blockhash, node behind), or if it's not confirmed before its blockhash expires.
+    /// Not retried by default.
+    pub send_retries: u8,
+    /// The minimum compute unit limit set when the compute units are estimated by the simulation, for transactions
+    /// whose cost depends on branches that may not be hit during the simulation. The limit is still capped by the ceiling.
+    pub min_compute_unit_limit: Option<u32>,
 }
 
 impl Default for SmartTxConfig {
@@ -61,16 +90,101 @@ impl Default for SmartTxConfig {
             ingore_simulation_error: false,
             sig_verify_on_simulation: true,
             transaction_timeout: None,
+            max_compute_unit_limit: None,
+            dry_run: false,
+            simulation_retries: 5,
+            jito_include_priority_fee: false,
+            observer: None,
+            send_retries: 0,
+            min_compute_unit_limit: None,
         }
     }
 }
 
+impl SmartTxConfig {
+    /// Returns the maximum compute unit limit that can be set for a transaction.
+    fn compute_unit_limit_ceiling(&self) -> u32 {
+        self.max_compute_unit_limit
+            .map_or(MAX_COMPUTE_UNIT_LIMIT, |x| x.min(MAX_COMPUTE_UNIT_LIMIT))
+    }
+
+    /// Adds the margin to the compute units consumed during the simulation, raises the result to the configured minimum
+    /// and clamps it to the ceiling.
+    fn compute_unit_limit_from_consumed(&self, cu_consumed: u64) -> u32 {
+        let cu_limit = cu_consumed as f64 * self.compute_unit_margin_multiplier.clamp(1.0, 10.0);
+        let cu_limit = u32::max(cu_limit as u32, self.min_compute_unit_limit.unwrap_or(0));
+        u32::min(self.compute_unit_limit_ceiling(), cu_limit)
+    }
+}
+
+/// Receives the events of a smart transaction, for example to collect metrics.
+/// The events are emitted in addition to the log messages. All methods do nothing by default.
+pub trait TxEventObserver: Send + Sync {
+    /// Called when a simulation attempt fails, including the failures which are retried or ignored.
+    fn on_simulation_failed(&self, _error: &SmartTransactionError) {}
+
+    /// Called before the transaction is simulated again after a failed attempt.
+    fn on_simulation_retry(&self, _attempt: u8) {}
+
+    /// Called once the compute unit limit and the priority fee are set, before the transaction is sent.
+    fn on_prepared(&self, _compute_unit_limit: u32, _priority_fee: u64) {}
+
+    /// Called before the transaction is rebuilt and sent again after a recoverable send error.
+    fn on_resubmit(&self, _attempt: u8, _error: &SmartTransactionError) {}
+
+    /// Called when the transaction is sent, via RPC or as a Jito bundle.
+    fn on_sent(&self, _signature: &Signature) {}
+
+    /// Called when the transaction is confirmed.
+    fn on_confirmed(&self, _result: &SmartTxResult) {}
+}
+
 #[derive(Clone)]
 pub struct SmartTxPriorityFeeConfig {
     pub additional_addresses: Vec<Pubkey>,
     pub fee_level: PriorityFeeLevel,
     pub fee_min: u64,
     pub fee_max: u64,
+    /// The maximum total priority fee in lamports. If set, the compute unit price is capped at the budget divided
+    /// by the estimated compute unit limit, and `fee_min`/`fee_max` are ignored.
+    pub total_fee_budget_lamports: Option<u64>,
+    /// Keeps well-known program IDs and sysvars in the accounts used to estimate the priority fee.
+    /// They are filtered out by default, since they skew the estimate.
+    pub include_program_accounts: bool,
+    /// A pre-computed priority fee in micro lamports per compute unit. If set, it's used instead of the RPC estimate,
+    /// but is still clamped by `fee_min`/`fee_max` or `total_fee_budget_lamports`.
+    pub fixed_priority_fee: Option<u64>,
+    /// The commitment of the recent prioritization fees used to estimate the priority fee.
+    /// The fees of all slots known to the RPC node are used by default.
+    pub commitment: Option<CommitmentConfig>,
+}
+
+impl Default for SmartTxPriorityFeeConfig {
+    fn default() -> Self {
+        Self {
+            additional_addresses: vec![],
+            fee_level: PriorityFeeLevel::Medium,
+            fee_min: 0,
+            fee_max: u64::MAX,
+            total_fee_budget_lamports: None,
+            include_program_accounts: false,
+            fixed_priority_fee: None,
+            commitment: None,
+        }
+    }
+}
+
+impl SmartTxPriorityFeeConfig {
+    /// Clamps the estimated compute unit price (micro lamports per compute unit) to the configured bounds.
+    fn clamp_priority_fee(&self, estimated_fee: u64, cu_limit: u32) -> u64 {
+        match self.total_fee_budget_lamports {
+            Some(budget) => {
+                let max_fee = (budget as u128 * MICRO_LAMPORTS_PER_LAMPORT as u128 / cu_limit.max(1) as u128) as u64;
+                estimated_fee.min(max_fee)
+            }
+            None => u64::max(u64::min(estimated_fee, self.fee_max), self.fee_min),
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -78,14 +192,29 @@ pub struct SmartTxJitoConfig {
     pub uuid: String,
     pub tips: u64,
     pub region: Option<String>,
+    /// The account receiving the tip. A random Jito tip account is used if not provided.
+    pub tip_account: Option<Pubkey>,
+}
+
+impl SmartTxJitoConfig {
+    /// Builds the tip transfer instruction, which is added to the end of the transaction.
+    fn tip_instruction(&self, payer: &Pubkey) -> Instruction {
+        let tip_account = self.tip_account.unwrap_or_else(|| {
+            let rnd = rand::rng().random_range(0..JITO_TIP_ACCOUNTS.len());
+            Pubkey::from_str(JITO_TIP_ACCOUNTS[rnd]).unwrap()
+        });
+        transfer(payer, &tip_account, self.tips.max(MIN_JITO_TIP_LAMPORTS))
+    }
 }
 
 #[derive(Clone)]
 pub struct SmartTxResult {
-    /// The transaction signature.
+    /// The transaction signature. Empty if the transaction has not been sent in the dry-run mode.
     pub signature: String,
     /// Used priority fee (micro lamports per compute unit).
     pub priority_fee: u64,
+    /// Used compute unit limit.
+    pub compute_unit_limit: u32,
     /// Jito bundle id if the transaction has been sent via Jito.
     pub jito_bundle_id: Option<String>,
 }
@@ -100,10 +229,16 @@ pub enum SmartTransactionError {
     SigningError(#[from] SignerError),
     #[error(transparent)]
     SimulationError(#[from] TransactionError),
+    #[error("Simulation failed: {error}")]
+    SimulationFailed { error: TransactionError, logs: Vec<String> },
     #[error(transparent)]
     RpcClientError(#[from] ClientError),
     #[error("JitoClientError: {0}")]
     JitoClientError(String),
+    #[error("Unable to confirm transaction {signature} in {} seconds", timeout.as_secs())]
+    ConfirmationTimeout { signature: Signature, timeout: Duration },
+    #[error("Transaction {signature} was not confirmed before its blockhash expired")]
+    BlockhashExpired { signature: Signature },
 }
 
 pub async fn send_smart_transaction(
@@ -118,48 +253,61 @@ pub async fn send_smart_transaction(
         .transaction_timeout
         .unwrap_or_else(|| Duration::from_secs(DEFAULT_TRANSACTION_TIMEOUT_SECONDS));
 
-    let mut priority_fee = 0;
-
-    if let Some(fee_config) = tx_config.priority_fee {
-        // Priority fee is not required for jito bundles.
-        if tx_config.jito.is_none() && fee_config.fee_level != PriorityFeeLevel::None {
-            let mut accounts_and_programs: Vec<Pubkey> = instructions.iter().flat_map(|ix| ix.accounts.iter()).map(|a| a.pubkey).collect();
-            accounts_and_programs.extend(fee_config.additional_addresses);
-            priority_fee = u64::max(
-                u64::min(get_priority_fee_estimate(client, accounts_and_programs, fee_config.fee_level).await?, fee_config.fee_max),
-                fee_config.fee_min,
-            )
-        }
-    }
+    // Priority fee is not required for jito bundles, unless explicitly requested.
+    let priority_fee_config = tx_config
+        .priority_fee
+        .as_ref()
+        .filter(|fee_config| (tx_config.jito.is_none() || tx_config.jito_include_priority_fee) && fee_config.fee_level != PriorityFeeLevel::None);
 
     let signers_copy: Vec<Keypair> = signers.iter().map(|keypair| keypair.insecure_clone()).collect();
 
+    let mut accounts_and_programs: Vec<Pubkey> = instructions.iter().flat_map(|ix| ix.accounts.iter()).map(|a| a.pubkey).collect();
+
+    // The compute unit price depends on the compute unit limit, so it's set after the simulation.
+    // A placeholder is simulated in its place to account for its compute units.
     let mut all_instructions = Vec::<Instruction>::new();
-    if priority_fee > 0 {
-        all_instructions.push(ComputeBudgetInstruction::set_compute_unit_price(priority_fee));
+    if priority_fee_config.is_some() {
+        all_instructions.push(ComputeBudgetInstruction::set_compute_unit_price(0));
     }
     all_instructions.extend(instructions);
 
     // Add a tip instruction to the end of the instructions list if jito tips are provided.
-    if let Some(jito_config) = tx_config.jito.clone() {
-        let rnd = rand::rng().random_range(0..JITO_TIP_ACCOUNTS.len());
-        let tip_amount = jito_config.tips.max(MIN_JITO_TIP_LAMPORTS);
-        let random_tip_account = Pubkey::from_str(JITO_TIP_ACCOUNTS[rnd]).unwrap();
-        let tip_instruction = transfer(payer, &random_tip_account, tip_amount);
-        all_instructions.push(tip_instruction);
+    if let Some(jito_config) = &tx_config.jito {
+        all_instructions.push(jito_config.tip_instruction(payer));
     }
 
     // Simulate transaction and estimate CU usage. A simulation may fail, so do it a few times.
+    let observer = tx_config.observer.as_deref();
     let mut cu_limit = 0;
-    for _ in 0..5 {
-        match simulate_transaction(client, &all_instructions, payer, &signers_copy, lookup_tables.clone(), tx_config.sig_verify_on_simulation).await {
+    for attempt in 0..tx_config.simulation_retries {
+        if attempt > 0 {
+            if let Some(observer) = observer {
+                observer.on_simulation_retry(attempt);
+            }
+        }
+        match simulate_transaction_with_fallback(
+            client,
+            &all_instructions,
+            payer,
+            &signers_copy,
+            lookup_tables.clone(),
+            tx_config.sig_verify_on_simulation,
+        )
+        .await
+        {
             Ok(response) => {
                 if let Some(err) = response.value.err {
+                    if let Some(observer) = observer {
+                        observer.on_simulation_failed(&SmartTransactionError::SimulationError(err.clone()));
+                    }
                     match err.clone() {
                         TransactionError::BlockhashNotFound => continue,
                         err => {
                             if !tx_config.ingore_simulation_error {
-                                return Err(err.into());
+                                return Err(SmartTransactionError::SimulationFailed {
+                                    error: err,
+                                    logs: response.value.logs.unwrap_or_default(),
+                                });
                             } else {
                                 warn!(target: "log", "Simulation failed with error: {:?}", err);
                                 break;
@@ -171,18 +319,21 @@ pub async fn send_smart_transaction(
                 let cu_consumed = response.value.units_consumed.unwrap_or(0);
 
                 // Add margin to the consumed compute units during the simulation.
-                cu_limit = u32::min(MAX_COMPUTE_UNIT_LIMIT, (cu_consumed as f64 * tx_config.compute_unit_margin_multiplier.clamp(1.0, 10.0)) as u32);
+                cu_limit = tx_config.compute_unit_limit_from_consumed(cu_consumed);
                 break;
             }
-            Err(_) => {
+            Err(err) => {
                 //warn!(target: "log", "Simulation failed with error: {:?}", err);
+                if let Some(observer) = observer {
+                    observer.on_simulation_failed(&err);
+                }
                 continue;
             }
         };
     }
 
     if cu_limit == 0 {
-        cu_limit = tx_config.default_compute_unit_limit;
+        cu_limit = tx_config.default_compute_unit_limit.min(tx_config.compute_unit_limit_ceiling());
         if cu_limit > 0 {
             warn!(target: "log", "Simulation failed; setting the CU limit to the default value of {}", cu_limit);
         } else {
@@ -190,23 +341,93 @@ pub async fn send_smart_transaction(
         }
     };
 
+    let mut priority_fee = 0;
+    if let Some(fee_config) = priority_fee_config {
+        let estimated_fee = match fee_config.fixed_priority_fee {
+            Some(fee) => fee,
+            None => {
+                accounts_and_programs.extend(&fee_config.additional_addresses);
+                if !fee_config.include_program_accounts {
+                    accounts_and_programs = filter_priority_fee_accounts(accounts_and_programs);
+                }
+                get_priority_fee_estimate(client, accounts_and_programs, fee_config.fee_level, fee_config.commitment).await?
+            }
+        };
+        priority_fee = fee_config.clamp_priority_fee(estimated_fee, cu_limit);
+        if priority_fee > 0 {
+            all_instructions[0] = ComputeBudgetInstruction::set_compute_unit_price(priority_fee);
+        } else {
+            all_instructions.remove(0);
+        }
+    }
+
     if cu_limit > 0 {
         all_instructions.insert(0, ComputeBudgetInstruction::set_compute_unit_limit(cu_limit));
     }
 
-    let recent_blockhash = client.get_latest_blockhash().await?;
+    if let Some(observer) = observer {
+        observer.on_prepared(cu_limit, priority_fee);
+    }
+
+    let mut send_attempt = 0;
+    loop {
+        let recent_blockhash = client.get_latest_blockhash().await?;
+
+        //
+        // Recreate the transaction with the updated CU limit.
+        //
+        let versioned_message = VersionedMessage::V0(v0::Message::try_compile(payer, &all_instructions, &lookup_tables, recent_blockhash)?);
+        let transaction = VersionedTransaction::try_new(versioned_message, &signers_copy)?;
 
-    //
-    // Recreate the transaction with the updated CU limit.
-    //
-    let versioned_message = VersionedMessage::V0(v0::Message::try_compile(payer, &all_instructions, &lookup_tables, recent_blockhash)?);
-    let transaction = VersionedTransaction::try_new(versioned_message, &signers_copy)?;
+        if tx_config.dry_run {
+            return Ok(SmartTxResult {
+                signature: String::new(),
+                priority_fee,
+                compute_unit_limit: cu_limit,
+                jito_bundle_id: None,
+            });
+        }
 
-    if let Some(jito_config) = tx_config.jito {
-        let serialized_transaction = bincode::serialize(&transaction).expect("Failed to serialize transaction");
+        match send_and_confirm_transaction(client, &transaction, tx_config.jito.as_ref(), transaction_timeout, observer).await {
+            Ok((signature, jito_bundle_id)) => {
+                let result = SmartTxResult {
+                    signature,
+                    priority_fee,
+                    compute_unit_limit: cu_limit,
+                    jito_bundle_id,
+                };
+                if let Some(observer) = observer {
+                    observer.on_confirmed(&result);
+                }
+                return Ok(result);
+            }
+            Err(err) if send_attempt < tx_config.send_retries && is_recoverable_send_error(&err) => {
+                // Rebuild the transaction with a new blockhash and send it again.
+                send_attempt += 1;
+                warn!(target: "log", "Sending the transaction failed with error: {}; retrying ({}/{})", err, send_attempt, tx_config.send_retries);
+                if let Some(observer) = observer {
+                    observer.on_resubmit(send_attempt, &err);
+                }
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Sends the transaction, via RPC or as a Jito bundle, and waits for the confirmation.
+/// Returns the transaction signature and the Jito bundle id.
+async fn send_and_confirm_transaction(
+    client: &RpcClient,
+    transaction: &VersionedTransaction,
+    jito_config: Option<&SmartTxJitoConfig>,
+    transaction_timeout: Duration,
+    observer: Option<&dyn TxEventObserver>,
+) -> Result<(String, Option<String>), SmartTransactionError> {
+    if let Some(jito_config) = jito_config {
+        let serialized_transaction = bincode::serialize(transaction).expect("Failed to serialize transaction");
         let transaction_base58 = bs58::encode(&serialized_transaction).into_string();
 
-        let user_provided_region = jito_config.region.unwrap_or("Default".to_string());
+        let user_provided_region = jito_config.region.clone().unwrap_or("Default".to_string());
         let jito_api_base_url = get_jito_api_url_by_region(&user_provided_region);
         let jito_api_url = if jito_config.uuid.is_empty() {
             format!("{}/api/v1/bundles", jito_api_base_url)
@@ -215,21 +436,20 @@ pub async fn send_smart_transaction(
         };
 
         // Send the transaction as Jito bundle.
-        let jito_client = Client::new();
+        let jito_client = jito_client()?;
         let jito_bundle_id = send_jito_bundle(jito_client.clone(), vec![transaction_base58], &jito_api_url)
             .await
             .map_err(|e| SmartTransactionError::JitoClientError(e.to_string()))?;
+        if let Some(observer) = observer {
+            observer.on_sent(&transaction.signatures[0]);
+        }
 
         // Wait for the confirmation.
         let signature = poll_jito_bundle_statuses(jito_client.clone(), jito_bundle_id.clone(), &jito_api_url, transaction_timeout)
             .await
             .map_err(|e| SmartTransactionError::JitoClientError(e.to_string()))?;
 
-        Ok(SmartTxResult {
-            signature,
-            priority_fee,
-            jito_bundle_id: Some(jito_bundle_id),
-        })
+        Ok((signature, Some(jito_bundle_id)))
     } else {
         let send_config = RpcSendTransactionConfig {
             skip_preflight: true,
@@ -239,19 +459,119 @@ pub async fn send_smart_transaction(
         };
 
         // Send the transaction.
-        let signature = client.send_transaction_with_config(&transaction, send_config).await?;
+        let signature = client.send_transaction_with_config(transaction, send_config).await?;
+        if let Some(observer) = observer {
+            observer.on_sent(&signature);
+        }
 
         // Wait for the confirmation.
-        poll_transaction_confirmation(client, signature, transaction_timeout).await?;
+        match poll_transaction_confirmation(client, signature, transaction_timeout).await {
+            Err(SmartTransactionError::ConfirmationTimeout { .. })
+                if !client
+                    .is_blockhash_valid(transaction.message.recent_blockhash(), CommitmentConfig::processed())
+                    .await? =>
+            {
+                // The transaction can't land anymore, unless it did since the last poll.
+                poll_transaction_confirmation(client, signature, Duration::ZERO)
+                    .await
+                    .map_err(|err| match err {
+                        SmartTransactionError::ConfirmationTimeout { signature, .. } => SmartTransactionError::BlockhashExpired { signature },
+                        err => err,
+                    })?;
+            }
+            result => {
+                result?;
+            }
+        }
 
-        Ok(SmartTxResult {
-            signature: signature.to_string(),
-            priority_fee,
-            jito_bundle_id: None,
-        })
+        Ok((signature.to_string(), None))
     }
 }
 
+/// Estimates the size of the serialized v0 transaction, e.g. to pack as many instructions as possible
+/// into a transaction without exceeding `MAX_TRANSACTION_SIZE`.
+///
+/// The message is compiled with a dummy blockhash, and the first signer of the instructions is used as the payer.
+/// Returns `usize::MAX` if the message cannot be compiled.
+pub fn estimate_transaction_size(instructions: &[Instruction], lookup_tables: &[AddressLookupTableAccount], num_signers: usize) -> usize {
+    let payer = instructions
+        .iter()
+        .flat_map(|ix| &ix.accounts)
+        .find(|account| account.is_signer)
+        .map_or_else(Pubkey::new_unique, |account| account.pubkey);
+
+    let Ok(message) = v0::Message::try_compile(&payer, instructions, lookup_tables, Hash::default()) else {
+        return usize::MAX;
+    };
+    let transaction = VersionedTransaction {
+        signatures: vec![Signature::default(); num_signers],
+        message: VersionedMessage::V0(message),
+    };
+    bincode::serialized_size(&transaction).map_or(usize::MAX, |size| size as usize)
+}
+
+/// Returns true if sending the transaction failed with an error that a new attempt with a fresh blockhash can recover from:
+/// the blockhash has expired or the RPC node is behind. A transaction which is not confirmed before its blockhash expires
+/// is recoverable too, since it can no longer land. A confirmation timeout with a valid blockhash is not, because
+/// the pending transaction may still be executed along with the new one.
+#[allow(clippy::result_large_err)]
+fn jito_client() -> Result<reqwest::Client, SmartTransactionError> {
+    if let Some(client) = JITO_CLIENT.get() {
+        return Ok(client.clone());
+    }
+    let client = RequestHandler::with_config(JITO_REQUEST_TIMEOUT, JITO_POOL_MAX_IDLE)
+        .map_err(|e| SmartTransactionError::JitoClientError(e.to_string()))?
+        .http_client;
+    Ok(JITO_CLIENT.get_or_init(|| client).clone())
+}
+
+fn is_recoverable_send_error(err: &SmartTransactionError) -> bool {
+    match err {
+        SmartTransactionError::BlockhashExpired { .. } => true,
+        SmartTransactionError::RpcClientError(err) => {
+            err.get_transaction_error() == Some(TransactionError::BlockhashNotFound)
+                || matches!(
+                    err.kind(),
+                    ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) if *code == JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY
+                )
+        }
+        _ => false,
+    }
+}
+
+/// Returns true if the simulation failed because the transaction signatures could not be verified.
+fn is_signature_failure(result: &Result<Response<RpcSimulateTransactionResult>, SmartTransactionError>) -> bool {
+    match result {
+        Ok(response) => response.value.err == Some(TransactionError::SignatureFailure),
+        Err(SmartTransactionError::SigningError(_)) => true,
+        Err(SmartTransactionError::RpcClientError(err)) => matches!(
+            err.kind(),
+            ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. })
+                if *code == JSON_RPC_SERVER_ERROR_TRANSACTION_SIGNATURE_VERIFICATION_FAILURE
+        ),
+        Err(_) => false,
+    }
+}
+
+/// Simulates the transaction. If the simulation fails due to signature verification, it's retried once
+/// without signature verification, so the compute units can still be estimated.
+#[allow(clippy::result_large_err)]
+async fn simulate_transaction_with_fallback(
+    client: &RpcClient,
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    signers: &[Keypair],
+    lookup_tables: Vec<AddressLookupTableAccount>,
+    sig_verify: bool,
+) -> Result<Response<RpcSimulateTransactionResult>, SmartTransactionError> {
+    let result = simulate_transaction(client, instructions, payer, signers, lookup_tables.clone(), sig_verify).await;
+    if sig_verify && is_signature_failure(&result) {
+        warn!(target: "log", "Simulation failed due to signature verification; retrying without signature verification");
+        return simulate_transaction(client, instructions, payer, signers, lookup_tables, false).await;
+    }
+    result
+}
+
 #[allow(clippy::result_large_err)]
 async fn simulate_transaction(
     client: &RpcClient,
@@ -273,7 +593,15 @@ async fn simulate_transaction(
     };
 
     let versioned_message = VersionedMessage::V0(v0::Message::try_compile(payer, &test_instructions, &lookup_tables, recent_blockhash)?);
-    let transaction = VersionedTransaction::try_new(versioned_message, signers)?;
+    let transaction = if sig_verify {
+        VersionedTransaction::try_new(versioned_message, signers)?
+    } else {
+        // Signatures are not verified, so the transaction doesn't need to be signed.
+        VersionedTransaction {
+            signatures: vec![Signature::default(); versioned_message.header().num_required_signatures as usize],
+            message: versioned_message,
+        }
+    };
 
     let simulate_config = RpcSimulateTransactionConfig {
         sig_verify,
@@ -294,39 +622,742 @@ async fn simulate_transaction(
 /// * `txt-sig` - The transaction signature to check
 ///
 /// # Returns
-/// The confirmed transaction signature or an error if the confirmation times out
-async fn poll_transaction_confirmation(client: &RpcClient, tx_sig: Signature, timeout: Duration) -> Result<Signature, ClientError> {
+/// The confirmed transaction signature or an error if the confirmation times out.
+/// The status is checked at least once, even if the timeout is zero.
+async fn poll_transaction_confirmation(client: &RpcClient, tx_sig: Signature, timeout: Duration) -> Result<Signature, SmartTransactionError> {
     // 2 seconds retry interval
     let interval = Duration::from_secs(2);
     let start = Instant::now();
 
-    while start.elapsed() < timeout {
+    loop {
         let status = client.get_signature_statuses(&[tx_sig]).await?;
 
-        match status.value[0].clone() {
-            Some(status) => {
-                if status.err.is_none()
-                    && (status.confirmation_status == Some(TransactionConfirmationStatus::Confirmed)
-                        || status.confirmation_status == Some(TransactionConfirmationStatus::Finalized))
-                {
-                    return Ok(tx_sig);
+        if let Some(status) = status.value[0].clone() {
+            if let Some(err) = status.err {
+                warn!(target: "log", "Transaction {} failed with error: {}", tx_sig, err);
+                return Err(ClientError {
+                    request: None,
+                    kind: err.into(),
+                }
+                .into());
+            }
+            if status.confirmation_status == Some(TransactionConfirmationStatus::Confirmed)
+                || status.confirmation_status == Some(TransactionConfirmationStatus::Finalized)
+            {
+                return Ok(tx_sig);
+            }
+        }
+
+        let elapsed = start.elapsed();
+        if elapsed >= timeout {
+            return Err(SmartTransactionError::ConfirmationTimeout { signature: tx_sig, timeout });
+        }
+        sleep(interval.min(timeout - elapsed)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use serde_json::{json, Value};
+    use solana_client::client_error::Result as ClientResult;
+    use solana_client::rpc_client::RpcClientConfig;
+    use solana_client::rpc_request::{RpcRequest, RpcResponseErrorData};
+    use solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+    use solana_instruction::{error::InstructionError, AccountMeta};
+    use solana_pubkey::pubkey;
+    use solana_signer::Signer;
+    use solana_transaction_status::{EncodedTransaction, TransactionBinaryEncoding};
+    use std::sync::Mutex;
+
+    /// Answers the requests needed by the smart transaction and records the called methods.
+    /// The first `simulation_failures` simulations fail with `simulation_error`, or with `BlockhashNotFound` if it's not set.
+    /// The accounts passed to the priority fee estimation are recorded in `priority_fee_accounts`, its commitment
+    /// in `priority_fee_commitments`, and the simulated transactions in `simulated_transactions`. The first `send_failures` sends fail because the node is behind,
+    /// the next `unconfirmed_sends` sent transactions are never confirmed and their blockhash expires,
+    /// the other sent transactions are confirmed immediately.
+    #[derive(Default)]
+    struct RecordingRpcSender {
+        methods: Arc<Mutex<Vec<String>>>,
+        simulation_failures: usize,
+        simulation_error: Option<Value>,
+        priority_fee_accounts: Arc<Mutex<Vec<String>>>,
+        priority_fee_commitments: Arc<Mutex<Vec<Value>>>,
+        simulated_transactions: Arc<Mutex<Vec<VersionedTransaction>>>,
+        send_failures: usize,
+        unconfirmed_sends: usize,
+        sent_signatures: Mutex<Vec<String>>,
+    }
+
+    impl RecordingRpcSender {
+        fn into_client(self) -> RpcClient {
+            RpcClient::new_sender(self, RpcClientConfig::default())
+        }
+    }
+
+    #[async_trait]
+    impl RpcSender for RecordingRpcSender {
+        async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+            let method = request.to_string();
+            if method == "getRecentPrioritizationFees" {
+                let accounts = params[0].as_array().into_iter().flatten().filter_map(|x| x.as_str().map(String::from));
+                self.priority_fee_accounts.lock().unwrap().extend(accounts);
+                self.priority_fee_commitments.lock().unwrap().push(params[1]["commitment"].clone());
+            }
+            if method == "simulateTransaction" {
+                let encoded = EncodedTransaction::Binary(params[0].as_str().unwrap_or_default().to_string(), TransactionBinaryEncoding::Base64);
+                self.simulated_transactions.lock().unwrap().extend(encoded.decode());
+            }
+            let (simulation_count, send_count) = {
+                let mut methods = self.methods.lock().unwrap();
+                methods.push(method.clone());
+                let count = |name: &str| methods.iter().filter(|m| *m == name).count();
+                (count("simulateTransaction"), count("sendTransaction"))
+            };
+            let context = json!({ "slot": 1 });
+            let response = match method.as_str() {
+                "getRecentPrioritizationFees" => json!([{ "slot": 1, "prioritizationFee": 5000 }]),
+                "getSlot" => json!(1),
+                "getLatestBlockhash" => json!({
+                    "context": context,
+                    "value": { "blockhash": Hash::new_unique().to_string(), "lastValidBlockHeight": 100 }
+                }),
+                "simulateTransaction" if simulation_count <= self.simulation_failures => match &self.simulation_error {
+                    Some(error) => json!({
+                        "context": context,
+                        "value": { "err": error, "logs": ["Program log: simulation failed"], "unitsConsumed": 0 }
+                    }),
+                    None => json!({
+                        "context": context,
+                        "value": { "err": "BlockhashNotFound", "logs": [], "unitsConsumed": 0 }
+                    }),
+                },
+                "simulateTransaction" => json!({
+                    "context": context,
+                    "value": { "err": null, "logs": [], "unitsConsumed": 100_000 }
+                }),
+                "sendTransaction" if send_count <= self.send_failures => {
+                    return Err(ClientError {
+                        request: Some(request),
+                        kind: ClientErrorKind::RpcError(RpcError::RpcResponseError {
+                            code: JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+                            message: "Node is behind by 42 slots".to_string(),
+                            data: RpcResponseErrorData::NodeUnhealthy { num_slots_behind: Some(42) },
+                        }),
+                    })
                 }
-                if status.err.is_some() {
-                    warn!(target: "log", "Transaction {} failed with error: {}", tx_sig, status.err.clone().unwrap());
+                "sendTransaction" => {
+                    let encoded = EncodedTransaction::Binary(params[0].as_str().unwrap_or_default().to_string(), TransactionBinaryEncoding::Base64);
+                    let signature = encoded.decode().map(|tx| tx.signatures[0].to_string()).unwrap_or_default();
+                    self.sent_signatures.lock().unwrap().push(signature.clone());
+                    json!(signature)
+                }
+                "getSignatureStatuses" => {
+                    let sent_signatures = self.sent_signatures.lock().unwrap();
+                    let index = sent_signatures.iter().position(|signature| params[0][0] == json!(signature));
+                    if index.is_some_and(|index| index < self.unconfirmed_sends) {
+                        json!({ "context": context, "value": [null] })
+                    } else {
+                        json!({
+                            "context": context,
+                            "value": [{ "slot": 1, "confirmations": null, "err": null, "status": { "Ok": null }, "confirmationStatus": "confirmed" }]
+                        })
+                    }
+                }
+                "isBlockhashValid" => json!({ "context": context, "value": false }),
+                _ => {
                     return Err(ClientError {
-                        request: None,
-                        kind: status.err.unwrap().into(),
-                    });
+                        request: Some(request),
+                        kind: ClientErrorKind::Custom(format!("Method not implemented: {}", method)),
+                    })
                 }
+            };
+            Ok(response)
+        }
+
+        fn get_transport_stats(&self) -> RpcTransportStats {
+            RpcTransportStats::default()
+        }
+
+        fn url(&self) -> String {
+            "recording".to_string()
+        }
+    }
+
+    #[test]
+    fn test_compute_unit_limit_without_cap() {
+        let tx_config = SmartTxConfig {
+            compute_unit_margin_multiplier: 1.5,
+            ..SmartTxConfig::default()
+        };
+        assert_eq!(tx_config.compute_unit_limit_from_consumed(100_000), 150_000);
+        assert_eq!(tx_config.compute_unit_limit_from_consumed(1_300_000), MAX_COMPUTE_UNIT_LIMIT);
+    }
+
+    #[test]
+    fn test_compute_unit_limit_with_cap() {
+        let tx_config = SmartTxConfig {
+            compute_unit_margin_multiplier: 1.5,
+            max_compute_unit_limit: Some(200_000),
+            ..SmartTxConfig::default()
+        };
+        assert_eq!(
+            ComputeBudgetInstruction::set_compute_unit_limit(tx_config.compute_unit_limit_from_consumed(500_000)),
+            ComputeBudgetInstruction::set_compute_unit_limit(200_000)
+        );
+        assert_eq!(tx_config.compute_unit_limit_from_consumed(100_000), 150_000);
+    }
+
+    #[test]
+    fn test_compute_unit_limit_with_floor() {
+        let tx_config = SmartTxConfig {
+            compute_unit_margin_multiplier: 1.5,
+            min_compute_unit_limit: Some(50_000),
+            ..SmartTxConfig::default()
+        };
+        assert_eq!(
+            ComputeBudgetInstruction::set_compute_unit_limit(tx_config.compute_unit_limit_from_consumed(1_000)),
+            ComputeBudgetInstruction::set_compute_unit_limit(50_000)
+        );
+        assert_eq!(tx_config.compute_unit_limit_from_consumed(100_000), 150_000);
+
+        // The ceiling takes precedence over the floor.
+        let tx_config = SmartTxConfig {
+            max_compute_unit_limit: Some(20_000),
+            ..tx_config
+        };
+        assert_eq!(tx_config.compute_unit_limit_from_consumed(1_000), 20_000);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_does_not_send_transaction() {
+        let methods = Arc::new(Mutex::new(Vec::new()));
+        let client = RecordingRpcSender {
+            methods: methods.clone(),
+            ..RecordingRpcSender::default()
+        }
+        .into_client();
+        let payer = Arc::new(Keypair::new());
+
+        let tx_config = SmartTxConfig {
+            priority_fee: Some(SmartTxPriorityFeeConfig::default()),
+            compute_unit_margin_multiplier: 1.5,
+            dry_run: true,
+            ..SmartTxConfig::default()
+        };
+        let instructions = vec![transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000)];
+
+        let result = send_smart_transaction(&client, vec![payer.clone()], &payer.pubkey(), instructions, vec![], tx_config)
+            .await
+            .unwrap();
+
+        assert!(result.signature.is_empty());
+        assert_eq!(result.priority_fee, 5000);
+        assert_eq!(result.compute_unit_limit, 150_000);
+        assert_eq!(result.jito_bundle_id, None);
+        assert!(!methods.lock().unwrap().iter().any(|method| method == "sendTransaction"));
+    }
+
+    #[tokio::test]
+    async fn test_jito_include_priority_fee() {
+        let simulated_transactions = Arc::new(Mutex::new(Vec::new()));
+        let client = RecordingRpcSender {
+            simulated_transactions: simulated_transactions.clone(),
+            ..RecordingRpcSender::default()
+        }
+        .into_client();
+        let payer = Arc::new(Keypair::new());
+        let tip_account = Pubkey::new_unique();
+
+        let tx_config = SmartTxConfig {
+            priority_fee: Some(SmartTxPriorityFeeConfig::default()),
+            jito: Some(SmartTxJitoConfig {
+                uuid: String::new(),
+                tips: 5000,
+                region: None,
+                tip_account: Some(tip_account),
+            }),
+            jito_include_priority_fee: true,
+            dry_run: true,
+            ..SmartTxConfig::default()
+        };
+        let instructions = vec![transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000)];
+
+        let result = send_smart_transaction(&client, vec![payer.clone()], &payer.pubkey(), instructions, vec![], tx_config.clone())
+            .await
+            .unwrap();
+        assert_eq!(result.priority_fee, 5000);
+
+        // The simulated transaction holds the compute unit price placeholder and ends with the tip.
+        let transaction = simulated_transactions.lock().unwrap().pop().unwrap();
+        let account_keys = transaction.message.static_account_keys();
+        let instructions = transaction.message.instructions();
+        let compute_unit_price = ComputeBudgetInstruction::set_compute_unit_price(0);
+        assert!(instructions
+            .iter()
+            .any(|ix| account_keys[ix.program_id_index as usize] == compute_unit_price.program_id && ix.data == compute_unit_price.data));
+        let tip = instructions.last().unwrap();
+        assert_eq!(account_keys[tip.accounts[1] as usize], tip_account);
+        assert_eq!(tip.data, transfer(&payer.pubkey(), &tip_account, 5000).data);
+
+        // Without the flag, only the tip is paid.
+        let tx_config = SmartTxConfig {
+            jito_include_priority_fee: false,
+            ..tx_config
+        };
+        let instructions = vec![transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000)];
+        let result = send_smart_transaction(&client, vec![payer.clone()], &payer.pubkey(), instructions, vec![], tx_config)
+            .await
+            .unwrap();
+        assert_eq!(result.priority_fee, 0);
+    }
+
+    async fn simulate_with_retries(simulation_failures: usize, simulation_retries: u8) -> (SmartTxResult, usize) {
+        let methods = Arc::new(Mutex::new(Vec::new()));
+        let client = RecordingRpcSender {
+            methods: methods.clone(),
+            simulation_failures,
+            ..RecordingRpcSender::default()
+        }
+        .into_client();
+        let payer = Arc::new(Keypair::new());
+
+        let tx_config = SmartTxConfig {
+            default_compute_unit_limit: 400_000,
+            compute_unit_margin_multiplier: 1.5,
+            dry_run: true,
+            simulation_retries,
+            ..SmartTxConfig::default()
+        };
+        let instructions = vec![transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000)];
+
+        let result = send_smart_transaction(&client, vec![payer.clone()], &payer.pubkey(), instructions, vec![], tx_config)
+            .await
+            .unwrap();
+        let simulations = methods.lock().unwrap().iter().filter(|method| *method == "simulateTransaction").count();
+        (result, simulations)
+    }
+
+    #[tokio::test]
+    async fn test_simulation_retries() {
+        let (result, simulations) = simulate_with_retries(2, 3).await;
+        assert_eq!(simulations, 3);
+        assert_eq!(result.compute_unit_limit, 150_000);
+
+        // The simulation never succeeds within the retry count, so the default limit is used.
+        let (result, simulations) = simulate_with_retries(2, 1).await;
+        assert_eq!(simulations, 1);
+        assert_eq!(result.compute_unit_limit, 400_000);
+    }
+
+    #[tokio::test]
+    async fn test_priority_fee_from_total_budget() {
+        let client = RecordingRpcSender::default().into_client();
+        let payer = Arc::new(Keypair::new());
+
+        let tx_config = SmartTxConfig {
+            priority_fee: Some(SmartTxPriorityFeeConfig {
+                total_fee_budget_lamports: Some(500),
+                ..SmartTxPriorityFeeConfig::default()
+            }),
+            compute_unit_margin_multiplier: 1.5,
+            dry_run: true,
+            ..SmartTxConfig::default()
+        };
+        let instructions = vec![transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000)];
+
+        let result = send_smart_transaction(&client, vec![payer.clone()], &payer.pubkey(), instructions, vec![], tx_config)
+            .await
+            .unwrap();
+
+        // The estimate of 5000 micro lamports exceeds the budget: 500 lamports / 150_000 CU = 3333 micro lamports per CU.
+        assert_eq!(result.compute_unit_limit, 150_000);
+        assert_eq!(result.priority_fee, 3333);
+        assert!(result.priority_fee * result.compute_unit_limit as u64 <= 500 * MICRO_LAMPORTS_PER_LAMPORT);
+    }
+
+    #[tokio::test]
+    async fn test_fixed_priority_fee() {
+        let methods = Arc::new(Mutex::new(Vec::new()));
+        let client = RecordingRpcSender {
+            methods: methods.clone(),
+            ..RecordingRpcSender::default()
+        }
+        .into_client();
+        let payer = Arc::new(Keypair::new());
+
+        let tx_config = SmartTxConfig {
+            priority_fee: Some(SmartTxPriorityFeeConfig {
+                fee_min: 1_000,
+                fee_max: 20_000,
+                fixed_priority_fee: Some(50_000),
+                ..SmartTxPriorityFeeConfig::default()
+            }),
+            dry_run: true,
+            ..SmartTxConfig::default()
+        };
+        let instructions = vec![transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000)];
+
+        let result = send_smart_transaction(&client, vec![payer.clone()], &payer.pubkey(), instructions, vec![], tx_config)
+            .await
+            .unwrap();
+
+        assert!(!methods.lock().unwrap().iter().any(|m| m == "getRecentPrioritizationFees"));
+        assert_eq!(result.priority_fee, 20_000);
+    }
+
+    #[tokio::test]
+    async fn test_simulation_falls_back_without_sig_verify() {
+        let methods = Arc::new(Mutex::new(Vec::new()));
+        let client = RecordingRpcSender {
+            methods: methods.clone(),
+            ..RecordingRpcSender::default()
+        }
+        .into_client();
+        let payer = Keypair::new();
+        // The sender of the transfer is a required signer, but its keypair is missing.
+        let instructions = vec![transfer(&Pubkey::new_unique(), &Pubkey::new_unique(), 1_000)];
+
+        let result = simulate_transaction(&client, &instructions, &payer.pubkey(), &[payer.insecure_clone()], vec![], true).await;
+        assert!(matches!(result, Err(SmartTransactionError::SigningError(_))));
+
+        let response = simulate_transaction_with_fallback(&client, &instructions, &payer.pubkey(), &[payer.insecure_clone()], vec![], true)
+            .await
+            .unwrap();
+        assert_eq!(response.value.err, None);
+        assert_eq!(response.value.units_consumed, Some(100_000));
+        assert_eq!(methods.lock().unwrap().iter().filter(|method| *method == "simulateTransaction").count(), 1);
+    }
+
+    async fn sampled_priority_fee_accounts(instructions: Vec<Instruction>, include_program_accounts: bool) -> Vec<String> {
+        let priority_fee_accounts = Arc::new(Mutex::new(Vec::new()));
+        let client = RecordingRpcSender {
+            priority_fee_accounts: priority_fee_accounts.clone(),
+            ..RecordingRpcSender::default()
+        }
+        .into_client();
+        let payer = Arc::new(Keypair::new());
+
+        let tx_config = SmartTxConfig {
+            priority_fee: Some(SmartTxPriorityFeeConfig {
+                include_program_accounts,
+                ..SmartTxPriorityFeeConfig::default()
+            }),
+            dry_run: true,
+            ..SmartTxConfig::default()
+        };
+
+        send_smart_transaction(&client, vec![payer.clone()], &payer.pubkey(), instructions, vec![], tx_config)
+            .await
+            .unwrap();
+        let accounts = priority_fee_accounts.lock().unwrap().clone();
+        accounts
+    }
+
+    #[tokio::test]
+    async fn test_priority_fee_accounts_exclude_programs() {
+        let token_program = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+        let pool = Pubkey::new_unique();
+        let instruction = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![AccountMeta::new(pool, false), AccountMeta::new_readonly(token_program, false)],
+        );
+
+        let accounts = sampled_priority_fee_accounts(vec![instruction.clone()], false).await;
+        assert_eq!(accounts, vec![pool.to_string()]);
+
+        let accounts = sampled_priority_fee_accounts(vec![instruction], true).await;
+        assert_eq!(accounts, vec![pool.to_string(), token_program.to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_priority_fee_commitment() {
+        let methods = Arc::new(Mutex::new(Vec::new()));
+        let priority_fee_commitments = Arc::new(Mutex::new(Vec::new()));
+        let client = RecordingRpcSender {
+            methods: methods.clone(),
+            priority_fee_commitments: priority_fee_commitments.clone(),
+            ..RecordingRpcSender::default()
+        }
+        .into_client();
+        let payer = Arc::new(Keypair::new());
+        let instruction = Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![AccountMeta::new(Pubkey::new_unique(), false)]);
+
+        for commitment in [None, Some(CommitmentConfig::finalized())] {
+            let tx_config = SmartTxConfig {
+                priority_fee: Some(SmartTxPriorityFeeConfig {
+                    commitment,
+                    ..SmartTxPriorityFeeConfig::default()
+                }),
+                dry_run: true,
+                ..SmartTxConfig::default()
+            };
+            let result = send_smart_transaction(&client, vec![payer.clone()], &payer.pubkey(), vec![instruction.clone()], vec![], tx_config)
+                .await
+                .unwrap();
+            assert_eq!(result.priority_fee, 5000);
+        }
+
+        assert_eq!(*priority_fee_commitments.lock().unwrap(), vec![Value::Null, json!("finalized")]);
+        assert_eq!(methods.lock().unwrap().iter().filter(|method| *method == "getSlot").count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_simulation_failure_logs() {
+        let client = RecordingRpcSender {
+            simulation_failures: 1,
+            simulation_error: Some(json!({ "InstructionError": [0, { "Custom": 6000 }] })),
+            ..RecordingRpcSender::default()
+        }
+        .into_client();
+        let payer = Arc::new(Keypair::new());
+        let instructions = vec![transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000)];
+
+        let result = send_smart_transaction(&client, vec![payer.clone()], &payer.pubkey(), instructions, vec![], SmartTxConfig::default()).await;
+        match result {
+            Err(SmartTransactionError::SimulationFailed { error, logs }) => {
+                assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(6000)));
+                assert_eq!(logs, vec!["Program log: simulation failed".to_string()]);
             }
-            None => {
-                sleep(interval).await;
-            }
+            _ => panic!("Expected a simulation failure"),
+        }
+    }
+
+    /// Records the names of the received events.
+    #[derive(Default)]
+    struct RecordingObserver {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl TxEventObserver for RecordingObserver {
+        fn on_simulation_failed(&self, error: &SmartTransactionError) {
+            self.events.lock().unwrap().push(format!("simulation_failed: {}", error));
+        }
+
+        fn on_simulation_retry(&self, attempt: u8) {
+            self.events.lock().unwrap().push(format!("simulation_retry: {}", attempt));
+        }
+
+        fn on_prepared(&self, compute_unit_limit: u32, priority_fee: u64) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("prepared: {} {}", compute_unit_limit, priority_fee));
+        }
+
+        fn on_resubmit(&self, attempt: u8, _error: &SmartTransactionError) {
+            self.events.lock().unwrap().push(format!("resubmit: {}", attempt));
+        }
+
+        fn on_sent(&self, signature: &Signature) {
+            self.events.lock().unwrap().push(format!("sent: {}", signature));
+        }
+
+        fn on_confirmed(&self, result: &SmartTxResult) {
+            self.events.lock().unwrap().push(format!("confirmed: {}", result.signature));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_observer_events() {
+        let client = RecordingRpcSender {
+            simulation_failures: 1,
+            ..RecordingRpcSender::default()
+        }
+        .into_client();
+        let payer = Arc::new(Keypair::new());
+        let observer = Arc::new(RecordingObserver::default());
+
+        let tx_config = SmartTxConfig {
+            compute_unit_margin_multiplier: 1.5,
+            observer: Some(observer.clone()),
+            ..SmartTxConfig::default()
+        };
+        let instructions = vec![transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000)];
+
+        let result = send_smart_transaction(&client, vec![payer.clone()], &payer.pubkey(), instructions, vec![], tx_config)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *observer.events.lock().unwrap(),
+            vec![
+                "simulation_failed: Blockhash not found".to_string(),
+                "simulation_retry: 1".to_string(),
+                "prepared: 150000 0".to_string(),
+                format!("sent: {}", result.signature),
+                format!("confirmed: {}", result.signature),
+            ]
+        );
+    }
+
+    async fn send_with_retries(send_failures: usize, send_retries: u8) -> (Result<SmartTxResult, SmartTransactionError>, Vec<String>) {
+        let methods = Arc::new(Mutex::new(Vec::new()));
+        let client = RecordingRpcSender {
+            methods: methods.clone(),
+            send_failures,
+            ..RecordingRpcSender::default()
+        }
+        .into_client();
+        let payer = Arc::new(Keypair::new());
+        let observer = Arc::new(RecordingObserver::default());
+
+        let tx_config = SmartTxConfig {
+            send_retries,
+            observer: Some(observer.clone()),
+            ..SmartTxConfig::default()
+        };
+        let instructions = vec![transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000)];
+
+        let result = send_smart_transaction(&client, vec![payer.clone()], &payer.pubkey(), instructions, vec![], tx_config).await;
+        let sent = observer
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|event| event.starts_with("sent") || event.starts_with("resubmit"))
+            .cloned()
+            .collect();
+        (result, sent)
+    }
+
+    #[tokio::test]
+    async fn test_send_retries() {
+        let (result, sent) = send_with_retries(1, 2).await;
+        assert_eq!(sent, vec!["resubmit: 1".to_string(), format!("sent: {}", result.unwrap().signature)]);
+
+        // Without retries, the error of the first send is returned.
+        let (result, sent) = send_with_retries(1, 0).await;
+        assert!(matches!(result, Err(ref err) if is_recoverable_send_error(err)));
+        assert!(sent.is_empty());
+
+        // The retries are exhausted.
+        let (result, sent) = send_with_retries(3, 2).await;
+        assert!(matches!(result, Err(SmartTransactionError::RpcClientError(_))));
+        assert_eq!(sent, vec!["resubmit: 1".to_string(), "resubmit: 2".to_string()]);
+    }
+
+    async fn send_with_expired_blockhash(send_retries: u8) -> (Result<SmartTxResult, SmartTransactionError>, Vec<String>) {
+        let client = RecordingRpcSender {
+            unconfirmed_sends: 1,
+            ..RecordingRpcSender::default()
         }
+        .into_client();
+        let payer = Arc::new(Keypair::new());
+        let observer = Arc::new(RecordingObserver::default());
+
+        let tx_config = SmartTxConfig {
+            send_retries,
+            transaction_timeout: Some(Duration::from_millis(10)),
+            observer: Some(observer.clone()),
+            ..SmartTxConfig::default()
+        };
+        let instructions = vec![transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000)];
+
+        let result = send_smart_transaction(&client, vec![payer.clone()], &payer.pubkey(), instructions, vec![], tx_config).await;
+        let events = observer
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|event| event.starts_with("sent") || event.starts_with("resubmit") || event.starts_with("confirmed"))
+            .cloned()
+            .collect();
+        (result, events)
+    }
+
+    #[tokio::test]
+    async fn test_send_retries_after_confirmation_timeout() {
+        // The first transaction is not confirmed before its blockhash expires, so it's rebuilt and sent again.
+        let (result, events) = send_with_expired_blockhash(1).await;
+        let signature = result.unwrap().signature;
+        assert_eq!(events.len(), 4);
+        assert!(events[0].starts_with("sent: ") && events[0] != format!("sent: {}", signature));
+        assert_eq!(
+            events[1..],
+            [
+                "resubmit: 1".to_string(),
+                format!("sent: {}", signature),
+                format!("confirmed: {}", signature)
+            ]
+        );
+
+        // Without retries, the expiration is returned.
+        let (result, events) = send_with_expired_blockhash(0).await;
+        assert!(matches!(result, Err(SmartTransactionError::BlockhashExpired { .. })));
+        assert_eq!(events.len(), 1);
+
+        // The transaction may still land while its blockhash is valid, so sending it again could execute it twice.
+        let timeout = SmartTransactionError::ConfirmationTimeout {
+            signature: Signature::default(),
+            timeout: Duration::from_secs(60),
+        };
+        assert!(!is_recoverable_send_error(&timeout));
     }
 
-    Err(ClientError {
-        request: None,
-        kind: ClientErrorKind::Custom(format!("Unable to confirm transaction {} in {} seconds", tx_sig, timeout.as_secs())),
-    })
+    #[test]
+    fn test_estimate_transaction_size() {
+        let payer = Keypair::new();
+        let instructions: Vec<Instruction> = (0..5).map(|i| transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000 + i)).collect();
+
+        let message = VersionedMessage::V0(v0::Message::try_compile(&payer.pubkey(), &instructions, &[], Hash::new_unique()).unwrap());
+        let transaction = VersionedTransaction::try_new(message, &[&payer]).unwrap();
+        let size = bincode::serialize(&transaction).unwrap().len();
+        assert_eq!(estimate_transaction_size(&instructions, &[], 1), size);
+
+        // The destination addresses are loaded from the lookup table.
+        let lookup_table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: instructions.iter().map(|ix| ix.accounts[1].pubkey).collect(),
+        };
+        let size_with_lookup_table = estimate_transaction_size(&instructions, &[lookup_table], 1);
+        assert!(size_with_lookup_table < size);
+        assert!(size_with_lookup_table <= MAX_TRANSACTION_SIZE);
+
+        let instructions: Vec<Instruction> = (0..50).map(|i| transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000 + i)).collect();
+        assert!(estimate_transaction_size(&instructions, &[], 1) > MAX_TRANSACTION_SIZE);
+    }
+
+    #[test]
+    fn test_jito_tip_account() {
+        let payer = Pubkey::new_unique();
+        let tip_account = Pubkey::new_unique();
+        let jito_config = SmartTxJitoConfig {
+            uuid: String::new(),
+            tips: 5000,
+            region: None,
+            tip_account: Some(tip_account),
+        };
+        assert_eq!(jito_config.tip_instruction(&payer), transfer(&payer, &tip_account, 5000));
+
+        let jito_config = SmartTxJitoConfig {
+            tip_account: None,
+            ..jito_config
+        };
+        let instruction = jito_config.tip_instruction(&payer);
+        assert!(JITO_TIP_ACCOUNTS.contains(&instruction.accounts[1].pubkey.to_string().as_str()));
+    }
+
+    #[test]
+    fn test_clamp_priority_fee() {
+        // The estimate is not bounded by default.
+        assert_eq!(SmartTxPriorityFeeConfig::default().clamp_priority_fee(5000, 200_000), 5000);
+
+        let fee_config = SmartTxPriorityFeeConfig {
+            fee_min: 100,
+            fee_max: 1000,
+            ..SmartTxPriorityFeeConfig::default()
+        };
+        assert_eq!(fee_config.clamp_priority_fee(10, 200_000), 100);
+        assert_eq!(fee_config.clamp_priority_fee(5000, 200_000), 1000);
+
+        let fee_config = SmartTxPriorityFeeConfig {
+            total_fee_budget_lamports: Some(1000),
+            ..fee_config
+        };
+        assert_eq!(fee_config.clamp_priority_fee(10, 200_000), 10);
+        assert_eq!(fee_config.clamp_priority_fee(10_000, 200_000), 5000);
+    }
 }